@@ -0,0 +1,23 @@
+#include "struct/noise_params.wgsl"
+
+@group(0) @binding(0) var<uniform> params: NoiseParams;
+@group(0) @binding(1) var<uniform> bake: BakeParams;
+@group(0) @binding(2) var<storage, read> permutation: array<vec4<i32>>;
+@group(0) @binding(3) var<storage, read> gradient: array<vec4<f32>>;
+@group(0) @binding(4) var tex: texture_storage_2d<rgba8unorm, write>;
+
+#include "noise/fn_perlin_noise.wgsl"
+#include "noise/fn_noise_basis.wgsl"
+#include "noise/fn_color_ramp.wgsl"
+#include "noise/fn_noise_material.wgsl"
+
+@compute @workgroup_size(16, 16)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let size = textureDimensions(tex);
+    if (gid.x >= size.x || gid.y >= size.y) {
+        return;
+    }
+    let uv = (vec2<f32>(gid.xy) + 0.5) / vec2<f32>(size);
+    let color = baked_color(vec3<f32>(uv, 0.5), bake.tile_axes);
+    textureStore(tex, vec2<i32>(gid.xy), vec4<f32>(color, 1.0));
+}
@@ -1,14 +1,23 @@
-@group(0) @binding(0) var<storage, read> permutation: array<vec4<i32>>;
-@group(0) @binding(1) var<storage, read> gradient: array<vec4<f32>>;
-@group(0) @binding(2) var tex: texture_storage_3d<rgba8unorm, write>;
+#include "struct/noise_params.wgsl"
+
+@group(0) @binding(0) var<uniform> params: NoiseParams;
+@group(0) @binding(1) var<uniform> bake: BakeParams;
+@group(0) @binding(2) var<storage, read> permutation: array<vec4<i32>>;
+@group(0) @binding(3) var<storage, read> gradient: array<vec4<f32>>;
+@group(0) @binding(4) var tex: texture_storage_3d<rgba8unorm, write>;
 
 #include "noise/fn_perlin_noise.wgsl"
+#include "noise/fn_noise_basis.wgsl"
+#include "noise/fn_color_ramp.wgsl"
+#include "noise/fn_noise_material.wgsl"
 
-@compute @workgroup_size(8, 8, 8)
+@compute @workgroup_size(4, 4, 4)
 fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
-    let p = vec3<f32>(gid.xyz) / 8.0 ; 
-    let val = noise(p);
-    
-    textureStore(tex, vec3<i32>(gid.xyz), vec4<f32>(val, val * 0.5 + 0.5, val * 0.25 + 0.5, val * 0.125 + 0.5));
+    let size = textureDimensions(tex);
+    if (any(gid >= size)) {
+        return;
+    }
+    let uvw = (vec3<f32>(gid) + 0.5) / vec3<f32>(size);
+    let color = baked_color(uvw, bake.tile_axes);
+    textureStore(tex, vec3<i32>(gid), vec4<f32>(color, 1.0));
 }
-
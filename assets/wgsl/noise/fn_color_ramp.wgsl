@@ -0,0 +1,22 @@
+// 多色标渐变中 t 处的颜色，t 限制在 [0, 1]，需先声明 NoiseParams 类型的 params
+fn color_ramp(t: f32) -> vec3<f32> {
+    let x = clamp(t, 0.0, 1.0);
+    if (x <= params.stops[0].w) {
+        return params.stops[0].rgb;
+    }
+    for (var i = 1; i < params.stop_count; i++) {
+        let s0 = params.stops[i - 1];
+        let s1 = params.stops[i];
+        if (x <= s1.w) {
+            var w = (x - s0.w) / max(s1.w - s0.w, 0.00001);
+            let mode = (params.stop_modes >> (2u * u32(i - 1))) & 3u;
+            if (mode == 1u) {
+                w = smoothstep(0.0, 1.0, w);
+            } else if (mode == 2u) {
+                w = 0.0;
+            }
+            return mix(s0.rgb, s1.rgb, w);
+        }
+    }
+    return params.stops[params.stop_count - 1].rgb;
+}
@@ -0,0 +1,73 @@
+// 可选的基础噪声，需先 include fn_perlin_noise.wgsl
+// 都使用同一张排列表做哈希，返回值大致在 [-1, 1]
+
+// 格点的哈希值 [0, 255]
+fn hash3(c: vec3<i32>) -> i32 {
+    let xy = perm(c.x & 255, c.y & 255).x;
+    return perm((xy + c.z) & 255, 0).x;
+}
+
+// 格点内的随机位置 [0, 1]
+fn hash3_vec(c: vec3<i32>) -> vec3<f32> {
+    let h = hash3(c);
+    return vec3<f32>(f32(perm(h, 1).x), f32(perm(h, 2).x), f32(perm(h, 3).x)) / 255.0;
+}
+
+// Simplex noise: http://staffwww.itn.liu.se/~stegu/simplexnoise/simplexnoise.pdf
+fn simplex_noise(pos: vec3<f32>) -> f32 {
+    let F3 = 1.0 / 3.0;
+    let G3 = 1.0 / 6.0;
+    // 所在的单形
+    let i = floor(pos + dot(pos, vec3<f32>(F3)));
+    let x0 = pos - i + dot(i, vec3<f32>(G3));
+    let g = step(x0.yzx, x0.xyz);
+    let l = 1.0 - g;
+    let i1 = min(g, l.zxy);
+    let i2 = max(g, l.zxy);
+
+    var corners = array<vec3<f32>, 4>(vec3<f32>(0.0), i1, i2, vec3<f32>(1.0));
+    let base = vec3<i32>(i);
+    var n = 0.0;
+    for (var c = 0; c < 4; c++) {
+        let x = x0 - corners[c] + f32(c) * G3;
+        let t = 0.6 - dot(x, x);
+        if (t > 0.0) {
+            let t2 = t * t;
+            n += t2 * t2 * grad(hash3(base + vec3<i32>(corners[c])), x);
+        }
+    }
+    return 32.0 * n;
+}
+
+fn value_noise(pos: vec3<f32>) -> f32 {
+    let i = vec3<i32>(floor(pos));
+    let f = fade(fract(pos));
+    var corner_values: array<f32, 8>;
+    for (var c = 0; c < 8; c++) {
+        let offset = vec3<i32>(c, c >> 1u, c >> 2u) & vec3<i32>(1);
+        corner_values[c] = f32(hash3(i + offset)) / 255.0;
+    }
+    let v = lerp(
+        lerp(lerp(corner_values[0], corner_values[1], f.x), lerp(corner_values[2], corner_values[3], f.x), f.y),
+        lerp(lerp(corner_values[4], corner_values[5], f.x), lerp(corner_values[6], corner_values[7], f.x), f.y),
+        f.z
+    );
+    return v * 2.0 - 1.0;
+}
+
+// Worley / cellular noise，返回到最近及次近特征点的距离 (F1, F2)
+fn worley_noise(pos: vec3<f32>) -> vec2<f32> {
+    let i = vec3<i32>(floor(pos));
+    var d = vec2<f32>(8.0);
+    // 嵌套的三层循环展开后编译非常慢
+    for (var k = 0; k < 27; k++) {
+        let cell = i + vec3<i32>(k % 3, (k / 3) % 3, k / 9) - 1;
+        let dist = length(vec3<f32>(cell) + hash3_vec(cell) - pos);
+        if (dist < d.x) {
+            d = vec2<f32>(dist, d.x);
+        } else if (dist < d.y) {
+            d.y = dist;
+        }
+    }
+    return d;
+}
@@ -0,0 +1,145 @@
+// 创建 shader 时插入生成的函数：
+// 所选基础噪声的 basis_noise(pos) -> f32，如 `return simplex_noise(pos);`
+// 及节点图材质的 graph_color(pos, time) -> vec3<f32>
+#insert_code_snippet
+
+// 材质动画的时间，由 material_color 设置
+var<private> noise_time: f32 = 0.0;
+
+// 时间作为第四维：每一层噪声以不同的方向及速度平移，叠加后的图案随时间演变
+fn octave_flow(i: i32) -> vec3<f32> {
+    let k = f32(i + 1);
+    return vec3<f32>(sin(k * 1.7), cos(k * 2.3), sin(k * 0.9 + 1.0)) * (0.15 * k * noise_time);
+}
+
+fn basis_turbulence(pos: vec3<f32>, octaves: i32, lacunarity: f32, gain: f32) -> f32 {
+    var sum: f32 = 0.0;
+    var scale: f32 = 1.0;
+    var totalgain: f32 = 1.0;
+    for (var i = 0; i < octaves; i = i + 1) {
+        sum += totalgain * basis_noise(pos * scale + octave_flow(i));
+        scale *= lacunarity;
+        totalgain *= gain;
+    }
+    return abs(sum);
+}
+
+// Fractal brownian motion
+fn fbm(pos: vec3<f32>) -> f32 {
+	var freq = 1.0;
+    var amp = 0.5;
+	var sum = 0.0;	
+	for (var i: i32 = 0; i < params.octave; i++) {
+		sum += basis_noise(pos * freq + octave_flow(i)) * amp;
+		freq *= params.lacunarity;
+		amp *= params.gain;
+	}
+	return sum;
+}
+
+const m3 = mat3x3<f32>(vec3<f32>(0.10,  0.80,  0.60),
+                      vec3<f32>(-0.80,  0.36, -0.48),
+                      vec3<f32>(-0.60, -0.48,  0.64) );
+
+fn fbm2(pos: vec3<f32>) -> f32 {
+	var x = pos;
+    var amp = 0.5;
+	var sum = 0.0;	
+	for (var i: i32 = 0; i < params.octave; i++) {
+		sum += basis_noise(x) * amp;
+        // Rotate to reduce axial bias
+		x = params.lacunarity * m3 * x;
+		amp *= params.gain;
+	}
+	return sum;
+}
+
+fn bias(t: f32, b: f32) -> f32 {
+	return pow(t, log(b)/log(0.5));
+}
+
+// 两种颜色之间插值，有多色标渐变时 n 在 [-1, 1] 内映射到整个渐变
+fn material_ramp(n: f32) -> vec3<f32> {
+    if (params.stop_count == 0) {
+        return lerp3(params.bg_color.rgb, params.front_color.rgb, n);
+    }
+    return color_ramp(n * 0.5 + 0.5);
+}
+
+// 迭代的域扭曲：p = pos + strength * fbm(p)
+fn domain_warp(pos: vec3<f32>) -> vec3<f32> {
+    var p = pos;
+    for (var i = 0; i < params.warp_iterations; i++) {
+        p = pos + params.warp_strength * vec3<f32>(
+            fbm(p),
+            fbm(p + vec3<f32>(5.2, 1.3, 2.8)),
+            fbm(p + vec3<f32>(1.7, 9.2, 4.1))
+        );
+    }
+    return p;
+}
+
+// 噪声材质的颜色，time 为动画的时间，烘焙纹理时为 0
+fn material_color(pos: vec3<f32>, time: f32) -> vec3<f32> {
+    noise_time = time;
+    if (params.ty == 4) {
+        // 节点图材质
+        return clamp(graph_color(pos, time), vec3<f32>(0.0), vec3<f32>(1.0));
+    }
+    let mc_pos = domain_warp(pos);
+    var n: f32;
+    var simu_color: vec3<f32>;
+    if (params.ty == 0) {
+        // Marble
+        n = cos(mc_pos.z * 0.1 + 6.0 * basis_turbulence(mc_pos, params.octave, params.lacunarity, params.gain));
+        simu_color = material_ramp(n);
+    } else if (params.ty == 1) {
+        // Wood
+        let g = basis_noise(mc_pos) * 30.0;
+        let grain = fract(g);
+        n = cos(mc_pos.z * 0.1 + 6.0 * basis_turbulence(mc_pos, params.octave, params.lacunarity, grain));
+        simu_color = material_ramp(n);
+    } else if (params.ty == 2) {
+        // Grim world
+        let q = vec3<f32>(n, fbm(mc_pos + vec3<f32>(5.2, 1.3, 0.4)), fbm(mc_pos + vec3<f32>(9.2, 2.3, 13.6)));
+        let r = vec3<f32>(fbm(mc_pos + 4.0*q + vec3<f32>(1.7,9.2, 12.7)),
+                   fbm(mc_pos + 4.0*q + vec3<f32>(8.3,2.8, 0.3)), fbm(mc_pos + 4.0*q));
+        let f = fbm(mc_pos + 4.0 * r);
+        simu_color =  vec3<f32>(0.176, 0.204, 0.216);
+        simu_color =  mix(simu_color, params.bg_color.rgb, f);
+        simu_color =  mix(simu_color, params.front_color.rgb, r*0.9);
+    } else {
+        var q = vec3<f32>(fbm2(mc_pos), fbm2(mc_pos + 1.0), fbm2(mc_pos + 2.0));
+        let r = vec3<f32>(fbm2(mc_pos + q + vec3<f32>(1.7,9.2, 3.3)+ 0.15 * time), 
+                            fbm2(mc_pos + q + vec3<f32>(8.3,2.8, 1.1)+ 0.126 * time), 
+                            fbm2(mc_pos + q + vec3<f32>(1.3,5.1, 9.7)+ 0.09 * time));
+        let f = fbm2(mc_pos + r);
+
+        simu_color = mix(vec3<f32>(0.101961,0.619608,0.666667),
+                    vec3<f32>(0.666667,0.666667,0.498039), min(f*3.2, 1.0));
+        simu_color = mix(simu_color,
+                    params.bg_color.rgb, min(length(q), 1.0));
+        simu_color = mix(simu_color, 
+                    params.front_color.rgb, min(length(r.x), 1.0));
+    }
+    return simu_color;
+}
+
+// 纹理坐标 [0, 1] 对应的材质颜色，与球面上的材质取相同的坐标范围
+// 可平铺的方向上与偏移一个周期的颜色按距离混合，使两端的颜色一致
+fn baked_color(uvw: vec3<f32>, tile_axes: vec3<f32>) -> vec3<f32> {
+    let mc_pos = (uvw * 2.0 - 1.0 + 3.5) * params.noise_scale;
+    let period = 2.0 * params.noise_scale;
+    // 不平铺的方向权重恒为 1
+    let t = uvw * tile_axes;
+    var color = vec3<f32>(0.0);
+    for (var i = 0u; i < 8u; i++) {
+        let corner = vec3<f32>(vec3<u32>(i, i >> 1u, i >> 2u) & vec3<u32>(1u));
+        if (any(corner > tile_axes)) {
+            continue;
+        }
+        let w = mix(1.0 - t, t, corner);
+        color += w.x * w.y * w.z * material_color(mc_pos - corner * period, 0.0);
+    }
+    return clamp(color, vec3<f32>(0.0), vec3<f32>(1.0));
+}
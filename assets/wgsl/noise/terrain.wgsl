@@ -0,0 +1,138 @@
+#include "struct/mvp_mat_uniform.wgsl"
+#include "struct/noise_params.wgsl"
+
+struct TerrainParams {
+    // 最高处相对于海平面的位移
+    height_scale: f32,
+    // [0, 1] 内的海平面高度，低于海平面的部分是平的水面
+    sea_level: f32,
+    // 0 平面地形 | 1 星球
+    planet: u32,
+    _padding: u32,
+};
+
+@group(0) @binding(0) var<uniform> mvp_mat: MVPMatUniform;
+@group(0) @binding(1) var<uniform> params: NoiseParams;
+@group(0) @binding(2) var<uniform> terrain: TerrainParams;
+@group(0) @binding(3) var<storage, read> permutation: array<vec4<i32>>;
+@group(0) @binding(4) var<storage, read> gradient: array<vec4<f32>>;
+
+#include "noise/fn_perlin_noise.wgsl"
+#include "noise/fn_noise_basis.wgsl"
+#include "func/color_space_convert.wgsl"
+#include "noise/fn_color_ramp.wgsl"
+#include "noise/fn_noise_material.wgsl"
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) world_pos: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) height: f32,
+};
+
+// [0, 1] 内的高度，节点图材质取颜色的亮度
+fn surface_height(p: vec3<f32>) -> f32 {
+    let mc_pos = (p + vec3<f32>(3.5)) * params.noise_scale;
+    if (params.ty == 4) {
+        let c = clamp(graph_color(mc_pos, noise_time), vec3<f32>(0.0), vec3<f32>(1.0));
+        return dot(c, vec3<f32>(0.2126, 0.7152, 0.0722));
+    }
+    return clamp(fbm(domain_warp(mc_pos)) + 0.5, 0.0, 1.0);
+}
+
+// 位移后的位置：平面沿 z 轴位移，星球上的点先投影到单位球面再沿法线位移
+fn displaced(p: vec3<f32>) -> vec4<f32> {
+    var base = p;
+    var n = vec3<f32>(0.0, 0.0, 1.0);
+    if (terrain.planet == 1u) {
+        n = normalize(p);
+        base = n;
+    }
+    let h = surface_height(base);
+    let offset = (max(h, terrain.sea_level) - terrain.sea_level) * terrain.height_scale;
+    return vec4<f32>(base + n * offset, h);
+}
+
+@vertex
+fn vs_main(@location(0) pos: vec3<f32>) -> VertexOutput {
+    noise_time = mvp_mat.u_time;
+
+    var n0 = vec3<f32>(0.0, 0.0, 1.0);
+    var t1 = vec3<f32>(1.0, 0.0, 0.0);
+    if (terrain.planet == 1u) {
+        n0 = normalize(pos);
+        // 两极处改用 x 轴计算切线
+        var up = vec3<f32>(0.0, 1.0, 0.0);
+        if (abs(n0.y) > 0.999) {
+            up = vec3<f32>(1.0, 0.0, 0.0);
+        }
+        t1 = normalize(cross(up, n0));
+    }
+    let t2 = cross(n0, t1);
+
+    // 用相邻两点的有限差分计算法线
+    let eps = 0.01;
+    let p0 = displaced(pos);
+    let p1 = displaced(pos + t1 * eps).xyz;
+    let p2 = displaced(pos + t2 * eps).xyz;
+    var normal = normalize(cross(p1 - p0.xyz, p2 - p0.xyz));
+    if (dot(normal, n0) < 0.0) {
+        normal = -normal;
+    }
+
+    var out: VertexOutput;
+    out.position = mvp_mat.mvp * vec4<f32>(p0.xyz, 1.0);
+    out.world_pos = (mvp_mat.mv * vec4<f32>(p0.xyz, 1.0)).xyz;
+    out.normal = (mvp_mat.normal * vec4<f32>(normal, 0.0)).xyz;
+    out.height = p0.w;
+    return out;
+}
+
+// 按高度着色：有多色标渐变时使用渐变，否则为水面、沙滩、草地、岩石与积雪
+fn height_color(h: f32) -> vec3<f32> {
+    if (params.stop_count > 0) {
+        return color_ramp(h);
+    }
+    let sea = terrain.sea_level;
+    if (h < sea) {
+        let depth = (sea - h) / max(sea, 0.0001);
+        return mix(vec3<f32>(0.16, 0.45, 0.62), vec3<f32>(0.02, 0.1, 0.3), depth);
+    }
+    let t = (h - sea) / max(1.0 - sea, 0.0001);
+    var color = vec3<f32>(0.86, 0.8, 0.56);
+    color = mix(color, vec3<f32>(0.3, 0.55, 0.22), smoothstep(0.02, 0.08, t));
+    color = mix(color, vec3<f32>(0.45, 0.4, 0.35), smoothstep(0.35, 0.55, t));
+    color = mix(color, vec3<f32>(0.95, 0.95, 0.97), smoothstep(0.7, 0.8, t));
+    return color;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let simu_color = height_color(in.height);
+
+    // Light
+    let light_color = vec3<f32>(1.0);
+    let light_pos = vec3<f32>(2.0, 3.5, 4.0);
+    let view_pos = vec3<f32>(0.0, 0., 3.0);
+    let ambient_strength = 0.4;
+    let ambient_color = light_color * ambient_strength;
+
+    let light_dir = normalize(light_pos - in.world_pos);
+    let view_dir = normalize(view_pos - in.world_pos);
+    let half_dir = normalize(view_dir + light_dir);
+
+    let new_normal = normalize(in.normal);
+    let diffuse_strength = max(dot(new_normal, light_dir), 0.0);
+    let diffuse_color = light_color * diffuse_strength;
+
+    // 只有水面有高光
+    var specular_strength = 0.0;
+    if (in.height < terrain.sea_level) {
+        specular_strength = pow(max(dot(new_normal, half_dir), 0.0), 32.0) * 0.6;
+    }
+    let specular_color = light_color * specular_strength;
+
+    let res_color = (ambient_color + diffuse_color + specular_color) * simu_color;
+
+    return vec4<f32>(res_color, 1.);
+}
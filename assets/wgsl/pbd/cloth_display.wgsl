@@ -1,25 +1,25 @@
 #include "pbd/struct/particle.wgsl"
-// #include "pbd/struct/collision.wgsl"
 #include "pbd/struct/cloth_uniform.wgsl"
+#include "struct/mvp_mat_uniform.wgsl"
 
-struct MVPMatUniform {
-    mv: mat4x4<f32>,
-    proj: mat4x4<f32>,
-    mvp: mat4x4<f32>,
-    normal: mat4x4<f32>,
+struct ShadingUniform {
+    // 视图空间中指向光源的方向
+    light_dir: vec4<f32>,
+    front_color: vec4<f32>,
+    back_color: vec4<f32>,
 };
 
 @group(0) @binding(0) var<uniform> mvp_mat: MVPMatUniform;
 @group(0) @binding(1) var<uniform> cloth: ClothUniform;
-@group(0) @binding(2) var<storage, read> particles: array<Particle>;
-// @group(0) @binding(3) var<storage, read_write> collisions: array<CollisionObj>;
+@group(0) @binding(2) var<uniform> shading: ShadingUniform;
+@group(0) @binding(3) var<storage, read> particles: array<Particle>;
+@group(0) @binding(4) var<storage, read> normals: array<vec4<f32>>;
 
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) uv: vec2<f32>,
     @location(1) normal: vec3<f32>,
     @location(2) ec_pos: vec3<f32>,
-    @location(3) collision_area: f32,
 };
 
 @vertex
@@ -27,53 +27,36 @@ fn vs_main(
     @location(0) particle_index: vec3<u32>,
 ) -> VertexOutput {
     let field_index = particle_index.x + particle_index.y * u32(cloth.num_x);
-    // 1，找出对应编号的粒子，
-    // 2，使用粒子的位置来计算顶点位置
     let particle = particles[field_index];
-
-    let particle1 = particles[particle.connect[0] ];
-    let particle2 = particles[particle.connect[1] ];
-    let particle3 = particles[particle.connect[2] ];
-    let particle4 = particles[particle.connect[3] ];
-
     let mv_pos = mvp_mat.mv * vec4<f32>(particle.pos.xyz, 1.0);
 
     var result: VertexOutput;
-    // normal = normalize(cross(particle1.pos.xyz - particle.pos.xyz, particle2.pos.xyz - particle.pos.xyz) +
-    //                    cross(particle3.pos.xyz - particle.pos.xyz, particle4.pos.xyz - particle.pos.xyz));
-    result.normal = (cross(particle2.pos.xyz - particle.pos.xyz, particle1.pos.xyz - particle.pos.xyz) +
-                        cross(particle4.pos.xyz - particle.pos.xyz, particle3.pos.xyz - particle.pos.xyz)) / 2.0;
     result.position = mvp_mat.proj * mv_pos;
     result.ec_pos = mv_pos.xyz;
     result.uv = particle.uv_mass.xy;
-    result.collision_area = 0.0;
-    // let collesion = collisions.data[field_index];
-    // if (collesion.count > 0) {
-    //     result.collision_area = 1.0;
-    // } else {
-    //     result.collision_area = 0.0;
-    // }
-   
+    result.normal = (mvp_mat.normal * vec4<f32>(normals[field_index].xyz, 0.0)).xyz;
     return result;
 }
 
-@group(0) @binding(3) var tex: texture_2d<f32>;
-@group(0) @binding(4) var tex_sampler: sampler;
-
-const light_color = vec3<f32>(1.0, 1.0, 1.0);
-const light_pos = vec3<f32>(-0.0, -0.0, 0.6);
-const view_pos = vec3<f32>(0.0, 0.0, 1.0);
+@group(0) @binding(5) var tex: texture_2d<f32>;
+@group(0) @binding(6) var tex_sampler: sampler;
 
 @fragment
-fn fs_main(vertex: VertexOutput) -> @location(0) vec4<f32> {
+fn fs_main(vertex: VertexOutput, @builtin(front_facing) is_front: bool) -> @location(0) vec4<f32> {
     let color: vec4<f32> = textureSample(tex, tex_sampler, vertex.uv);
-    // let color = vec4<f32>(0.2, 0.8, 0.3, 1.0);
     var norm = normalize(vertex.normal);
-    // 利用 faceforward 函数的方法，判断面相对于光线的朝向，如果背面朝向光源，则要反转法线
-    norm = faceForward(norm, view_pos, norm);
-
-    let light_dir = normalize(light_pos - vertex.ec_pos);
-    // 0.5 ambient
-    let diffuse = clamp(abs(dot(norm, light_dir)), 0.5, 1.0) * color.rgb;
-    return vec4<f32>(diffuse, color.a);
-}
\ No newline at end of file
+    var base_color = color.rgb * shading.front_color.rgb;
+    // 背面使用反向的法线及背面颜色
+    if (!is_front) {
+        norm = -norm;
+        base_color = color.rgb * shading.back_color.rgb;
+    }
+
+    // Blinn-Phong
+    let light_dir = shading.light_dir.xyz;
+    let view_dir = normalize(-vertex.ec_pos);
+    let half_dir = normalize(light_dir + view_dir);
+    let diffuse = max(dot(norm, light_dir), 0.0);
+    let specular = pow(max(dot(norm, half_dir), 0.0), 32.0) * 0.2;
+    return vec4<f32>(base_color * (0.3 + 0.7 * diffuse) + vec3<f32>(specular), color.a);
+}
@@ -2,41 +2,28 @@
 #include "pbd/struct/cloth_uniform.wgsl"
 
 @group(0) @binding(0) var<uniform> cloth: ClothUniform;
+// 固定粒子在本帧的位移，由 CPU 端计算
 @group(0) @binding(1) var<storage, read_write> velocity: vec4<f32>;
 @group(0) @binding(2) var<storage, read_write> particles: array<Particle>;
 
-fn is_movable_particle(particle: Particle) -> bool {
-  if (particle.uv_mass.z < 0.001) {
-    return false;
-  }
-  return true;
+// 只有网格布料顶行的固定粒子随摆动移动，其它固定方式及鼠标拖动的粒子保持不动
+fn is_swing_particle(particle: Particle) -> bool {
+  return particle.uv_mass.z < 0.001 && particle.uv_mass.w > 0.5;
 }
 
-const offset = -0.015;
-
 @compute @workgroup_size(64, 1)
-fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {  
-    let index = i32(gid.x);
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    if (index >= arrayLength(&particles)) {
+      return;
+    }
     var particle = particles[index];
 
-    // if (!is_movable_particle(particle)) {
-    if (index < cloth.num_x) {
-      if (velocity.x == 0.0) {
-        velocity = vec4<f32>(-1.0, 0.0, 0.07, 0.0);
-      }
-
-      particle.pos.x += velocity.x * offset;
-      particle.pos.z += velocity.z * offset;
+    if (is_swing_particle(particle)) {
+      particle.pos.x += velocity.x;
+      particle.pos.z += velocity.z;
 
       particle.old_pos = particle.pos;
       particles[index] = particle;
-
-      if (index == cloth.num_x - 1) {
-        if (particle.pos.x < - 0.1 ) {
-          velocity = vec4<f32>(-1.0, 0.0, 0.07, 0.0);
-        } else if (particle.pos.x > 2.17) {
-          velocity *= -1.0;
-        }
-      }
     }
-}
\ No newline at end of file
+}
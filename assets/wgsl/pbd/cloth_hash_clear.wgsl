@@ -0,0 +1,13 @@
+#include "pbd/struct/bin.wgsl"
+
+@group(0) @binding(0) var<uniform> bin: BinUniform;
+@group(0) @binding(1) var<storage, read_write> bin_counts: array<u32>;
+
+@compute @workgroup_size(64, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    if (index >= u32(bin.bin_num.x)) {
+      return;
+    }
+    bin_counts[index] = 0u;
+}
@@ -0,0 +1,22 @@
+#include "pbd/struct/particle.wgsl"
+#include "pbd/struct/bin.wgsl"
+
+@group(0) @binding(0) var<uniform> bin: BinUniform;
+@group(0) @binding(1) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(2) var<storage, read_write> bin_counts: array<atomic<u32>>;
+@group(0) @binding(3) var<storage, read_write> bin_entries: array<i32>;
+
+// 将粒子放入其所在网格单元对应的哈希容器
+@compute @workgroup_size(64, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    if (index >= arrayLength(&particles)) {
+      return;
+    }
+    let key = bin_hash(bin_cell(particles[index].pos.xyz, bin), bin);
+    let slot = atomicAdd(&bin_counts[key], 1u);
+    // 容器已满时丢弃，漏掉的碰撞会在之后的帧中处理
+    if (slot < u32(bin.max_bin_count)) {
+      bin_entries[key * u32(bin.max_bin_count) + slot] = i32(index);
+    }
+}
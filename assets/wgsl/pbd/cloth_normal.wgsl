@@ -0,0 +1,31 @@
+#include "pbd/struct/particle.wgsl"
+
+@group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(1) var<storage, read_write> indices: array<u32>;
+@group(0) @binding(2) var<storage, read> triangle_offsets: array<u32>;
+@group(0) @binding(3) var<storage, read> triangle_list: array<u32>;
+@group(0) @binding(4) var<storage, read_write> normals: array<vec4<f32>>;
+
+// 以三角形面积为权重累加相邻三角形的法线
+@compute @workgroup_size(64, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    if (index >= arrayLength(&normals)) {
+      return;
+    }
+    var normal = vec3<f32>(0.0);
+    for (var i = triangle_offsets[index]; i < triangle_offsets[index + 1u]; i++) {
+      let t = triangle_list[i] * 3u;
+      let p0 = particles[indices[t]].pos.xyz;
+      let p1 = particles[indices[t + 1u]].pos.xyz;
+      let p2 = particles[indices[t + 2u]].pos.xyz;
+      normal += cross(p1 - p0, p2 - p0);
+    }
+    let len = length(normal);
+    // 不属于任何三角形的粒子
+    if (len < 1.0e-12) {
+      normals[index] = vec4<f32>(0.0, 0.0, 1.0, 0.0);
+    } else {
+      normals[index] = vec4<f32>(normal / len, 0.0);
+    }
+}
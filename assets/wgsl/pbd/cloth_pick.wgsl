@@ -0,0 +1,26 @@
+#include "pbd/struct/particle.wgsl"
+#include "pbd/struct/grab.wgsl"
+
+@group(0) @binding(0) var<uniform> grab: GrabUniform;
+@group(0) @binding(1) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(2) var<storage, read_write> indices: array<u32>;
+@group(0) @binding(3) var<storage, read_write> state: GrabState;
+
+// 找出与鼠标射线最近的交点
+@compute @workgroup_size(64, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let triangle = gid.x;
+    if (triangle * 3u >= arrayLength(&indices)) {
+      return;
+    }
+    let t = ray_triangle(
+      grab.ray_origin.xyz,
+      grab.ray_dir.xyz,
+      particles[indices[triangle * 3u]].pos.xyz,
+      particles[indices[triangle * 3u + 1u]].pos.xyz,
+      particles[indices[triangle * 3u + 2u]].pos.xyz,
+    );
+    if (t > 0.0) {
+      atomicMin(&state.t_bits, bitcast<u32>(t));
+    }
+}
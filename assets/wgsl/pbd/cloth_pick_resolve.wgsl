@@ -0,0 +1,38 @@
+#include "pbd/struct/particle.wgsl"
+#include "pbd/struct/grab.wgsl"
+
+@group(0) @binding(0) var<uniform> grab: GrabUniform;
+@group(0) @binding(1) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(2) var<storage, read_write> indices: array<u32>;
+@group(0) @binding(3) var<storage, read_write> state: GrabState;
+
+// 在最近的三角形上选取离交点最近的粒子
+@compute @workgroup_size(64, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let triangle = gid.x;
+    if (triangle * 3u >= arrayLength(&indices)) {
+      return;
+    }
+    let t_bits = atomicLoad(&state.t_bits);
+    let ids = vec3<u32>(indices[triangle * 3u], indices[triangle * 3u + 1u], indices[triangle * 3u + 2u]);
+    let p0 = particles[ids.x].pos.xyz;
+    let p1 = particles[ids.y].pos.xyz;
+    let p2 = particles[ids.z].pos.xyz;
+    let t = ray_triangle(grab.ray_origin.xyz, grab.ray_dir.xyz, p0, p1, p2);
+    if (t <= 0.0 || bitcast<u32>(t) != t_bits) {
+      return;
+    }
+
+    let hit = grab.ray_origin.xyz + grab.ray_dir.xyz * t;
+    var nearest = ids.x;
+    var min_dis = distance(hit, p0);
+    if (distance(hit, p1) < min_dis) {
+      nearest = ids.y;
+      min_dis = distance(hit, p1);
+    }
+    if (distance(hit, p2) < min_dis) {
+      nearest = ids.z;
+    }
+    atomicStore(&state.particle, i32(nearest));
+    state.depth = t;
+}
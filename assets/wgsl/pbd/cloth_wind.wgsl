@@ -0,0 +1,78 @@
+#include "pbd/struct/particle.wgsl"
+#include "pbd/struct/cloth_uniform.wgsl"
+#include "pbd/struct/wind.wgsl"
+
+@group(0) @binding(0) var<uniform> cloth: ClothUniform;
+@group(0) @binding(1) var<uniform> wind: WindUniform;
+@group(0) @binding(2) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(3) var<storage, read> permutation: array<vec4<i32>>;
+@group(0) @binding(4) var<storage, read> gradient: array<vec4<f32>>;
+
+#include "noise/fn_perlin_noise.wgsl"
+
+fn particle_velocity(p: Particle) -> vec3<f32> {
+  return (p.pos.xyz - p.old_pos.xyz) / cloth.dt;
+}
+
+// 带湍流的风速
+fn wind_at(pos: vec3<f32>) -> vec3<f32> {
+  let gust = wind.wind.w;
+  // 噪声只在正数区间采样
+  let q = (pos + 100.0) * wind.noise_offset.w + wind.noise_offset.xyz;
+  let n = vec3<f32>(noise(q), noise(q + vec3<f32>(31.4, 0.0, 0.0)), noise(q + vec3<f32>(0.0, 71.7, 0.0)));
+  let strength = length(wind.wind.xyz);
+  return wind.wind.xyz * (1.0 + 2.0 * gust * n.x) + gust * strength * n;
+}
+
+// 单个三角形受到的阻力与升力
+fn aerodynamic_force(p0: Particle, p1: Particle, p2: Particle) -> vec3<f32> {
+  let c = cross(p1.pos.xyz - p0.pos.xyz, p2.pos.xyz - p0.pos.xyz);
+  let c_len = length(c);
+  if (c_len < 0.0000001) {
+    return vec3<f32>(0.0);
+  }
+  let center = (p0.pos.xyz + p1.pos.xyz + p2.pos.xyz) / 3.0;
+  let v = (particle_velocity(p0) + particle_velocity(p1) + particle_velocity(p2)) / 3.0;
+  let v_rel = v - wind_at(center);
+  let speed = length(v_rel);
+  if (speed < 0.0000001) {
+    return vec3<f32>(0.0);
+  }
+  let v_dir = v_rel / speed;
+  var normal = c / c_len;
+  // 法线朝向相对速度的方向
+  if (dot(normal, v_dir) < 0.0) {
+    normal = -normal;
+  }
+  let area = 0.5 * c_len / wind.rest_area;
+  let cos_theta = dot(normal, v_dir);
+  let pressure = area * speed * speed;
+  // 阻力与相对速度方向相反
+  var force = -wind.drag * pressure * cos_theta * v_dir;
+  // 升力垂直于相对速度，位于法线与相对速度所在的平面内
+  let lift_dir = cross(cross(normal, v_dir), v_dir);
+  let lift_len = length(lift_dir);
+  if (lift_len > 0.0000001) {
+    let sin_theta = sqrt(max(1.0 - cos_theta * cos_theta, 0.0));
+    force += -wind.lift * pressure * cos_theta * sin_theta * lift_dir / lift_len;
+  }
+  return force;
+}
+
+// 以粒子与相邻粒子组成的三角形来计算空气动力，每个三角形的力平均分到三个顶点上；
+// 只写入自身粒子的 accelerate 字段，在预测位置时使用
+@compute @workgroup_size(64, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    if (index >= arrayLength(&particles)) {
+      return;
+    }
+    let particle = particles[index];
+    var force = vec3<f32>(0.0);
+    for (var i = 0; i < 4; i++) {
+      let p1 = particles[particle.connect[i]];
+      let p2 = particles[particle.connect[(i + 1) % 4]];
+      force += aerodynamic_force(particle, p1, p2) / 3.0;
+    }
+    particles[index].accelerate = vec4<f32>(force * particle.uv_mass.z, 0.0);
+}
@@ -0,0 +1,23 @@
+#include "pbd/struct/particle.wgsl"
+#include "pbd/struct/cloth_uniform.wgsl"
+#include "struct/mvp_mat_uniform.wgsl"
+
+@group(0) @binding(0) var<uniform> mvp_mat: MVPMatUniform;
+@group(0) @binding(1) var<uniform> cloth: ClothUniform;
+@group(0) @binding(2) var<storage, read> particles: array<Particle>;
+
+@vertex
+fn vs_main(
+    @location(0) particle_index: vec3<u32>,
+) -> @builtin(position) vec4<f32> {
+    let field_index = particle_index.x + particle_index.y * u32(cloth.num_x);
+    var position = mvp_mat.mvp * vec4<f32>(particles[field_index].pos.xyz, 1.0);
+    // 稍微移向相机，避免与布料表面的深度冲突
+    position.z -= 0.0005 * position.w;
+    return position;
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(0.1, 0.1, 0.12, 1.0);
+}
@@ -0,0 +1,40 @@
+#include "struct/mvp_mat_uniform.wgsl"
+
+struct ModelUniform {
+    model: mat4x4<f32>,
+    color: vec4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> mvp_mat: MVPMatUniform;
+@group(0) @binding(1) var<uniform> model: ModelUniform;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) normal: vec3<f32>,
+    @location(1) ec_pos: vec3<f32>,
+};
+
+@vertex
+fn vs_main(
+    @location(0) pos: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+) -> VertexOutput {
+    let mv_pos = mvp_mat.mv * model.model * vec4<f32>(pos, 1.0);
+    var result: VertexOutput;
+    // 碰撞体只有平移与缩放，法线用 model 矩阵变换后再归一化即可
+    result.normal = (mvp_mat.normal * model.model * vec4<f32>(normal, 0.0)).xyz;
+    result.position = mvp_mat.proj * mv_pos;
+    result.ec_pos = mv_pos.xyz;
+    return result;
+}
+
+const light_pos = vec3<f32>(0.0, 0.0, 0.6);
+
+@fragment
+fn fs_main(vertex: VertexOutput) -> @location(0) vec4<f32> {
+    let norm = normalize(vertex.normal);
+    let light_dir = normalize(light_pos - vertex.ec_pos);
+    let diffuse = clamp(dot(norm, light_dir), 0.3, 1.0) * model.color.rgb;
+    return vec4<f32>(diffuse, model.color.a);
+}
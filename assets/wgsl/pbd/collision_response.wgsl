@@ -0,0 +1,63 @@
+#include "pbd/struct/particle.wgsl"
+#include "pbd/struct/cloth_uniform.wgsl"
+#include "pbd/struct/collider.wgsl"
+
+@group(0) @binding(0) var<uniform> cloth: ClothUniform;
+@group(0) @binding(1) var<uniform> collider: ColliderUniform;
+@group(0) @binding(2) var<storage, read_write> particles: array<Particle>;
+
+// 沿接触面法线方向的投影之后，衰减本次子步内的切向位移来模拟摩擦
+fn apply_friction(pos: vec3<f32>, old_pos: vec3<f32>, normal: vec3<f32>) -> vec3<f32> {
+  let dx = pos - old_pos;
+  let tangent = dx - dot(dx, normal) * normal;
+  return pos - tangent * collider.friction;
+}
+
+// 将粒子推出碰撞体，correction 为碰撞之前附加的位置修正
+fn collide_particle(index: u32, correction: vec3<f32>) {
+    var particle = particles[index];
+    if (particle.uv_mass.z < 0.001) {
+      return;
+    }
+    var pos = particle.pos.xyz + correction;
+    let old_pos = particle.old_pos.xyz;
+
+    if (collider.sphere_enabled != 0) {
+      let radius = collider.sphere.w + collider.thickness;
+      let delta = pos - collider.sphere.xyz;
+      let dis = length(delta);
+      if (dis < radius && dis > 0.0000001) {
+        let normal = delta / dis;
+        pos = apply_friction(collider.sphere.xyz + normal * radius, old_pos, normal);
+      }
+    }
+
+    if (collider.box_enabled != 0) {
+      let local = pos - collider.box_center.xyz;
+      let half_size = collider.box_half_size.xyz + collider.thickness;
+      let d = abs(local) - half_size;
+      if (max(d.x, max(d.y, d.z)) < 0.0) {
+        // 从穿透深度最小的面推出
+        var normal = vec3<f32>(0.0);
+        if (d.x > d.y && d.x > d.z) {
+          normal.x = select(-1.0, 1.0, local.x >= 0.0);
+        } else if (d.y > d.z) {
+          normal.y = select(-1.0, 1.0, local.y >= 0.0);
+        } else {
+          normal.z = select(-1.0, 1.0, local.z >= 0.0);
+        }
+        pos = apply_friction(pos - normal * dot(d, abs(normal)), old_pos, normal);
+      }
+    }
+
+    if (collider.ground_enabled != 0) {
+      let height = collider.ground_height + collider.thickness;
+      if (pos.y < height) {
+        pos.y = height;
+        pos = apply_friction(pos, old_pos, vec3<f32>(0.0, 1.0, 0.0));
+      }
+    }
+
+    particle.pos = vec4<f32>(pos, particle.pos.w);
+    particles[index] = particle;
+}
@@ -0,0 +1,16 @@
+#include "pbd/struct/fluid.wgsl"
+
+@group(0) @binding(0) var<uniform> fluid: FluidUniform;
+@group(0) @binding(1) var<storage, read_write> particles: array<FluidParticle>;
+@group(0) @binding(2) var<storage, read_write> deltas: array<vec4<f32>>;
+
+// 所有粒子的修正量计算完后再统一应用（Jacobi 迭代）
+@compute @workgroup_size(64, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    if (index >= fluid.particle_num) {
+      return;
+    }
+    let pos = clamp_to_bounds(particles[index].pos.xyz + deltas[index].xyz, fluid);
+    particles[index].pos = vec4<f32>(pos, 0.0);
+}
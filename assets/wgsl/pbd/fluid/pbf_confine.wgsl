@@ -0,0 +1,44 @@
+#include "pbd/struct/fluid.wgsl"
+#include "pbd/struct/bin.wgsl"
+
+@group(0) @binding(0) var<uniform> fluid: FluidUniform;
+@group(0) @binding(1) var<uniform> bin: BinUniform;
+@group(0) @binding(2) var<storage, read_write> particles: array<FluidParticle>;
+@group(0) @binding(3) var<storage, read_write> bin_starts: array<u32>;
+@group(0) @binding(4) var<storage, read_write> sorted_indices: array<u32>;
+@group(0) @binding(5) var<storage, read_write> omegas: array<vec4<f32>>;
+@group(0) @binding(6) var<storage, read_write> deltas: array<vec4<f32>>;
+
+// eq. 16：涡度约束 f = ε (N × ω)，N 为涡度大小的梯度方向，把数值耗散掉的涡旋补回来
+@compute @workgroup_size(64, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    if (index >= fluid.particle_num) {
+      return;
+    }
+    let pos = particles[index].pos.xyz;
+    let h = fluid.kernel_radius;
+    let omega = omegas[index];
+    var eta = vec3<f32>(0.0);
+    // 遍历相邻的 27 个网格单元
+    var keys = neighbor_bin_keys(bin_cell(pos, bin), bin);
+    for (var n = 0; n < 27; n += 1) {
+        if (is_visited_bin(&keys, n)) {
+            continue;
+        }
+        let key = keys[n];
+        let end = bin_starts[key + 1u];
+        for (var k = bin_starts[key]; k < end; k += 1u) {
+            let j = sorted_indices[k];
+            let r = pos - particles[j].pos.xyz;
+            eta += (omegas[j].w - omega.w) * spiky_grad(r, h);
+        }
+    }
+    var force = vec3<f32>(0.0);
+    let eta_len = length(eta);
+    if (eta_len > 0.000001) {
+        force = fluid.vorticity * cross(eta / eta_len, omega.xyz);
+    }
+    let vel = particles[index].vel.xyz + force * fluid.dt + deltas[index].xyz;
+    particles[index].vel = vec4<f32>(vel, 0.0);
+}
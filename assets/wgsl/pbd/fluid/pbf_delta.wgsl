@@ -0,0 +1,42 @@
+#include "pbd/struct/fluid.wgsl"
+#include "pbd/struct/bin.wgsl"
+
+@group(0) @binding(0) var<uniform> fluid: FluidUniform;
+@group(0) @binding(1) var<uniform> bin: BinUniform;
+@group(0) @binding(2) var<storage, read_write> particles: array<FluidParticle>;
+@group(0) @binding(3) var<storage, read_write> bin_starts: array<u32>;
+@group(0) @binding(4) var<storage, read_write> sorted_indices: array<u32>;
+@group(0) @binding(5) var<storage, read_write> lambdas: array<f32>;
+@group(0) @binding(6) var<storage, read_write> deltas: array<vec4<f32>>;
+
+// eq. 12, 14：位置修正及防止粒子聚集的人工压强
+@compute @workgroup_size(64, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    if (index >= fluid.particle_num) {
+      return;
+    }
+    let pos = particles[index].pos.xyz;
+    let h = fluid.kernel_radius;
+    let lambda = lambdas[index];
+    var delta = vec3<f32>(0.0);
+    // 遍历相邻的 27 个网格单元
+    var keys = neighbor_bin_keys(bin_cell(pos, bin), bin);
+    for (var n = 0; n < 27; n += 1) {
+        if (is_visited_bin(&keys, n)) {
+            continue;
+        }
+        let key = keys[n];
+        let end = bin_starts[key + 1u];
+        for (var k = bin_starts[key]; k < end; k += 1u) {
+            let j = sorted_indices[k];
+            if (j == index) {
+                continue;
+            }
+            let r = pos - particles[j].pos.xyz;
+            let scorr = -fluid.scorr_k * pow(poly6(r, h) * fluid.scorr_invert_w, 4.0);
+            delta += (lambda + lambdas[j] + scorr) * spiky_grad(r, h);
+        }
+    }
+    deltas[index] = vec4<f32>(delta / fluid.rest_density, 0.0);
+}
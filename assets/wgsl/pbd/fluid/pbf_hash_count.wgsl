@@ -0,0 +1,18 @@
+#include "pbd/struct/fluid.wgsl"
+#include "pbd/struct/bin.wgsl"
+
+@group(0) @binding(0) var<uniform> bin: BinUniform;
+@group(0) @binding(1) var<storage, read_write> particles: array<FluidParticle>;
+@group(0) @binding(2) var<storage, read_write> bin_counts: array<atomic<u32>>;
+@group(0) @binding(3) var<storage, read_write> particle_slots: array<u32>;
+
+// 计数排序第一步：统计每个容器的粒子数，并记录粒子在容器内的序号
+@compute @workgroup_size(64, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    if (index >= arrayLength(&particles)) {
+      return;
+    }
+    let key = bin_hash(bin_cell(particles[index].pos.xyz, bin), bin);
+    particle_slots[index] = atomicAdd(&bin_counts[key], 1u);
+}
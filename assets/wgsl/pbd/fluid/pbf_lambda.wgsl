@@ -0,0 +1,44 @@
+#include "pbd/struct/fluid.wgsl"
+#include "pbd/struct/bin.wgsl"
+
+@group(0) @binding(0) var<uniform> fluid: FluidUniform;
+@group(0) @binding(1) var<uniform> bin: BinUniform;
+@group(0) @binding(2) var<storage, read_write> particles: array<FluidParticle>;
+@group(0) @binding(3) var<storage, read_write> bin_starts: array<u32>;
+@group(0) @binding(4) var<storage, read_write> sorted_indices: array<u32>;
+@group(0) @binding(5) var<storage, read_write> lambdas: array<f32>;
+
+// Macklin & Müller 2013, Position Based Fluids
+// 密度约束 C = ρ / ρ0 - 1，eq. 11：λ = -C / (Σ|∇C|² + ε)
+@compute @workgroup_size(64, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    if (index >= fluid.particle_num) {
+      return;
+    }
+    let pos = particles[index].pos.xyz;
+    let h = fluid.kernel_radius;
+    var density = 0.0;
+    var grad_i = vec3<f32>(0.0);
+    var sum_grad2 = 0.0;
+    // 遍历相邻的 27 个网格单元
+    var keys = neighbor_bin_keys(bin_cell(pos, bin), bin);
+    for (var n = 0; n < 27; n += 1) {
+        if (is_visited_bin(&keys, n)) {
+            continue;
+        }
+        let key = keys[n];
+        let end = bin_starts[key + 1u];
+        for (var k = bin_starts[key]; k < end; k += 1u) {
+            let j = sorted_indices[k];
+            let r = pos - particles[j].pos.xyz;
+            density += poly6(r, h);
+            let grad = spiky_grad(r, h) / fluid.rest_density;
+            grad_i += grad;
+            sum_grad2 += dot(grad, grad);
+        }
+    }
+    // 只约束压缩，低于静止密度的表面粒子不相互吸引
+    let c = max(density / fluid.rest_density - 1.0, 0.0);
+    lambdas[index] = -c / (sum_grad2 + dot(grad_i, grad_i) + fluid.relaxation);
+}
@@ -0,0 +1,18 @@
+#include "pbd/struct/fluid.wgsl"
+
+@group(0) @binding(0) var<uniform> fluid: FluidUniform;
+@group(0) @binding(1) var<storage, read_write> particles: array<FluidParticle>;
+
+@compute @workgroup_size(64, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    if (index >= fluid.particle_num) {
+      return;
+    }
+    var particle = particles[index];
+    particle.vel.y += fluid.gravity * fluid.dt;
+    particle.old_pos = particle.pos;
+    let pos = clamp_to_bounds(particle.pos.xyz + particle.vel.xyz * fluid.dt, fluid);
+    particle.pos = vec4<f32>(pos, 0.0);
+    particles[index] = particle;
+}
@@ -0,0 +1,44 @@
+#include "pbd/struct/bin.wgsl"
+
+@group(0) @binding(0) var<uniform> bin: BinUniform;
+@group(0) @binding(1) var<storage, read_write> bin_counts: array<u32>;
+// 长度为容器数 + 1，最后一个元素为粒子总数
+@group(0) @binding(2) var<storage, read_write> bin_starts: array<u32>;
+
+const THREAD_NUM: u32 = 256u;
+var<workgroup> partial_sums: array<u32, THREAD_NUM>;
+
+// 计数排序第二步：单个工作组计算容器计数的前缀和。
+// 每个线程先累加一段连续的容器，再对各段的和做 Hillis-Steele 扫描
+@compute @workgroup_size(256, 1)
+fn cs_main(@builtin(local_invocation_id) lid: vec3<u32>) {
+    let bin_num = u32(bin.bin_num.x);
+    let chunk = (bin_num + THREAD_NUM - 1u) / THREAD_NUM;
+    let begin = min(lid.x * chunk, bin_num);
+    let end = min(begin + chunk, bin_num);
+    var sum = 0u;
+    for (var i = begin; i < end; i += 1u) {
+        sum += bin_counts[i];
+    }
+    partial_sums[lid.x] = sum;
+    workgroupBarrier();
+
+    for (var offset = 1u; offset < THREAD_NUM; offset *= 2u) {
+        var v = 0u;
+        if (lid.x >= offset) {
+            v = partial_sums[lid.x - offset];
+        }
+        workgroupBarrier();
+        partial_sums[lid.x] += v;
+        workgroupBarrier();
+    }
+
+    var start = partial_sums[lid.x] - sum;
+    for (var i = begin; i < end; i += 1u) {
+        bin_starts[i] = start;
+        start += bin_counts[i];
+    }
+    if (lid.x == THREAD_NUM - 1u) {
+        bin_starts[bin_num] = partial_sums[lid.x];
+    }
+}
@@ -0,0 +1,16 @@
+#include "pbd/struct/fluid.wgsl"
+
+@group(0) @binding(0) var<uniform> fluid: FluidUniform;
+@group(0) @binding(1) var<storage, read_write> particles: array<FluidParticle>;
+
+@compute @workgroup_size(64, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    if (index >= fluid.particle_num) {
+      return;
+    }
+    var particle = particles[index];
+    let vel = (particle.pos.xyz - particle.old_pos.xyz) / fluid.dt * (1.0 - fluid.damping);
+    particle.vel = vec4<f32>(vel, 0.0);
+    particles[index] = particle;
+}
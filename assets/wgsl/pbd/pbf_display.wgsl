@@ -0,0 +1,52 @@
+#include "struct/mvp_mat_uniform.wgsl"
+#include "pbd/struct/fluid.wgsl"
+
+@group(0) @binding(0) var<uniform> mvp_mat: MVPMatUniform;
+@group(0) @binding(1) var<uniform> fluid: FluidUniform;
+@group(0) @binding(2) var<storage, read> particles: array<FluidParticle>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    // 精灵上的坐标，范围为 [-1, 1]
+    @location(0) offset: vec2<f32>,
+    @location(1) ec_pos: vec3<f32>,
+    @location(2) speed: f32,
+};
+
+// 每个粒子一个朝向相机的四边形
+@vertex
+fn vs_main(
+    @location(0) pos: vec3<f32>,
+    @location(1) uv: vec2<f32>,
+    @builtin(instance_index) instance_index: u32,
+) -> VertexOutput {
+    let particle = particles[instance_index];
+    // 粒子半径为粒子间距的一半
+    let radius = fluid.kernel_radius * 0.25;
+    let center = mvp_mat.mv * vec4<f32>(particle.pos.xyz, 1.0);
+    let ec_pos = center.xyz + vec3<f32>(pos.xy * radius, 0.0);
+    var result: VertexOutput;
+    result.position = mvp_mat.proj * vec4<f32>(ec_pos, 1.0);
+    result.offset = pos.xy;
+    result.ec_pos = ec_pos;
+    result.speed = length(particle.vel.xyz);
+    return result;
+}
+
+const light_pos = vec3<f32>(0.0, 0.0, 0.6);
+const deep_color = vec3<f32>(0.08, 0.32, 0.72);
+const foam_color = vec3<f32>(0.75, 0.9, 1.0);
+
+@fragment
+fn fs_main(vertex: VertexOutput) -> @location(0) vec4<f32> {
+    let r2 = dot(vertex.offset, vertex.offset);
+    if (r2 > 1.0) {
+        discard;
+    }
+    // 按球面着色
+    let normal = vec3<f32>(vertex.offset, sqrt(1.0 - r2));
+    let light_dir = normalize(light_pos - vertex.ec_pos);
+    let color = mix(deep_color, foam_color, clamp(vertex.speed * 0.3, 0.0, 1.0));
+    let diffuse = clamp(dot(normal, light_dir), 0.3, 1.0) * color;
+    return vec4<f32>(diffuse, 1.0);
+}
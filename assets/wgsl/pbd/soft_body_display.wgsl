@@ -0,0 +1,44 @@
+#include "struct/mvp_mat_uniform.wgsl"
+#include "pbd/struct/particle.wgsl"
+
+@group(0) @binding(0) var<uniform> mvp_mat: MVPMatUniform;
+@group(0) @binding(1) var<storage, read> particles: array<Particle>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) normal: vec3<f32>,
+    @location(1) ec_pos: vec3<f32>,
+};
+
+@vertex
+fn vs_main(
+    @location(0) particle_index: vec3<u32>,
+) -> VertexOutput {
+    // 表面顶点只用到 x 分量
+    let particle = particles[particle_index.x];
+    let p = particle.pos.xyz;
+    let p1 = particles[particle.connect[0]].pos.xyz;
+    let p2 = particles[particle.connect[1]].pos.xyz;
+    let p3 = particles[particle.connect[2]].pos.xyz;
+    let p4 = particles[particle.connect[3]].pos.xyz;
+
+    let mv_pos = mvp_mat.mv * vec4<f32>(p, 1.0);
+    var result: VertexOutput;
+    // 表面三角形朝外，法线不需要再翻转
+    let normal = cross(p2 - p, p1 - p) + cross(p4 - p, p3 - p);
+    result.normal = (mvp_mat.normal * vec4<f32>(normal, 0.0)).xyz;
+    result.position = mvp_mat.proj * mv_pos;
+    result.ec_pos = mv_pos.xyz;
+    return result;
+}
+
+const light_pos = vec3<f32>(0.0, 0.0, 0.6);
+const body_color = vec3<f32>(0.92, 0.42, 0.36);
+
+@fragment
+fn fs_main(vertex: VertexOutput) -> @location(0) vec4<f32> {
+    let norm = normalize(vertex.normal);
+    let light_dir = normalize(light_pos - vertex.ec_pos);
+    let diffuse = clamp(dot(norm, light_dir), 0.3, 1.0) * body_color;
+    return vec4<f32>(diffuse, 1.0);
+}
@@ -0,0 +1,30 @@
+#include "struct/mvp_mat_uniform.wgsl"
+#include "pbd/struct/particle.wgsl"
+
+@group(0) @binding(0) var<uniform> mvp_mat: MVPMatUniform;
+@group(0) @binding(1) var<storage, read> particles: array<Particle>;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    // 从发根到发梢为 0 ~ 1
+    @location(0) along: f32,
+};
+
+@vertex
+fn vs_main(
+    @location(0) particle_index: vec3<u32>,
+) -> VertexOutput {
+    let particle = particles[particle_index.x];
+    var result: VertexOutput;
+    result.position = mvp_mat.proj * mvp_mat.mv * vec4<f32>(particle.pos.xyz, 1.0);
+    result.along = particle.uv_mass.x;
+    return result;
+}
+
+const root_color = vec3<f32>(0.22, 0.13, 0.07);
+const tip_color = vec3<f32>(0.86, 0.66, 0.38);
+
+@fragment
+fn fs_main(vertex: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(mix(root_color, tip_color, vertex.along), 1.0);
+}
@@ -0,0 +1,44 @@
+struct BinUniform {
+  // x: 哈希表的容器数
+  bin_num: vec4<i32>,
+  // x: 容器数 - 1，容器数为 2 的幂，用于取模
+  bin_max_index: vec4<i32>,
+  // 每个网格单元的尺寸
+  bin_size: vec4<f32>,
+  // 转换到 [0～n] 坐标空间需要的偏移
+  pos_offset: vec4<f32>,
+  // 每个容器最多能存放的粒子数
+  max_bin_count: i32,
+  padding0: f32,
+  padding1: f32,
+  padding2: f32,
+};
+
+fn bin_cell(pos: vec3<f32>, bin: BinUniform) -> vec3<i32> {
+  return vec3<i32>(floor((pos + bin.pos_offset.xyz) / bin.bin_size.xyz));
+}
+
+// 空间哈希：Teschner et al. 2003, Optimized Spatial Hashing for Collision Detection of Deformable Objects
+fn bin_hash(cell: vec3<i32>, bin: BinUniform) -> u32 {
+  let h = (u32(cell.x) * 92837111u) ^ (u32(cell.y) * 689287499u) ^ (u32(cell.z) * 283923481u);
+  return h & u32(bin.bin_max_index.x);
+}
+
+// 相邻的 27 个网格单元所在的容器
+fn neighbor_bin_keys(cell: vec3<i32>, bin: BinUniform) -> array<u32, 27> {
+  var keys: array<u32, 27>;
+  for (var n = 0; n < 27; n += 1) {
+    keys[n] = bin_hash(cell + vec3<i32>(n % 3, (n / 3) % 3, n / 9) - 1, bin);
+  }
+  return keys;
+}
+
+// 不同的网格单元可能哈希到同一个容器，已遍历过的容器需要跳过，否则粒子会被重复计算
+fn is_visited_bin(keys: ptr<function, array<u32, 27>>, n: i32) -> bool {
+  for (var i = 0; i < n; i += 1) {
+    if ((*keys)[i] == (*keys)[n]) {
+      return true;
+    }
+  }
+  return false;
+}
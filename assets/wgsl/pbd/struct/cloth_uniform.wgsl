@@ -9,4 +9,6 @@ struct ClothUniform {
    compliance: f32,
    stiffness: f32,
    dt: f32,
+  // 约束长度超过静止长度的此倍数时断开，0 表示不可撕裂
+   tear_threshold: f32,
 };
\ No newline at end of file
@@ -0,0 +1,19 @@
+struct ColliderUniform {
+  // xyz: 球心, w: 半径
+  sphere: vec4<f32>,
+  box_center: vec4<f32>,
+  // 长方体各轴向上的半边长
+  box_half_size: vec4<f32>,
+  ground_height: f32,
+  // 0 | 1
+  ground_enabled: i32,
+  box_enabled: i32,
+  sphere_enabled: i32,
+  // 接触面上切向位移的衰减比例
+  friction: f32,
+  // 粒子与碰撞体之间保持的距离
+  thickness: f32,
+  self_collision: i32,
+  // 自碰撞时粒子之间保持的距离
+  self_thickness: f32,
+};
@@ -0,0 +1,56 @@
+struct FluidParticle {
+  // 预测位置
+  pos: vec4<f32>,
+  // 子步开始时的位置
+  old_pos: vec4<f32>,
+  vel: vec4<f32>,
+};
+
+struct FluidUniform {
+  // 容器的范围
+  bounds_min: vec4<f32>,
+  bounds_max: vec4<f32>,
+  gravity: f32,
+  dt: f32,
+  // 核函数半径 h
+  kernel_radius: f32,
+  rest_density: f32,
+  // 约束的松弛系数 ε
+  relaxation: f32,
+  // 人工压强 s_corr = -k * (W(r) / W(Δq))^4
+  scorr_k: f32,
+  // 1 / W(Δq)
+  scorr_invert_w: f32,
+  // XSPH 粘性系数
+  viscosity: f32,
+  // 涡度约束强度
+  vorticity: f32,
+  damping: f32,
+  particle_num: u32,
+  padding: f32,
+};
+
+const PI: f32 = 3.14159265;
+
+// Müller et al. 2003, Particle-Based Fluid Simulation for Interactive Applications
+fn poly6(r: vec3<f32>, h: f32) -> f32 {
+  let r2 = dot(r, r);
+  if (r2 >= h * h) {
+    return 0.0;
+  }
+  let d = h * h - r2;
+  return 315.0 / (64.0 * PI * pow(h, 9.0)) * d * d * d;
+}
+
+fn spiky_grad(r: vec3<f32>, h: f32) -> vec3<f32> {
+  let len = length(r);
+  if (len >= h || len < 0.000001) {
+    return vec3<f32>(0.0);
+  }
+  let d = h - len;
+  return -45.0 / (PI * pow(h, 6.0)) * d * d * (r / len);
+}
+
+fn clamp_to_bounds(pos: vec3<f32>, fluid: FluidUniform) -> vec3<f32> {
+  return clamp(pos, fluid.bounds_min.xyz, fluid.bounds_max.xyz);
+}
@@ -0,0 +1,43 @@
+struct GrabUniform {
+  // 模型空间中的鼠标射线
+  ray_origin: vec4<f32>,
+  ray_dir: vec4<f32>,
+  compliance: f32,
+  padding0: f32,
+  padding1: f32,
+  padding2: f32,
+};
+
+struct GrabState {
+  // 最近交点距离的位表示，正数浮点的位表示与其大小顺序一致，可以直接用于 atomicMin
+  t_bits: atomic<u32>,
+  // 被抓取的粒子，-1 表示没有抓到
+  particle: atomic<i32>,
+  // 抓取点到射线起点的距离
+  depth: f32,
+  padding: f32,
+};
+
+// Möller–Trumbore 射线与三角形求交，未相交时返回 -1
+fn ray_triangle(origin: vec3<f32>, dir: vec3<f32>, v0: vec3<f32>, v1: vec3<f32>, v2: vec3<f32>) -> f32 {
+  let e1 = v1 - v0;
+  let e2 = v2 - v0;
+  let p = cross(dir, e2);
+  let det = dot(e1, p);
+  // 布料是双面的，不剔除背面
+  if (abs(det) < 0.0000001) {
+    return -1.0;
+  }
+  let inv_det = 1.0 / det;
+  let s = origin - v0;
+  let u = dot(s, p) * inv_det;
+  if (u < 0.0 || u > 1.0) {
+    return -1.0;
+  }
+  let q = cross(s, e1);
+  let v = dot(dir, q) * inv_det;
+  if (v < 0.0 || u + v > 1.0) {
+    return -1.0;
+  }
+  return dot(e2, q) * inv_det;
+}
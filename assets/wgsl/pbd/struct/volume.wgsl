@@ -0,0 +1,16 @@
+struct SoftBodyUniform {
+    // XPBD 的 α̃ = α / dt²，0 表示体积不可压缩
+    volume_compliance: f32,
+    padding0: f32,
+    padding1: f32,
+    padding2: f32,
+};
+
+struct VolumeConstraint {
+    particles: vec4<i32>,
+    rest_volume: f32,
+    // 当前子步内累积的拉格朗日乘子
+    lambda: f32,
+    padding0: f32,
+    padding1: f32,
+};
@@ -0,0 +1,13 @@
+struct WindUniform {
+  // xyz: 风速矢量, w: 阵风强度
+  wind: vec4<f32>,
+  // xyz: 湍流噪声的采样偏移，随时间沿风向移动, w: 噪声的空间缩放
+  noise_offset: vec4<f32>,
+  // 阻力系数
+  drag: f32,
+  // 升力系数
+  lift: f32,
+  // 静止状态下单个三角形的面积，用于归一化
+  rest_area: f32,
+  padding: f32,
+};
@@ -0,0 +1,34 @@
+#include "pbd/struct/particle.wgsl"
+#include "pbd/struct/cloth_uniform.wgsl"
+#include "pbd/struct/grab.wgsl"
+
+@group(0) @binding(0) var<uniform> cloth: ClothUniform;
+@group(0) @binding(1) var<uniform> grab: GrabUniform;
+@group(0) @binding(2) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(3) var<storage, read_write> state: GrabState;
+
+// 附着约束：将抓取的粒子拉向鼠标射线上的目标点
+@compute @workgroup_size(1, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = atomicLoad(&state.particle);
+    if (index < 0) {
+      return;
+    }
+    var particle = particles[index];
+    let invert_mass = particle.uv_mass.z;
+    // 固定的粒子不能被拖动
+    if (invert_mass < 0.001) {
+      return;
+    }
+    let target_pos = grab.ray_origin.xyz + grab.ray_dir.xyz * state.depth;
+    let delta = particle.pos.xyz - target_pos;
+    let dis = length(delta);
+    if (dis < 0.0000001) {
+      return;
+    }
+    // XPBD: C = |x - target|, α~ = α / dt^2
+    let alpha = grab.compliance / (cloth.dt * cloth.dt);
+    let dlambda = -dis / (invert_mass + alpha);
+    particle.pos = vec4<f32>(particle.pos.xyz + invert_mass * dlambda * delta / dis, particle.pos.w);
+    particles[index] = particle;
+}
@@ -0,0 +1,16 @@
+#include "pbd/collision_response.wgsl"
+
+@group(0) @binding(3) var<storage, read_write> corrections: array<vec4<f32>>;
+
+@compute @workgroup_size(64, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    if (index >= arrayLength(&particles)) {
+      return;
+    }
+    var correction = vec3<f32>(0.0);
+    if (collider.self_collision != 0) {
+      correction = corrections[index].xyz;
+    }
+    collide_particle(index, correction);
+}
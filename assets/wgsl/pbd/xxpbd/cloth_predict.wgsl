@@ -16,7 +16,8 @@ fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
       // 预估新的位置
     //   particle.pos = particle.pos + (particle.pos - particle.old_pos) + (particle.accelerate + force) * cloth.dt * cloth.dt;
     // Xn+1 = Xn + dt * Vn + dt^2 * M^-1 * F(Xn)
-      particle.pos += (particle.pos - particle.old_pos)*(1.0 - cloth.damping) + vec4<f32>(0.0, cloth.gravity, 0.0, 0.0) * particle.uv_mass.z * cloth.dt * cloth.dt ;
+      // accelerate 为风力与空气动力产生的加速度
+      particle.pos += (particle.pos - particle.old_pos)*(1.0 - cloth.damping) + (vec4<f32>(0.0, cloth.gravity, 0.0, 0.0) * particle.uv_mass.z + vec4<f32>(particle.accelerate.xyz, 0.0)) * cloth.dt * cloth.dt ;
       particle.old_pos = temp_pos;
       particles[field_index] = particle;
     }
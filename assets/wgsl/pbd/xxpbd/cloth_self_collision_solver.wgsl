@@ -0,0 +1,68 @@
+#include "pbd/struct/particle.wgsl"
+#include "pbd/struct/collider.wgsl"
+#include "pbd/struct/bin.wgsl"
+
+@group(0) @binding(0) var<uniform> collider: ColliderUniform;
+@group(0) @binding(1) var<uniform> bin: BinUniform;
+@group(0) @binding(2) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(3) var<storage, read_write> bin_counts: array<u32>;
+@group(0) @binding(4) var<storage, read_write> bin_entries: array<i32>;
+// 自碰撞的位置修正，由 cloth_collision_solver 应用到粒子上
+@group(0) @binding(5) var<storage, read_write> corrections: array<vec4<f32>>;
+// 粒子在静止状态下的位置
+@group(0) @binding(6) var<storage, read> rest_positions: array<vec4<f32>>;
+
+// 静止状态下距离小于此值（以粒子间距为单位）的粒子之间不检测自碰撞，
+// 与网格的 uv 无关，封闭网格的正反面之间也能检测
+const REST_NEIGHBOR_DISTANCE: f32 = 2.5;
+
+// 每个线程只写入自身粒子的修正量，避免并行写入冲突
+@compute @workgroup_size(64, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    if (index >= arrayLength(&particles)) {
+      return;
+    }
+    let particle = particles[index];
+    let invert_mass = particle.uv_mass.z;
+    if (invert_mass < 0.001) {
+      corrections[index] = vec4<f32>(0.0);
+      return;
+    }
+    // 空间哈希的网格单元与粒子间距一致
+    let neighbor_distance = REST_NEIGHBOR_DISTANCE * bin.bin_size.x;
+    let rest_pos = rest_positions[index].xyz;
+    var keys = neighbor_bin_keys(bin_cell(particle.pos.xyz, bin), bin);
+    let max_count = u32(bin.max_bin_count);
+    var correction = vec3<f32>(0.0);
+    var count = 0.0;
+    for (var n = 0; n < 27; n++) {
+      if (is_visited_bin(&keys, n)) {
+        continue;
+      }
+      let key = keys[n];
+      let num = min(bin_counts[key], max_count);
+      for (var i = 0u; i < num; i++) {
+        let other_index = bin_entries[key * max_count + i];
+        if (u32(other_index) == index) {
+          continue;
+        }
+        let other = particles[other_index];
+        if (length(rest_pos - rest_positions[other_index].xyz) < neighbor_distance) {
+          continue;
+        }
+        let delta = particle.pos.xyz - other.pos.xyz;
+        let dis = length(delta);
+        if (dis >= collider.self_thickness || dis < 0.0000001) {
+          continue;
+        }
+        let w = invert_mass / (invert_mass + other.uv_mass.z);
+        correction += w * (collider.self_thickness - dis) * delta / dis;
+        count += 1.0;
+      }
+    }
+    if (count > 0.0) {
+      correction /= count;
+    }
+    corrections[index] = vec4<f32>(correction, 0.0);
+}
@@ -0,0 +1,11 @@
+#include "pbd/collision_response.wgsl"
+
+// 没有自碰撞的碰撞求解，用于软体及发丝
+@compute @workgroup_size(64, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    if (index >= arrayLength(&particles)) {
+      return;
+    }
+    collide_particle(index, vec3<f32>(0.0));
+}
@@ -0,0 +1,15 @@
+#include "pbd/distance_constraint.wgsl"
+
+// 不会断开的距离约束，用于软体及发丝
+@compute @workgroup_size(32, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let field_index = group_constraint_index(gid);
+    if (field_index < 0) {
+        return;
+    }
+    let constraint = constraints[field_index];
+    if (is_fixed_constraint(constraint)) {
+        return;
+    }
+    solve_distance_constraint(constraint);
+}
@@ -0,0 +1,13 @@
+#include "pbd/struct/volume.wgsl"
+
+@group(0) @binding(0) var<storage, read_write> constraints: array<VolumeConstraint>;
+
+// 每个子步开始时清零累积的拉格朗日乘子
+@compute @workgroup_size(64, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let index = gid.x;
+    if (index >= arrayLength(&constraints)) {
+        return;
+    }
+    constraints[index].lambda = 0.0;
+}
@@ -0,0 +1,57 @@
+#include "pbd/struct/particle.wgsl"
+#include "pbd/struct/dynamic_uniform.wgsl"
+#include "pbd/struct/volume.wgsl"
+
+@group(0) @binding(0) var<uniform> soft: SoftBodyUniform;
+@group(0) @binding(1) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(2) var<storage, read_write> constraints: array<VolumeConstraint>;
+@group(1) @binding(0) var<uniform> dy_uniform: DynamicUniform;
+
+const EPSILON: f32 = 0.0000001;
+
+// C = V - V0，同一分组内的约束没有共享的粒子
+@compute @workgroup_size(32, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    var field_index = i32(gid.x);
+    if (field_index >= dy_uniform.group_len) {
+        return;
+    }
+    field_index += dy_uniform.offset;
+
+    let constraint = constraints[field_index];
+    var p: array<vec3<f32>, 4>;
+    var w: array<f32, 4>;
+    for (var i = 0; i < 4; i++) {
+        let particle = particles[constraint.particles[i]];
+        p[i] = particle.pos.xyz;
+        w[i] = particle.uv_mass.z;
+    }
+
+    // 体积对各顶点的梯度：对面三角形的面积矢量 / 6
+    var grads: array<vec3<f32>, 4>;
+    grads[1] = cross(p[2] - p[0], p[3] - p[0]) / 6.0;
+    grads[2] = cross(p[3] - p[0], p[1] - p[0]) / 6.0;
+    grads[3] = cross(p[1] - p[0], p[2] - p[0]) / 6.0;
+    grads[0] = -(grads[1] + grads[2] + grads[3]);
+
+    var sum_w = 0.0;
+    for (var i = 0; i < 4; i++) {
+        sum_w += w[i] * dot(grads[i], grads[i]);
+    }
+    if (sum_w < EPSILON * EPSILON) {
+        return;
+    }
+    let volume = dot(grads[3], p[3] - p[0]);
+    // XPBD: Δλ = (-C - α̃λ) / (Σ w|∇C|² + α̃)
+    let alpha = soft.volume_compliance;
+    let dlambda = (-(volume - constraint.rest_volume) - alpha * constraint.lambda) / (sum_w + alpha);
+    constraints[field_index].lambda = constraint.lambda + dlambda;
+
+    for (var i = 0; i < 4; i++) {
+        if (w[i] < 0.001) {
+            continue;
+        }
+        let index = constraint.particles[i];
+        particles[index].pos = vec4<f32>(p[i] + dlambda * w[i] * grads[i], particles[index].pos.w);
+    }
+}
@@ -0,0 +1,98 @@
+#include "pbd/struct/particle.wgsl"
+#include "pbd/struct/dynamic_uniform.wgsl"
+
+struct TwistConstraint {
+    // 相邻两段发丝的三个粒子
+    particles: array<i32, 3>,
+    // 两段发丝各自的幽灵粒子
+    ghosts: array<i32, 2>,
+    rest_twist: f32,
+    padding0: f32,
+    padding1: f32,
+};
+
+@group(0) @binding(0) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(1) var<storage, read_write> constraints: array<TwistConstraint>;
+@group(1) @binding(0) var<uniform> dy_uniform: DynamicUniform;
+
+const EPSILON: f32 = 0.0000001;
+const PI: f32 = 3.14159265;
+
+// 把与 t0 垂直的 u 沿中心线平行移动到与 t1 垂直
+fn parallel_transport(u: vec3<f32>, t0: vec3<f32>, t1: vec3<f32>) -> vec3<f32> {
+    let c = 1.0 + dot(t0, t1);
+    if (c < EPSILON) {
+        return u;
+    }
+    return u - dot(u, t1) / c * (t0 + t1);
+}
+
+// 幽灵粒子相对于所在段中心线的垂直偏移
+fn frame_offset(ghost: vec3<f32>, p: vec3<f32>, t: vec3<f32>) -> vec3<f32> {
+    let d = ghost - p;
+    return d - dot(d, t) * t;
+}
+
+// 幽灵粒子绕所在段的中心线旋转，到两个端点的距离保持不变
+fn rotate_ghost(index: i32, p: vec3<f32>, t: vec3<f32>, angle: f32) {
+    var ghost = particles[index];
+    if (ghost.uv_mass.z < 0.001) {
+        return;
+    }
+    let d = ghost.pos.xyz - p;
+    let axial = dot(d, t) * t;
+    let r = d - axial;
+    let rotated = r * cos(angle) + cross(t, r) * sin(angle);
+    ghost.pos = vec4<f32>(p + axial + rotated, ghost.pos.w);
+    particles[index] = ghost;
+}
+
+// 扭转角为前一段的标架平行移动到后一段上之后，与后一段的标架之间绕中心线的夹角。
+// 同一分组内的约束没有共享的幽灵粒子
+@compute @workgroup_size(32, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    var field_index = i32(gid.x);
+    if (field_index >= dy_uniform.group_len) {
+        return;
+    }
+    field_index += dy_uniform.offset;
+
+    let constraint = constraints[field_index];
+    let p0 = particles[constraint.particles[0]].pos.xyz;
+    let p1 = particles[constraint.particles[1]].pos.xyz;
+    let p2 = particles[constraint.particles[2]].pos.xyz;
+    let e0 = p1 - p0;
+    let e1 = p2 - p1;
+    if (length(e0) < EPSILON || length(e1) < EPSILON) {
+        return;
+    }
+    let t0 = normalize(e0);
+    let t1 = normalize(e1);
+
+    let ghost0 = particles[constraint.ghosts[0]];
+    let ghost1 = particles[constraint.ghosts[1]];
+    let w0 = ghost0.uv_mass.z;
+    let w1 = ghost1.uv_mass.z;
+    if (w0 + w1 < 0.001) {
+        return;
+    }
+    let u0 = frame_offset(ghost0.pos.xyz, p0, t0);
+    let u1 = frame_offset(ghost1.pos.xyz, p1, t1);
+    if (length(u0) < EPSILON || length(u1) < EPSILON) {
+        return;
+    }
+    let transported = parallel_transport(normalize(u0), t0, t1);
+    let n1 = normalize(u1);
+    let twist = atan2(dot(cross(transported, n1), t1), dot(transported, n1));
+    // C = twist - rest_twist，映射到 [-π, π]
+    var c = twist - constraint.rest_twist;
+    if (c > PI) {
+        c -= 2.0 * PI;
+    } else if (c < -PI) {
+        c += 2.0 * PI;
+    }
+
+    // 前一段的标架绕中心线正向旋转会减小扭转角，后一段的标架则相反
+    rotate_ghost(constraint.ghosts[0], p0, t0, c * w0 / (w0 + w1));
+    rotate_ghost(constraint.ghosts[1], p1, t1, -c * w1 / (w0 + w1));
+}
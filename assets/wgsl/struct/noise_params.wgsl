@@ -7,4 +7,20 @@ struct NoiseParams {
   lacunarity: f32,
   gain: f32,
   ty: i32,
-};
\ No newline at end of file
+  // 基础噪声在创建 shader 时选择，这里只占位
+  basis: i32,
+  warp_iterations: i32,
+  warp_strength: f32,
+  // 排列表的种子，只在 CPU 端使用
+  seed: u32,
+  // 多色标渐变，为 0 时使用 bg_color 与 front_color 两种颜色
+  stop_count: i32,
+  // 每个色标到下一个色标的插值方式，各占 2 位：0 线性 | 1 平滑 | 2 常量
+  stop_modes: u32,
+  // rgb 为颜色，w 为位置，按位置升序排列
+  stops: array<vec4<f32>, 8>,
+};
+struct BakeParams {
+  // 值为 1 的方向可平铺
+  tile_axes: vec3<f32>,
+};
@@ -0,0 +1,81 @@
+#include "struct/mvp_mat_uniform.wgsl"
+
+struct VolumeParams {
+    // 采样值与之点乘得到标量，如彩色纹理取亮度，单通道纹理取 r
+    value_mask: vec4<f32>,
+    // 每单位长度的不透明度倍数
+    density_scale: f32,
+    // 穿过整个体积的最大步数
+    step_count: u32,
+    _padding: vec2<u32>,
+};
+
+@group(0) @binding(0) var<uniform> mvp_mat: MVPMatUniform;
+@group(0) @binding(1) var<uniform> params: VolumeParams;
+@group(0) @binding(2) var volume: texture_3d<f32>;
+@group(0) @binding(3) var transfer: texture_2d<f32>;
+@group(0) @binding(4) var tex_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) obj_pos: vec3<f32>,
+};
+
+// 体积占据模型空间中的 [-1, 1] 立方体，只绘制背面，相机进入体积内部时仍能看到
+@vertex
+fn vs_main(@location(0) pos: vec3<f32>) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = mvp_mat.mvp * vec4<f32>(pos, 1.0);
+    out.obj_pos = pos;
+    return out;
+}
+
+// 射线与立方体的相交区间
+fn intersect_box(origin: vec3<f32>, dir: vec3<f32>) -> vec2<f32> {
+    let inv_dir = 1.0 / dir;
+    let t0 = (vec3<f32>(-1.0) - origin) * inv_dir;
+    let t1 = (vec3<f32>(1.0) - origin) * inv_dir;
+    let t_min = min(t0, t1);
+    let t_max = max(t0, t1);
+    return vec2<f32>(max(max(t_min.x, t_min.y), t_min.z), min(min(t_max.x, t_max.y), t_max.z));
+}
+
+// 屏幕空间的随机偏移，用来消除步进产生的条纹
+fn hash(p: vec2<f32>) -> f32 {
+    return fract(sin(dot(p, vec2<f32>(12.9898, 78.233))) * 43758.5453);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // normal 矩阵是 mv 的逆矩阵的转置，由此得到相机在模型空间中的位置
+    let inv_mv = transpose(mvp_mat.normal);
+    let origin = (inv_mv * vec4<f32>(0.0, 0.0, 0.0, 1.0)).xyz;
+    let dir = normalize(in.obj_pos - origin);
+    let range = intersect_box(origin, dir);
+    let t_start = max(range.x, 0.0);
+    let t_end = range.y;
+    if (t_end <= t_start) {
+        discard;
+    }
+
+    // 立方体对角线的长度为 2√3
+    let step = 3.4641 / f32(params.step_count);
+    var t = t_start + step * hash(in.position.xy);
+    var color = vec3<f32>(0.0);
+    var alpha = 0.0;
+    loop {
+        if (t >= t_end || alpha > 0.99) {
+            break;
+        }
+        let uvw = (origin + dir * t) * 0.5 + 0.5;
+        let value = dot(textureSampleLevel(volume, tex_sampler, uvw, 0.0), params.value_mask);
+        let sample = textureSampleLevel(transfer, tex_sampler, vec2<f32>(clamp(value, 0.0, 1.0), 0.5), 0.0);
+        // 按步长修正不透明度，步数变化时整体的密度不变
+        let a = 1.0 - exp(-sample.a * params.density_scale * step);
+        // 由前向后合成
+        color += (1.0 - alpha) * a * sample.rgb;
+        alpha += (1.0 - alpha) * a;
+        t += step;
+    }
+    return vec4<f32>(color, alpha);
+}
@@ -116,7 +116,12 @@ impl SimuverseAppHandler {
                     }
                 });
             } else {
-                let simu_app = pollster::block_on(SimuverseApp::new(window));
+                let mut simu_app = pollster::block_on(SimuverseApp::new(window));
+                // 命令行参数：--script <path>
+                let mut args = std::env::args().skip_while(|arg| arg != "--script");
+                if let Some(path) = args.nth(1) {
+                    simu_app.load_script(std::path::Path::new(&path));
+                }
                 self.app.lock().replace(simu_app);
             }
         }
@@ -160,11 +165,11 @@ impl ApplicationHandler for SimuverseAppHandler {
         if app.as_ref().is_none() {
             // 如果 app 还没有初始化完成，则记录错失的窗口事件
             match event {
-                WindowEvent::Resized(physical_size) => {
-                    if physical_size.width > 0 && physical_size.height > 0 {
-                        let mut missed_resize = self.missed_resize.lock();
-                        *missed_resize = Some(physical_size);
-                    }
+                WindowEvent::Resized(physical_size)
+                    if physical_size.width > 0 && physical_size.height > 0 =>
+                {
+                    let mut missed_resize = self.missed_resize.lock();
+                    *missed_resize = Some(physical_size);
                 }
                 WindowEvent::RedrawRequested => {
                     let mut missed_request_redraw = self.missed_request_redraw.lock();
@@ -176,7 +181,7 @@ impl ApplicationHandler for SimuverseAppHandler {
         }
 
         let app = app.as_mut().unwrap();
-        app.on_ui_event(&event);
+        let consumed_by_ui = app.on_ui_event(&event);
 
         match event {
             WindowEvent::CloseRequested => {
@@ -190,6 +195,11 @@ impl ApplicationHandler for SimuverseAppHandler {
                     app.set_window_resized(physical_size);
                 }
             }
+            // 在 UI 面板上的操作不传递给模拟器，但鼠标抬起事件总是需要传递
+            WindowEvent::MouseInput { state, .. }
+                if consumed_by_ui && state == ElementState::Pressed => {}
+            WindowEvent::CursorMoved { .. } if consumed_by_ui => {}
+            WindowEvent::MouseWheel { .. } if consumed_by_ui => {}
             WindowEvent::MouseInput {
                 device_id: _,
                 state,
@@ -210,10 +220,16 @@ impl ApplicationHandler for SimuverseAppHandler {
                 self.last_touch_point = point;
             }
             WindowEvent::MouseWheel { delta, phase, .. } => app.mouse_wheel(&delta, &phase),
+            #[cfg(not(target_arch = "wasm32"))]
+            WindowEvent::DroppedFile(path) => app.open_dropped_file(&path),
             WindowEvent::RedrawRequested => {
                 self.pre_present_notify();
 
                 app.render();
+                if app.is_exit_requested() {
+                    event_loop.exit();
+                    return;
+                }
 
                 self.request_redraw();
             }
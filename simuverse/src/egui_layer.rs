@@ -68,13 +68,15 @@ impl EguiLayer {
         }
     }
 
-    pub fn on_ui_event(&mut self, window: &Window, event: &WindowEvent) {
+    /// 返回事件是否已被 egui 消费
+    pub fn on_ui_event(&mut self, window: &Window, event: &WindowEvent) -> bool {
         let response = self.egui_state.on_window_event(window, event);
         self.egui_repaint = if response.consumed {
             20
         } else {
             self.egui_repaint.max(1)
         };
+        response.consumed
     }
 
     pub fn resize(&mut self, app: &AppSurface) {
@@ -96,8 +98,8 @@ impl EguiLayer {
         self.egui_repaint -= 1;
 
         let raw_input = self.egui_state.take_egui_input(app.get_view());
-        let full_output = self.ctx.run(raw_input, |ctx| {
-            egui_app.ui_contents(ctx);
+        let full_output = self.ctx.run_ui(raw_input, |ui| {
+            egui_app.ui_contents(ui);
         });
         let clipped_primitives = self.ctx.tessellate(full_output.shapes, app.scale_factor);
         let textures_delta = full_output.textures_delta;
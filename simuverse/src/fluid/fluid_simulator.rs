@@ -194,9 +194,13 @@ impl Simulator for FluidSimulator {
 
     fn update_by(
         &mut self,
-        _app: &app_surface::AppSurface,
-        _control_panel: &mut crate::ControlPanel,
+        app: &app_surface::AppSurface,
+        control_panel: &mut crate::ControlPanel,
     ) {
+        if control_panel.setting.fluid_viscosity != control_panel.fluid_viscosity {
+            control_panel.setting.fluid_viscosity = control_panel.fluid_viscosity;
+            self.update_uniforms(app, &control_panel.setting);
+        }
     }
 
     fn update_workgroup_count(
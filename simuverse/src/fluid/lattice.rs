@@ -44,10 +44,10 @@ pub fn init_lattice_material(
 
                 // need boundary cell to avoid NAN
                 match ty {
-                    FieldAnimationType::Custom => {
-                        if x == 0 || x == nx - 1 || y == 0 || y == ny - 1 {
-                            material = LatticeType::Boundary as i32;
-                        }
+                    FieldAnimationType::Custom
+                        if x == 0 || x == nx - 1 || y == 0 || y == ny - 1 =>
+                    {
+                        material = LatticeType::Boundary as i32;
                     }
                     FieldAnimationType::LidDrivenCavity => {
                         if x == 0 || x == nx - 1 || y == ny - 1 {
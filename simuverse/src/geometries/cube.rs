@@ -0,0 +1,49 @@
+use crate::util::vertex::PosNormalUv;
+use alloc::{vec, vec::Vec};
+
+/// 以原点为中心的长方体，每个面有独立的顶点与法线
+pub struct Cube {
+    half_size: glam::Vec3,
+}
+
+impl Cube {
+    pub fn new(width: f32, height: f32, depth: f32) -> Self {
+        Cube {
+            half_size: glam::Vec3::new(width, height, depth) * 0.5,
+        }
+    }
+
+    pub fn generate_vertices(&self) -> (Vec<PosNormalUv>, Vec<u32>) {
+        // (法线, 面上的 u 轴, 面上的 v 轴)
+        let faces = [
+            (glam::Vec3::X, glam::Vec3::NEG_Z, glam::Vec3::Y),
+            (glam::Vec3::NEG_X, glam::Vec3::Z, glam::Vec3::Y),
+            (glam::Vec3::Y, glam::Vec3::X, glam::Vec3::NEG_Z),
+            (glam::Vec3::NEG_Y, glam::Vec3::X, glam::Vec3::Z),
+            (glam::Vec3::Z, glam::Vec3::X, glam::Vec3::Y),
+            (glam::Vec3::NEG_Z, glam::Vec3::NEG_X, glam::Vec3::Y),
+        ];
+        let mut vertices = vec![];
+        let mut indices: Vec<u32> = vec![];
+        for (normal, u_axis, v_axis) in faces {
+            let offset = vertices.len() as u32;
+            for uv in [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]] {
+                let p = normal + u_axis * (uv[0] * 2.0 - 1.0) + v_axis * (uv[1] * 2.0 - 1.0);
+                vertices.push(PosNormalUv {
+                    pos: (p * self.half_size).to_array(),
+                    normal: normal.to_array(),
+                    uv: [uv[0], 1.0 - uv[1]],
+                });
+            }
+            indices.extend_from_slice(&[
+                offset,
+                offset + 1,
+                offset + 2,
+                offset,
+                offset + 2,
+                offset + 3,
+            ]);
+        }
+        (vertices, indices)
+    }
+}
@@ -1,3 +1,6 @@
+mod cube;
+pub use cube::Cube;
+
 mod plane;
 #[allow(unused_imports)]
 pub use plane::Plane;
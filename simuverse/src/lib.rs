@@ -38,6 +38,9 @@ pub use field_velocity_code::get_velocity_code_snippet;
 
 pub mod pbd;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod script;
+
 #[cfg(not(target_arch = "wasm32"))]
 mod truck;
 #[cfg(not(target_arch = "wasm32"))]
@@ -105,7 +108,7 @@ pub trait Simulator {
     );
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum SimuType {
     Field = 0,
     Fluid,
@@ -115,6 +118,31 @@ pub enum SimuType {
     CAD,
 }
 
+impl SimuType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "field" => Some(SimuType::Field),
+            "fluid" => Some(SimuType::Fluid),
+            "noise" => Some(SimuType::Noise),
+            "pbd" => Some(SimuType::PBDynamic),
+            #[cfg(not(target_arch = "wasm32"))]
+            "cad" => Some(SimuType::CAD),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SimuType::Field => "field",
+            SimuType::Fluid => "fluid",
+            SimuType::Noise => "noise",
+            SimuType::PBDynamic => "pbd",
+            SimuType::D3Fluid => "d3fluid",
+            SimuType::CAD => "cad",
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq)]
 pub enum FieldAnimationType {
     Basic = 0,
@@ -13,12 +13,11 @@ pub struct BindGroupSetting {
 impl BindGroupSetting {
     pub fn new(device: &wgpu::Device, bg_data: &BindGroupData) -> Self {
         let mut layouts: Vec<wgpu::BindGroupLayoutEntry> = vec![];
-        let mut entries: Vec<wgpu::BindGroupEntry> = vec![];
 
         // 关于 min_binding_size
         // https://gpuweb.github.io/gpuweb/#dom-gpubindgrouplayoutentry-minbufferbindingsize
         let mut b_index = 0_u32;
-        for buffer_obj in bg_data.uniforms.iter() {
+        for _ in bg_data.uniforms.iter() {
             layouts.push(wgpu::BindGroupLayoutEntry {
                 binding: b_index,
                 visibility: bg_data.visibilitys[b_index as usize],
@@ -29,10 +28,6 @@ impl BindGroupSetting {
                 },
                 count: None,
             });
-            entries.push(wgpu::BindGroupEntry {
-                binding: b_index,
-                resource: buffer_obj.buffer.as_entire_binding(),
-            });
             b_index += 1;
         }
 
@@ -49,10 +44,6 @@ impl BindGroupSetting {
                 },
                 count: None,
             });
-            entries.push(wgpu::BindGroupEntry {
-                binding: b_index,
-                resource: buffer_obj.buffer.as_entire_binding(),
-            });
             b_index += 1;
         }
 
@@ -78,24 +69,16 @@ impl BindGroupSetting {
                 },
                 count: None,
             });
-            entries.push(wgpu::BindGroupEntry {
-                binding: b_index,
-                resource: wgpu::BindingResource::TextureView(&any_tex.tex_view),
-            });
             b_index += 1;
         }
 
-        for sampler in bg_data.samplers.iter() {
+        for _ in bg_data.samplers.iter() {
             layouts.push(wgpu::BindGroupLayoutEntry {
                 binding: b_index,
                 visibility: bg_data.visibilitys[b_index as usize],
                 ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                 count: None,
             });
-            entries.push(wgpu::BindGroupEntry {
-                binding: b_index,
-                resource: wgpu::BindingResource::Sampler(sampler),
-            });
             b_index += 1;
         }
 
@@ -104,17 +87,53 @@ impl BindGroupSetting {
             label: None,
         });
 
-        let bind_group: wgpu::BindGroup = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &bind_group_layout,
-            entries: &entries,
-            label: None,
-        });
+        let bind_group = create_bind_group(device, &bind_group_layout, bg_data);
 
         Self {
             bind_group_layout,
             bind_group,
         }
     }
+
+    /// 缓冲区重新分配后，使用原有的布局重建绑定组，依赖此布局的管线无需重建
+    pub fn rebind(&mut self, device: &wgpu::Device, bg_data: &BindGroupData) {
+        self.bind_group = create_bind_group(device, &self.bind_group_layout, bg_data);
+    }
+}
+
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    bg_data: &BindGroupData,
+) -> wgpu::BindGroup {
+    let mut entries: Vec<wgpu::BindGroupEntry> = vec![];
+    let buffers = bg_data
+        .uniforms
+        .iter()
+        .chain(bg_data.storage_buffers.iter());
+    for buffer_obj in buffers {
+        entries.push(wgpu::BindGroupEntry {
+            binding: entries.len() as u32,
+            resource: buffer_obj.buffer.as_entire_binding(),
+        });
+    }
+    for (any_tex, _) in bg_data.inout_tv.iter() {
+        entries.push(wgpu::BindGroupEntry {
+            binding: entries.len() as u32,
+            resource: wgpu::BindingResource::TextureView(&any_tex.tex_view),
+        });
+    }
+    for sampler in bg_data.samplers.iter() {
+        entries.push(wgpu::BindGroupEntry {
+            binding: entries.len() as u32,
+            resource: wgpu::BindingResource::Sampler(sampler),
+        });
+    }
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        layout,
+        entries: &entries,
+        label: None,
+    })
 }
 
 fn texture_sample_filterable(format: TextureFormat) -> bool {
@@ -4,7 +4,6 @@ use super::{BindGroupSetting, DynamicUniformBindGroup};
 use crate::util::BufferObj;
 
 use alloc::{vec, vec::Vec};
-use core::ops::Range;
 
 pub struct ComputeNode {
     pub bg_setting: BindGroupSetting,
@@ -90,7 +89,7 @@ impl ComputeNode {
 
         let mut ranges: Vec<u32> = vec![];
         if let Some(constants) = push_constants {
-            for (stage, range) in constants.iter() {
+            for (_stage, range) in constants.iter() {
                 ranges.push(*range)
             }
         }
@@ -118,6 +117,12 @@ impl ComputeNode {
         }
     }
 
+    /// 绑定的缓冲区重新分配后，更新绑定组及线程组数量，管线保持不变
+    pub fn rebind(&mut self, device: &wgpu::Device, bg_data: &super::BindGroupData) {
+        self.bg_setting.rebind(device, bg_data);
+        self.workgroup_count = bg_data.workgroup_count;
+    }
+
     pub fn compute(&self, encoder: &mut wgpu::CommandEncoder) {
         self.compute_by_offsets(encoder, None);
     }
@@ -12,6 +12,9 @@ pub use view_node::{ViewNode, ViewNodeBuilder};
 mod bufferless_fullscreen_node;
 pub use bufferless_fullscreen_node::BufferlessFullscreenNode;
 
+mod volume_node;
+pub use volume_node::{TransferStop, VolumeNode};
+
 use crate::util::{AnyTexture, BufferObj};
 use alloc::vec::Vec;
 
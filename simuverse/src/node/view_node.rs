@@ -171,7 +171,7 @@ impl ViewNode {
         let index_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("index buffer"),
             contents: bytemuck::cast_slice(&vi.1),
-            usage: wgpu::BufferUsages::INDEX,
+            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
         });
 
         let default_layout_attributes = T::vertex_attributes(0);
@@ -235,6 +235,11 @@ impl ViewNode {
             }),
             primitive: wgpu::PrimitiveState {
                 topology: attributes.primitive_topology,
+                // strip 类型的图元可以用 u32::MAX 索引断开
+                strip_index_format: attributes
+                    .primitive_topology
+                    .is_strip()
+                    .then_some(wgpu::IndexFormat::Uint32),
                 front_face: wgpu::FrontFace::Ccw,
                 cull_mode: attributes.cull_mode,
                 polygon_mode: attributes.polygon_mode,
@@ -269,6 +274,44 @@ impl ViewNode {
         }
     }
 
+    /// 更新顶点及索引数据，缓冲区容量不足时才重新分配
+    pub fn update_vertices_and_indices<T: Vertex + Pod>(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        vertices_and_indices: (Vec<T>, Vec<u32>),
+    ) {
+        let (vertices, indices) = vertices_and_indices;
+        if let Some(vertex_buf) = self.vertex_buf.as_mut() {
+            if (core::mem::size_of_val(vertices.as_slice()) as u64) <= vertex_buf.size {
+                queue.write_buffer(&vertex_buf.buffer, 0, bytemuck::cast_slice(&vertices));
+            } else {
+                *vertex_buf = BufferObj::create_buffer(
+                    device,
+                    Some(&vertices),
+                    None,
+                    wgpu::BufferUsages::VERTEX,
+                    Some("Vertex buffer"),
+                );
+            }
+        }
+        if core::mem::size_of_val(indices.as_slice()) as u64 <= self.index_buf.size() {
+            queue.write_buffer(&self.index_buf, 0, bytemuck::cast_slice(&indices));
+        } else {
+            self.index_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("index buffer"),
+                contents: bytemuck::cast_slice(&indices),
+                usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+            });
+        }
+        self.index_count = indices.len();
+    }
+
+    /// 绑定的缓冲区重新分配后重建绑定组，渲染管线保持不变
+    pub fn rebind(&mut self, device: &wgpu::Device, bg_data: &BindGroupData) {
+        self.bg_setting.rebind(device, bg_data);
+    }
+
     pub fn draw(
         &self,
         frame_view: &wgpu::TextureView,
@@ -0,0 +1,206 @@
+use crate::{
+    MVPMatUniform,
+    geometries::Cube,
+    util::{AnyTexture, BufferObj, load_texture},
+};
+use alloc::{vec, vec::Vec};
+use app_surface::AppSurface;
+use bytemuck::Zeroable;
+use wgpu::ShaderStages;
+
+use super::{BindGroupData, ViewNode, ViewNodeBuilder};
+
+/// 传递函数查找表的长度
+const TRANSFER_SIZE: u32 = 256;
+
+/// 传递函数的节点：标量值在 [0, 1] 内的位置及对应的颜色与不透明度
+#[derive(Clone, Copy, PartialEq)]
+pub struct TransferStop {
+    pub position: f32,
+    pub color: [f32; 4],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct VolumeParams {
+    value_mask: [f32; 4],
+    density_scale: f32,
+    step_count: u32,
+    _padding: [u32; 2],
+}
+
+/// 光线步进绘制 3D 纹理
+///
+/// 体积占据模型空间中的 [-1, 1] 立方体，采样值与 value_mask 点乘得到标量，
+/// 再经传递函数映射为颜色及不透明度，可用于噪声纹理及其它 3D 标量场
+pub struct VolumeNode {
+    display_node: ViewNode,
+    mvp_buf: BufferObj,
+    params: VolumeParams,
+    params_buf: BufferObj,
+    transfer_tex: AnyTexture,
+    sampler: wgpu::Sampler,
+}
+
+impl VolumeNode {
+    /// volume 须是可过滤的 3D 纹理，如 Rgba8Unorm、R16Float
+    pub fn new(app: &AppSurface, volume: &AnyTexture, value_mask: [f32; 4]) -> Self {
+        let mvp_buf = BufferObj::create_uniform_buffer(
+            &app.device,
+            &MVPMatUniform::zeroed(),
+            Some("volume mvp"),
+        );
+        let params = VolumeParams {
+            value_mask,
+            density_scale: 4.0,
+            step_count: 96,
+            _padding: [0; 2],
+        };
+        let params_buf =
+            BufferObj::create_uniform_buffer(&app.device, &params, Some("volume params"));
+        let transfer_tex = load_texture::empty(
+            &app.device,
+            wgpu::TextureFormat::Rgba8Unorm,
+            wgpu::Extent3d {
+                width: TRANSFER_SIZE,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            None,
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            Some("transfer function"),
+        );
+        let sampler = load_texture::bilinear_sampler(&app.device);
+        let display_node =
+            Self::create_node(app, &mvp_buf, &params_buf, volume, &transfer_tex, &sampler);
+
+        let instance = Self {
+            display_node,
+            mvp_buf,
+            params,
+            params_buf,
+            transfer_tex,
+            sampler,
+        };
+        // 默认从全透明的黑色到不透明的白色
+        instance.set_transfer_function(
+            app,
+            &[
+                TransferStop {
+                    position: 0.0,
+                    color: [0.0; 4],
+                },
+                TransferStop {
+                    position: 1.0,
+                    color: [1.0; 4],
+                },
+            ],
+        );
+        instance
+    }
+
+    fn create_node(
+        app: &AppSurface,
+        mvp_buf: &BufferObj,
+        params_buf: &BufferObj,
+        volume: &AnyTexture,
+        transfer_tex: &AnyTexture,
+        sampler: &wgpu::Sampler,
+    ) -> ViewNode {
+        let shader = crate::util::shader::create_shader_module(&app.device, "volume", None);
+        let bg_data = BindGroupData {
+            uniforms: vec![mvp_buf, params_buf],
+            inout_tv: vec![(volume, None), (transfer_tex, None)],
+            samplers: vec![sampler],
+            visibilitys: vec![
+                ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                ShaderStages::FRAGMENT,
+                ShaderStages::FRAGMENT,
+                ShaderStages::FRAGMENT,
+                ShaderStages::FRAGMENT,
+            ],
+            ..Default::default()
+        };
+        // 只绘制背面，相机在体积内部时也能看到
+        ViewNodeBuilder::<crate::util::vertex::PosNormalUv>::new(bg_data, &shader)
+            .with_vertices_and_indices(Cube::new(2.0, 2.0, 2.0).generate_vertices())
+            .with_cull_mode(Some(wgpu::Face::Front))
+            .with_color_blend_state(Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING))
+            .with_color_format(app.config.format)
+            .build(&app.device)
+    }
+
+    /// 体积纹理重新创建后需要重新绑定
+    pub fn set_volume(&mut self, app: &AppSurface, volume: &AnyTexture) {
+        self.display_node = Self::create_node(
+            app,
+            &self.mvp_buf,
+            &self.params_buf,
+            volume,
+            &self.transfer_tex,
+            &self.sampler,
+        );
+    }
+
+    /// stops 须按位置升序排列，两端之外取端点的值
+    pub fn set_transfer_function(&self, app: &AppSurface, stops: &[TransferStop]) {
+        let texels = transfer_texels(stops);
+        app.queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.transfer_tex.tex,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &texels,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * TRANSFER_SIZE),
+                rows_per_image: Some(1),
+            },
+            self.transfer_tex.size,
+        );
+    }
+
+    pub fn set_density(&mut self, app: &AppSurface, density_scale: f32, step_count: u32) {
+        let params = VolumeParams {
+            density_scale,
+            step_count: step_count.max(1),
+            ..self.params
+        };
+        if params != self.params {
+            self.params = params;
+            app.queue
+                .write_buffer(&self.params_buf.buffer, 0, bytemuck::bytes_of(&params));
+        }
+    }
+
+    pub fn draw_by_pass<'a, 'b: 'a>(
+        &'b self,
+        app: &AppSurface,
+        rpass: &mut wgpu::RenderPass<'b>,
+        mvp: &MVPMatUniform,
+    ) {
+        app.queue
+            .write_buffer(&self.mvp_buf.buffer, 0, bytemuck::bytes_of(mvp));
+        self.display_node.draw_by_pass(rpass);
+    }
+}
+
+fn transfer_texels(stops: &[TransferStop]) -> Vec<u8> {
+    let mut texels = Vec::with_capacity(4 * TRANSFER_SIZE as usize);
+    for i in 0..TRANSFER_SIZE {
+        let x = i as f32 / (TRANSFER_SIZE - 1) as f32;
+        let color = match stops.iter().position(|stop| stop.position >= x) {
+            None => stops.last().map_or([0.0; 4], |stop| stop.color),
+            Some(0) => stops[0].color,
+            Some(index) => {
+                let (s0, s1) = (stops[index - 1], stops[index]);
+                let w = (x - s0.position) / (s1.position - s0.position).max(0.00001);
+                core::array::from_fn(|c| s0.color[c] + (s1.color[c] - s0.color[c]) * w)
+            }
+        };
+        texels.extend(color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8));
+    }
+    texels
+}
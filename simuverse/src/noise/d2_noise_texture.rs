@@ -0,0 +1,71 @@
+use crate::{
+    node::{BindGroupData, ComputeNode},
+    noise::{
+        BakeUniform, TexGeneratorParams, create_gradient_buf, create_noise_shader,
+        create_permulation_buf,
+    },
+    util::BufferObj,
+};
+use alloc::vec;
+
+/// 把噪声材质烘焙到 2D 纹理，可作为其它模拟的贴图或导出为图片
+pub struct D2NoiseTexture {
+    pub tex: crate::util::AnyTexture,
+}
+
+impl D2NoiseTexture {
+    pub(crate) fn create(
+        app: &app_surface::AppSurface,
+        params: &TexGeneratorParams,
+        code: &str,
+        size: u32,
+        tileable: bool,
+    ) -> Self {
+        let tex = crate::util::load_texture::empty(
+            &app.device,
+            wgpu::TextureFormat::Rgba8Unorm,
+            wgpu::Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 1,
+            },
+            None,
+            wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::STORAGE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+            Some("2d noise tex"),
+        );
+
+        let group_count = size.div_ceil(16);
+        let uniform_buf = BufferObj::create_uniform_buffer(&app.device, params, None);
+        let bake_buf = BufferObj::create_uniform_buffer(
+            &app.device,
+            &BakeUniform::new(glam::BVec3::new(tileable, tileable, false)),
+            None,
+        );
+        let permulation_buf = create_permulation_buf(&app.device, params.seed);
+        let gradient_buf = create_gradient_buf(&app.device, params.seed);
+        let shader = create_noise_shader(&app.device, "noise/2d_noise_tex", code);
+        let noise_node = ComputeNode::new(
+            &app.device,
+            &BindGroupData {
+                workgroup_count: (group_count, group_count, 1),
+                uniforms: vec![&uniform_buf, &bake_buf],
+                storage_buffers: vec![&permulation_buf, &gradient_buf],
+                inout_tv: vec![(&tex, Some(wgpu::StorageTextureAccess::WriteOnly))],
+                ..Default::default()
+            },
+            &shader,
+        );
+
+        let mut encoder = app
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("2d noise encoder"),
+            });
+        noise_node.compute(&mut encoder);
+        app.queue.submit(Some(encoder.finish()));
+
+        Self { tex }
+    }
+}
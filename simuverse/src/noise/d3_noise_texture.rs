@@ -1,38 +1,56 @@
 use crate::{
-    create_shader_module,
     node::{BindGroupData, ComputeNode},
-    noise::{create_gradient_buf, create_permulation_buf},
+    noise::{
+        BakeUniform, TexGeneratorParams, create_gradient_buf, create_noise_shader,
+        create_permulation_buf,
+    },
+    util::BufferObj,
 };
 use alloc::vec;
 
+/// 把噪声材质烘焙到 3D 纹理，可逐层导出为图片或以体积显示
 pub struct D3NoiseTexture {
     pub tex: crate::util::AnyTexture,
 }
 
 impl D3NoiseTexture {
-    pub fn create(app: &app_surface::AppSurface) -> Self {
+    pub(crate) fn create(
+        app: &app_surface::AppSurface,
+        params: &TexGeneratorParams,
+        code: &str,
+        size: u32,
+        tileable: bool,
+    ) -> Self {
         let tex = crate::util::load_texture::empty(
             &app.device,
             wgpu::TextureFormat::Rgba8Unorm,
             wgpu::Extent3d {
-                width: 64,
-                height: 64,
-                depth_or_array_layers: 64,
+                width: size,
+                height: size,
+                depth_or_array_layers: size,
             },
             Some(wgpu::TextureViewDimension::D3),
-            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::STORAGE_BINDING,
-            None,
+            wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::STORAGE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+            Some("3d noise tex"),
         );
 
-        let dispatch_group_count = (8, 8, 8);
-
-        let permulation_buf = create_permulation_buf(&app.device);
-        let gradient_buf = create_gradient_buf(&app.device);
-        let shader = create_shader_module(&app.device, "noise/3d_noise_tex", None);
+        let group_count = size.div_ceil(4);
+        let uniform_buf = BufferObj::create_uniform_buffer(&app.device, params, None);
+        let bake_buf = BufferObj::create_uniform_buffer(
+            &app.device,
+            &BakeUniform::new(glam::BVec3::splat(tileable)),
+            None,
+        );
+        let permulation_buf = create_permulation_buf(&app.device, params.seed);
+        let gradient_buf = create_gradient_buf(&app.device, params.seed);
+        let shader = create_noise_shader(&app.device, "noise/3d_noise_tex", code);
         let noise_node = ComputeNode::new(
             &app.device,
             &BindGroupData {
-                workgroup_count: dispatch_group_count,
+                workgroup_count: (group_count, group_count, group_count),
+                uniforms: vec![&uniform_buf, &bake_buf],
                 storage_buffers: vec![&permulation_buf, &gradient_buf],
                 inout_tv: vec![(&tex, Some(wgpu::StorageTextureAccess::WriteOnly))],
                 ..Default::default()
@@ -0,0 +1,665 @@
+//! 节点图材质
+//!
+//! 由噪声、fBm、数学运算、颜色渐变及混合节点组合成材质，编译为 WGSL 的 `graph_color` 函数后插入到噪声 shader 中。
+//! 所有端口的值都是 `vec3<f32>`，标量会被扩展为三个分量相同的向量。
+
+use super::basis_expr;
+use alloc::{format, string::String, vec, vec::Vec};
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum NodeKind {
+    /// 材质坐标
+    Position,
+    Time,
+    /// 映射到 [0, 1] 的单层噪声
+    Noise {
+        basis: i32,
+    },
+    /// 映射到 [0, 1] 的分形布朗运动
+    Fbm {
+        basis: i32,
+        octaves: i32,
+        lacunarity: f32,
+        gain: f32,
+    },
+    Math(MathOp),
+    /// 输入值在 [0, 1] 内从 color0 渐变到 color1
+    ColorRamp {
+        color0: [f32; 3],
+        color1: [f32; 3],
+    },
+    Blend(BlendMode),
+    /// 材质的最终颜色，图中有且只有一个
+    Output,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum MathOp {
+    Add,
+    Subtract,
+    Multiply,
+    Min,
+    Max,
+    Abs,
+    Sine,
+    Fract,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum BlendMode {
+    Mix,
+    Multiply,
+    Screen,
+    Add,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(super) enum SlotKind {
+    /// 未连接时使用材质坐标
+    Position,
+    /// 未连接时使用输入框中的值，参数为默认值
+    Scalar(f32),
+}
+
+pub(crate) struct Input {
+    /// 所连接的节点 id
+    pub link: Option<u32>,
+    pub value: f32,
+}
+
+pub(crate) struct Node {
+    pub id: u32,
+    pub kind: NodeKind,
+    pub inputs: Vec<Input>,
+    /// 在编辑器画布中的位置
+    pub pos: egui::Pos2,
+}
+
+pub(crate) struct MaterialGraph {
+    pub(super) nodes: Vec<Node>,
+    next_id: u32,
+    // 编辑器的状态
+    pub(super) pan: egui::Vec2,
+    /// 正在从该节点的输出端口拖出连线
+    pub(super) dragging_link: Option<u32>,
+}
+
+impl MathOp {
+    pub(super) const ALL: [Self; 8] = [
+        Self::Add,
+        Self::Subtract,
+        Self::Multiply,
+        Self::Min,
+        Self::Max,
+        Self::Abs,
+        Self::Sine,
+        Self::Fract,
+    ];
+
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            Self::Add => "Add",
+            Self::Subtract => "Subtract",
+            Self::Multiply => "Multiply",
+            Self::Min => "Min",
+            Self::Max => "Max",
+            Self::Abs => "Abs",
+            Self::Sine => "Sine",
+            Self::Fract => "Fract",
+        }
+    }
+
+    /// 一元运算只使用输入 a
+    pub(super) fn is_unary(self) -> bool {
+        matches!(self, Self::Abs | Self::Sine | Self::Fract)
+    }
+}
+
+impl BlendMode {
+    pub(super) const ALL: [Self; 4] = [Self::Mix, Self::Multiply, Self::Screen, Self::Add];
+
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            Self::Mix => "Mix",
+            Self::Multiply => "Multiply",
+            Self::Screen => "Screen",
+            Self::Add => "Add",
+        }
+    }
+}
+
+impl NodeKind {
+    /// 可以在编辑器中添加的节点
+    pub(super) const TEMPLATES: [Self; 7] = [
+        Self::Position,
+        Self::Time,
+        Self::Noise { basis: 0 },
+        Self::Fbm {
+            basis: 0,
+            octaves: 5,
+            lacunarity: 2.0,
+            gain: 0.5,
+        },
+        Self::Math(MathOp::Multiply),
+        Self::ColorRamp {
+            color0: [0.0; 3],
+            color1: [1.0; 3],
+        },
+        Self::Blend(BlendMode::Mix),
+    ];
+
+    pub(super) fn name(&self) -> &'static str {
+        match self {
+            Self::Position => "Position",
+            Self::Time => "Time",
+            Self::Noise { .. } => "Noise",
+            Self::Fbm { .. } => "fBm",
+            Self::Math(_) => "Math",
+            Self::ColorRamp { .. } => "Color ramp",
+            Self::Blend(_) => "Blend",
+            Self::Output => "Output",
+        }
+    }
+
+    pub(super) fn slots(&self) -> &'static [(&'static str, SlotKind)] {
+        match self {
+            Self::Position | Self::Time => &[],
+            Self::Noise { .. } | Self::Fbm { .. } => &[
+                ("pos", SlotKind::Position),
+                ("scale", SlotKind::Scalar(1.0)),
+            ],
+            Self::Math(_) => &[("a", SlotKind::Scalar(0.0)), ("b", SlotKind::Scalar(1.0))],
+            Self::ColorRamp { .. } => &[("t", SlotKind::Scalar(0.5))],
+            Self::Blend(_) => &[
+                ("a", SlotKind::Scalar(0.0)),
+                ("b", SlotKind::Scalar(1.0)),
+                ("factor", SlotKind::Scalar(0.5)),
+            ],
+            Self::Output => &[("color", SlotKind::Scalar(0.0))],
+        }
+    }
+
+    /// 编辑器中显示的输入数
+    pub(super) fn visible_inputs(&self) -> usize {
+        match self {
+            Self::Math(op) if op.is_unary() => 1,
+            _ => self.slots().len(),
+        }
+    }
+
+    pub(super) fn has_output(&self) -> bool {
+        *self != Self::Output
+    }
+
+    /// 保存时使用的名称及参数
+    fn to_param(self) -> (&'static str, Vec<f32>) {
+        match self {
+            Self::Position => ("position", vec![]),
+            Self::Time => ("time", vec![]),
+            Self::Noise { basis } => ("noise", vec![basis as f32]),
+            Self::Fbm {
+                basis,
+                octaves,
+                lacunarity,
+                gain,
+            } => ("fbm", vec![basis as f32, octaves as f32, lacunarity, gain]),
+            Self::Math(op) => ("math", vec![index_of(&MathOp::ALL, op)]),
+            Self::ColorRamp { color0, color1 } => ("ramp", [color0, color1].concat()),
+            Self::Blend(mode) => ("blend", vec![index_of(&BlendMode::ALL, mode)]),
+            Self::Output => ("output", vec![]),
+        }
+    }
+
+    fn from_param(name: &str, params: &[f32]) -> Option<Self> {
+        let param = |i: usize| params.get(i).copied().unwrap_or_default();
+        let basis = param(0).clamp(0.0, 4.0) as i32;
+        let kind = match name {
+            "position" => Self::Position,
+            "time" => Self::Time,
+            "noise" => Self::Noise { basis },
+            "fbm" => Self::Fbm {
+                basis,
+                octaves: param(1).clamp(1.0, 10.0) as i32,
+                lacunarity: param(2).clamp(0.2, 8.4),
+                gain: param(3).clamp(0.15, 1.0),
+            },
+            "math" => Self::Math(*MathOp::ALL.get(param(0) as usize)?),
+            "ramp" => {
+                let color =
+                    |i: usize| [param(i), param(i + 1), param(i + 2)].map(|c| c.clamp(0.0, 1.0));
+                Self::ColorRamp {
+                    color0: color(0),
+                    color1: color(3),
+                }
+            }
+            "blend" => Self::Blend(*BlendMode::ALL.get(param(0) as usize)?),
+            "output" => Self::Output,
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
+
+impl Node {
+    fn new(id: u32, kind: NodeKind, pos: egui::Pos2) -> Self {
+        let inputs = kind
+            .slots()
+            .iter()
+            .map(|(_, slot)| Input {
+                link: None,
+                value: match slot {
+                    SlotKind::Scalar(value) => *value,
+                    SlotKind::Position => 0.0,
+                },
+            })
+            .collect();
+        Self {
+            id,
+            kind,
+            inputs,
+            pos,
+        }
+    }
+}
+
+impl Default for MaterialGraph {
+    /// fBm 经颜色渐变后输出
+    fn default() -> Self {
+        let mut graph = Self {
+            nodes: Vec::new(),
+            next_id: 0,
+            pan: egui::Vec2::ZERO,
+            dragging_link: None,
+        };
+        let fbm = graph.add_node(NodeKind::TEMPLATES[3], egui::pos2(20.0, 40.0));
+        let ramp = graph.add_node(
+            NodeKind::ColorRamp {
+                color0: [0.05, 0.1, 0.3],
+                color1: [0.95, 0.85, 0.6],
+            },
+            egui::pos2(220.0, 60.0),
+        );
+        let output = graph.add_node(NodeKind::Output, egui::pos2(420.0, 80.0));
+        graph.connect(fbm, ramp, 0);
+        graph.connect(ramp, output, 0);
+        graph
+    }
+}
+
+impl MaterialGraph {
+    pub(super) fn add_node(&mut self, kind: NodeKind, pos: egui::Pos2) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.nodes.push(Node::new(id, kind, pos));
+        id
+    }
+
+    /// 删除节点及与它相连的连线，输出节点不能删除
+    pub(super) fn remove_node(&mut self, id: u32) {
+        self.nodes
+            .retain(|node| node.id != id || node.kind == NodeKind::Output);
+        for node in self.nodes.iter_mut() {
+            for input in node.inputs.iter_mut() {
+                if input.link == Some(id) {
+                    input.link = None;
+                }
+            }
+        }
+    }
+
+    /// 把 from 的输出连接到 to 的第 slot 个输入，会形成环时返回 false
+    pub(super) fn connect(&mut self, from: u32, to: u32, slot: usize) -> bool {
+        if self.depends_on(from, to) || self.node(from).is_none_or(|n| !n.kind.has_output()) {
+            return false;
+        }
+        match self
+            .nodes
+            .iter_mut()
+            .find(|node| node.id == to)
+            .and_then(|node| node.inputs.get_mut(slot))
+        {
+            Some(input) => {
+                input.link = Some(from);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(super) fn node(&self, id: u32) -> Option<&Node> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// id 的上游（包括自身）是否有 target
+    fn depends_on(&self, id: u32, target: u32) -> bool {
+        if id == target {
+            return true;
+        }
+        self.node(id).is_some_and(|node| {
+            node.inputs
+                .iter()
+                .filter_map(|input| input.link)
+                .any(|link| self.depends_on(link, target))
+        })
+    }
+
+    /// 编译为 `fn graph_color(pos: vec3<f32>, time: f32) -> vec3<f32>`
+    ///
+    /// 只生成输出节点上游的节点，每个节点对应一个局部变量 `v<id>`
+    pub(crate) fn to_wgsl(&self) -> String {
+        let mut body = String::new();
+        let mut emitted = Vec::new();
+        let output = self.nodes.iter().find(|node| node.kind == NodeKind::Output);
+        let result = match output {
+            Some(node) => {
+                self.emit(node, &mut body, &mut emitted);
+                format!("v{}", node.id)
+            }
+            None => String::from("vec3<f32>(0.0)"),
+        };
+        format!(
+            "fn graph_color(pos: vec3<f32>, time: f32) -> vec3<f32> {{\n{body}    return {result};\n}}\n"
+        )
+    }
+
+    fn emit(&self, node: &Node, code: &mut String, emitted: &mut Vec<u32>) {
+        if emitted.contains(&node.id) {
+            return;
+        }
+        emitted.push(node.id);
+        for link in node.inputs.iter().filter_map(|input| input.link) {
+            if let Some(upstream) = self.node(link) {
+                self.emit(upstream, code, emitted);
+            }
+        }
+
+        let id = node.id;
+        let arg = |slot: usize| self.input_expr(node, slot);
+        let expr = match node.kind {
+            NodeKind::Position => String::from("pos"),
+            NodeKind::Time => String::from("vec3<f32>(time)"),
+            NodeKind::Noise { basis } => {
+                let n = basis_expr(basis, &format!("{} * {}", arg(0), arg(1)));
+                format!("vec3<f32>({n} * 0.5 + 0.5)")
+            }
+            NodeKind::Fbm {
+                basis,
+                octaves,
+                lacunarity,
+                gain,
+            } => {
+                code.push_str(&format!(
+                    "    var fbm{id} = 0.0;
+    {{
+        var p = {} * {};
+        var amp = 0.5;
+        for (var i = 0; i < {octaves}; i++) {{
+            fbm{id} += {} * amp;
+            p *= {};
+            amp *= {};
+        }}
+    }}
+",
+                    arg(0),
+                    arg(1),
+                    basis_expr(basis, "p"),
+                    float(lacunarity),
+                    float(gain)
+                ));
+                format!("vec3<f32>(fbm{id} * 0.5 + 0.5)")
+            }
+            NodeKind::Math(op) => {
+                let (a, b) = (arg(0), arg(1));
+                match op {
+                    MathOp::Add => format!("{a} + {b}"),
+                    MathOp::Subtract => format!("{a} - {b}"),
+                    MathOp::Multiply => format!("{a} * {b}"),
+                    MathOp::Min => format!("min({a}, {b})"),
+                    MathOp::Max => format!("max({a}, {b})"),
+                    MathOp::Abs => format!("abs({a})"),
+                    MathOp::Sine => format!("sin({a})"),
+                    MathOp::Fract => format!("fract({a})"),
+                }
+            }
+            NodeKind::ColorRamp { color0, color1 } => format!(
+                "mix({}, {}, clamp({}.x, 0.0, 1.0))",
+                vec3(color0),
+                vec3(color1),
+                arg(0)
+            ),
+            NodeKind::Blend(mode) => {
+                let (a, b) = (arg(0), arg(1));
+                let blended = match mode {
+                    BlendMode::Mix => b,
+                    BlendMode::Multiply => format!("{a} * {b}"),
+                    BlendMode::Screen => format!("1.0 - (1.0 - {a}) * (1.0 - {b})"),
+                    BlendMode::Add => format!("{a} + {b}"),
+                };
+                format!("mix({a}, {blended}, clamp({}.x, 0.0, 1.0))", arg(2))
+            }
+            NodeKind::Output => arg(0),
+        };
+        code.push_str(&format!("    let v{id} = {expr};\n"));
+    }
+
+    fn input_expr(&self, node: &Node, slot: usize) -> String {
+        let input = &node.inputs[slot];
+        match (input.link, node.kind.slots()[slot].1) {
+            (Some(link), _) if self.node(link).is_some() => format!("v{link}"),
+            (_, SlotKind::Position) => String::from("pos"),
+            _ => format!("vec3<f32>({})", float(input.value)),
+        }
+    }
+
+    /// 保存为一行文本：节点之间用 `;` 分隔，
+    /// 每个节点为 `id/类型/参数/x,y/输入`，输入为 `#<节点 id>` 或未连接时的值
+    pub(crate) fn to_param(&self) -> String {
+        let join = |values: &mut dyn Iterator<Item = String>| values.collect::<Vec<_>>().join(",");
+        self.nodes
+            .iter()
+            .map(|node| {
+                let (name, params) = node.kind.to_param();
+                let inputs = join(&mut node.inputs.iter().map(|input| match input.link {
+                    Some(link) => format!("#{link}"),
+                    None => format!("{}", input.value),
+                }));
+                format!(
+                    "{}/{name}/{}/{},{}/{inputs}",
+                    node.id,
+                    join(&mut params.iter().map(|v| format!("{v}"))),
+                    node.pos.x,
+                    node.pos.y,
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
+    pub(crate) fn from_param(value: &str) -> Result<Self, String> {
+        let mut graph = Self {
+            nodes: Vec::new(),
+            next_id: 0,
+            pan: egui::Vec2::ZERO,
+            dragging_link: None,
+        };
+        // 先创建所有节点，再连线以检查是否有环
+        let mut links = Vec::new();
+        for item in value.split(';').filter(|item| !item.trim().is_empty()) {
+            let invalid = || format!("invalid graph node `{item}`");
+            let fields: Vec<&str> = item.trim().split('/').collect();
+            let [id, name, params, pos, inputs] = fields[..] else {
+                return Err(invalid());
+            };
+            let id: u32 = id.parse().map_err(|_| invalid())?;
+            let params = parse_list(params).ok_or_else(invalid)?;
+            let kind = NodeKind::from_param(name, &params).ok_or_else(invalid)?;
+            let [x, y] = parse_list(pos).ok_or_else(invalid)?[..] else {
+                return Err(invalid());
+            };
+            if graph.node(id).is_some() {
+                return Err(format!("duplicated graph node id {id}"));
+            }
+            let mut node = Node::new(id, kind, egui::pos2(x, y));
+            for (slot, input) in inputs.split(',').filter(|s| !s.is_empty()).enumerate() {
+                let Some(node_input) = node.inputs.get_mut(slot) else {
+                    return Err(invalid());
+                };
+                match input.strip_prefix('#') {
+                    Some(link) => {
+                        links.push((link.parse::<u32>().map_err(|_| invalid())?, id, slot))
+                    }
+                    None => node_input.value = parse_finite(input).ok_or_else(invalid)?,
+                }
+            }
+            graph.next_id = graph.next_id.max(id + 1);
+            graph.nodes.push(node);
+        }
+        if graph
+            .nodes
+            .iter()
+            .filter(|node| node.kind == NodeKind::Output)
+            .count()
+            != 1
+        {
+            return Err(String::from("the graph needs exactly one output node"));
+        }
+        for (from, to, slot) in links {
+            if !graph.connect(from, to, slot) {
+                return Err(format!("invalid graph link #{from} -> {to}"));
+            }
+        }
+        Ok(graph)
+    }
+}
+
+fn index_of<T: PartialEq>(list: &[T], item: T) -> f32 {
+    list.iter().position(|v| *v == item).unwrap_or_default() as f32
+}
+
+fn parse_list(text: &str) -> Option<Vec<f32>> {
+    text.split(',')
+        .filter(|s| !s.is_empty())
+        .map(parse_finite)
+        .collect()
+}
+
+// NaN 及无穷大会在生成的 WGSL 中产生无效的字面量
+fn parse_finite(text: &str) -> Option<f32> {
+    text.parse().ok().filter(|v: &f32| v.is_finite())
+}
+
+/// WGSL 的浮点字面量需要小数点，没有 NaN 及无穷大的字面量
+fn float(v: f32) -> String {
+    if v.is_finite() {
+        format!("{v:?}")
+    } else {
+        String::from("0.0")
+    }
+}
+
+fn vec3(c: [f32; 3]) -> String {
+    format!(
+        "vec3<f32>({}, {}, {})",
+        float(c[0]),
+        float(c[1]),
+        float(c[2])
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 用到所有类型节点的图
+    fn full_graph() -> MaterialGraph {
+        // 默认的图：fBm -> 颜色渐变 -> 输出
+        let mut graph = MaterialGraph::default();
+        let (ramp, output) = (graph.nodes[1].id, graph.nodes[2].id);
+        let pos = graph.add_node(NodeKind::Position, egui::pos2(0.0, 0.0));
+        let time = graph.add_node(NodeKind::Time, egui::pos2(0.0, 100.0));
+        let noise = graph.add_node(NodeKind::Noise { basis: 2 }, egui::pos2(100.0, 0.0));
+        let math = graph.add_node(NodeKind::Math(MathOp::Sine), egui::pos2(200.0, 0.0));
+        let blend = graph.add_node(NodeKind::Blend(BlendMode::Screen), egui::pos2(300.0, 0.0));
+        assert!(graph.connect(pos, noise, 0));
+        assert!(graph.connect(time, math, 0));
+        assert!(graph.connect(noise, blend, 0));
+        assert!(graph.connect(math, blend, 1));
+        assert!(graph.connect(ramp, blend, 2));
+        assert!(graph.connect(blend, output, 0));
+        graph
+    }
+
+    #[test]
+    fn param_round_trip() {
+        let graph = full_graph();
+        let text = graph.to_param();
+        let restored = MaterialGraph::from_param(&text).unwrap();
+        assert_eq!(restored.to_param(), text);
+        assert_eq!(restored.to_wgsl(), graph.to_wgsl());
+        assert_eq!(restored.next_id, graph.next_id);
+    }
+
+    #[test]
+    fn reject_invalid_params() {
+        assert!(MaterialGraph::from_param("0/output//0,0/NaN").is_err());
+        assert!(MaterialGraph::from_param("0/output//inf,0/").is_err());
+        assert!(MaterialGraph::from_param("0/ramp/0,0,NaN,1,1,1/0,0/;1/output//0,0/#0").is_err());
+        // 没有输出节点
+        assert!(MaterialGraph::from_param("0/time//0,0/").is_err());
+        // 环
+        assert!(
+            MaterialGraph::from_param("0/math/0/0,0/#1;1/math/0/0,0/#0;2/output//0,0/").is_err()
+        );
+    }
+
+    #[test]
+    fn connect_rejects_cycles() {
+        let mut graph = MaterialGraph::default();
+        let a = graph.add_node(NodeKind::Math(MathOp::Add), egui::pos2(0.0, 0.0));
+        let b = graph.add_node(NodeKind::Math(MathOp::Add), egui::pos2(0.0, 0.0));
+        assert!(graph.connect(a, b, 0));
+        assert!(!graph.connect(b, a, 1));
+        assert!(!graph.connect(a, a, 0));
+        // 输出节点没有输出端口
+        let output = graph.nodes[2].id;
+        assert!(!graph.connect(output, a, 0));
+        assert!(
+            graph
+                .node(a)
+                .unwrap()
+                .inputs
+                .iter()
+                .all(|input| input.link.is_none())
+        );
+    }
+
+    #[test]
+    fn generated_wgsl_is_valid() {
+        let shader = include_str!("../../../assets/preprocessed-wgsl/noise_2d_noise_tex.wgsl");
+        let mut graph = full_graph();
+        // 未连接的输入使用输入框中的值
+        graph.nodes[0].inputs[1].value = f32::NAN;
+        let code = format!(
+            "fn basis_noise(pos: vec3<f32>) -> f32 {{\n    return {};\n}}\n{}",
+            basis_expr(1, "pos"),
+            graph.to_wgsl()
+        );
+        let source: String = shader
+            .lines()
+            .map(|line| {
+                if line.contains("#insert_code_snippet") {
+                    format!("{code}\n")
+                } else {
+                    format!("{line}\n")
+                }
+            })
+            .collect();
+        let module = naga::front::wgsl::parse_str(&source).unwrap();
+        naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .unwrap();
+    }
+}
@@ -0,0 +1,352 @@
+use super::{
+    BASIS_NAMES,
+    material_graph::{BlendMode, MaterialGraph, MathOp, Node, NodeKind, SlotKind},
+};
+use alloc::vec::Vec;
+use egui::{Align2, Color32, FontId, Pos2, Rect, Sense, Stroke, StrokeKind, Ui, Vec2, pos2, vec2};
+
+const NODE_WIDTH: f32 = 170.0;
+const TITLE_HEIGHT: f32 = 22.0;
+const ROW_HEIGHT: f32 = 24.0;
+const PORT_RADIUS: f32 = 5.0;
+
+/// 节点及端口在屏幕上的位置
+struct NodeLayout {
+    rect: Rect,
+    title_rect: Rect,
+    output_port: Option<Pos2>,
+    input_ports: Vec<Pos2>,
+    // 参数控件所在的行
+    param_rows: Vec<Rect>,
+}
+
+enum Action {
+    Remove(u32),
+    Disconnect(u32, usize),
+}
+
+impl NodeLayout {
+    fn new(node: &Node, origin: Pos2) -> Self {
+        let param_count = param_rows(&node.kind);
+        let input_count = node.kind.visible_inputs();
+        let size = vec2(
+            NODE_WIDTH,
+            TITLE_HEIGHT + (param_count + input_count) as f32 * ROW_HEIGHT + 4.0,
+        );
+        let rect = Rect::from_min_size(origin + node.pos.to_vec2(), size);
+        let title_rect = Rect::from_min_size(rect.min, vec2(NODE_WIDTH, TITLE_HEIGHT));
+        let row = |i: usize| {
+            Rect::from_min_size(
+                pos2(
+                    rect.left(),
+                    rect.top() + TITLE_HEIGHT + i as f32 * ROW_HEIGHT,
+                ),
+                vec2(NODE_WIDTH, ROW_HEIGHT),
+            )
+        };
+        Self {
+            rect,
+            title_rect,
+            output_port: node.kind.has_output().then(|| title_rect.right_center()),
+            input_ports: (0..input_count)
+                .map(|i| row(param_count + i).left_center())
+                .collect(),
+            param_rows: (0..param_count).map(row).collect(),
+        }
+    }
+}
+
+fn param_rows(kind: &NodeKind) -> usize {
+    match kind {
+        NodeKind::Noise { .. }
+        | NodeKind::Math(_)
+        | NodeKind::ColorRamp { .. }
+        | NodeKind::Blend(_) => 1,
+        NodeKind::Fbm { .. } => 4,
+        _ => 0,
+    }
+}
+
+impl MaterialGraph {
+    /// 节点编辑器
+    ///
+    /// 拖动标题栏移动节点，从输出端口拖到输入端口连线，点击已连接的输入端口断开；
+    /// 右键画布添加节点，右键标题栏删除节点，拖动空白处平移画布
+    pub(crate) fn editor_ui(&mut self, ui: &mut Ui) {
+        let (canvas, painter) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
+        if canvas.dragged() {
+            self.pan += canvas.drag_delta();
+        }
+        let origin = canvas.rect.min + self.pan;
+        painter.rect_filled(canvas.rect, 0.0, ui.visuals().extreme_bg_color);
+
+        canvas.context_menu(|ui| {
+            // 菜单的左上角即为点击的位置
+            let at = ui.min_rect().min - origin.to_vec2();
+            for kind in NodeKind::TEMPLATES {
+                if ui.button(kind.name()).clicked() {
+                    self.add_node(kind, at);
+                    ui.close();
+                }
+            }
+        });
+
+        // 节点上的控件限制在画布内
+        let mut ui = ui.new_child(egui::UiBuilder::new().max_rect(canvas.rect));
+        ui.set_clip_rect(canvas.rect.intersect(ui.clip_rect()));
+        let painter = painter.with_clip_rect(ui.clip_rect());
+
+        // 先确定所有节点的位置，连线画在节点下面
+        let layouts: Vec<NodeLayout> = self
+            .nodes
+            .iter()
+            .map(|node| NodeLayout::new(node, origin))
+            .collect();
+        let link_stroke = Stroke::new(2.0, ui.visuals().widgets.active.bg_fill);
+        for (node, layout) in self.nodes.iter().zip(&layouts) {
+            for (input, to) in node.inputs.iter().zip(&layout.input_ports) {
+                let from = input
+                    .link
+                    .and_then(|link| self.nodes.iter().position(|n| n.id == link))
+                    .and_then(|index| layouts[index].output_port);
+                if let Some(from) = from {
+                    draw_link(&painter, from, *to, link_stroke);
+                }
+            }
+        }
+
+        let mut action = None;
+        for (index, layout) in layouts.iter().enumerate() {
+            let id = self.nodes[index].id;
+            let node_id = ui.id().with(("graph_node", id));
+            let title = ui.interact(layout.title_rect, node_id, Sense::click_and_drag());
+            if title.dragged() {
+                self.nodes[index].pos += title.drag_delta();
+            }
+            if self.nodes[index].kind != NodeKind::Output {
+                title.context_menu(|ui| {
+                    if ui.button("Delete").clicked() {
+                        action = Some(Action::Remove(id));
+                        ui.close();
+                    }
+                });
+            }
+
+            let visuals = ui.visuals();
+            let stroke = if title.hovered() || title.dragged() {
+                visuals.widgets.hovered.bg_stroke
+            } else {
+                visuals.widgets.noninteractive.bg_stroke
+            };
+            painter.rect(
+                layout.rect,
+                4.0,
+                visuals.window_fill,
+                stroke,
+                StrokeKind::Inside,
+            );
+            painter.rect_filled(
+                layout.title_rect.shrink(1.0),
+                4.0,
+                visuals.widgets.inactive.bg_fill,
+            );
+            painter.text(
+                layout.title_rect.left_center() + vec2(8.0, 0.0),
+                Align2::LEFT_CENTER,
+                self.nodes[index].kind.name(),
+                FontId::proportional(13.0),
+                visuals.strong_text_color(),
+            );
+            let port_color = visuals.widgets.active.bg_fill;
+            let text_color = visuals.text_color();
+
+            if let Some(port) = layout.output_port {
+                let response = ui.interact(
+                    Rect::from_center_size(port, Vec2::splat(PORT_RADIUS * 3.0)),
+                    node_id.with("output"),
+                    Sense::drag(),
+                );
+                if response.drag_started() {
+                    self.dragging_link = Some(id);
+                }
+                painter.circle(port, PORT_RADIUS, port_color, Stroke::NONE);
+            }
+
+            param_ui(&mut ui, &mut self.nodes[index], &layout.param_rows);
+
+            let node = &mut self.nodes[index];
+            for (slot, port) in layout.input_ports.iter().enumerate() {
+                let (name, slot_kind) = node.kind.slots()[slot];
+                let input = &mut node.inputs[slot];
+                let response = ui.interact(
+                    Rect::from_center_size(*port, Vec2::splat(PORT_RADIUS * 3.0)),
+                    node_id.with(("input", slot)),
+                    Sense::click(),
+                );
+                if response.clicked() && input.link.is_some() {
+                    action = Some(Action::Disconnect(id, slot));
+                }
+                let fill = if input.link.is_some() {
+                    port_color
+                } else {
+                    Color32::TRANSPARENT
+                };
+                painter.circle(*port, PORT_RADIUS, fill, Stroke::new(1.5, port_color));
+                painter.text(
+                    *port + vec2(10.0, 0.0),
+                    Align2::LEFT_CENTER,
+                    name,
+                    FontId::proportional(12.0),
+                    text_color,
+                );
+                if input.link.is_none() && slot_kind != SlotKind::Position {
+                    let rect = Rect::from_min_max(
+                        pos2(port.x + NODE_WIDTH * 0.45, port.y - ROW_HEIGHT * 0.5 + 2.0),
+                        pos2(port.x + NODE_WIDTH - 8.0, port.y + ROW_HEIGHT * 0.5 - 2.0),
+                    );
+                    ui.put(rect, egui::DragValue::new(&mut input.value).speed(0.01));
+                }
+            }
+        }
+
+        // 拖出的连线跟随光标，松开时连接到光标下的输入端口
+        if let Some(from) = self.dragging_link {
+            let pointer = ui.input(|i| i.pointer.latest_pos());
+            let from_port = self
+                .nodes
+                .iter()
+                .position(|n| n.id == from)
+                .and_then(|index| layouts[index].output_port);
+            if let (Some(from_port), Some(pointer)) = (from_port, pointer) {
+                draw_link(&painter, from_port, pointer, link_stroke);
+            }
+            if ui.input(|i| i.pointer.any_released()) {
+                self.dragging_link = None;
+                let target = pointer.and_then(|pointer| {
+                    self.nodes.iter().zip(&layouts).find_map(|(node, layout)| {
+                        layout
+                            .input_ports
+                            .iter()
+                            .position(|port| port.distance(pointer) < PORT_RADIUS * 2.5)
+                            .map(|slot| (node.id, slot))
+                    })
+                });
+                if let Some((to, slot)) = target {
+                    self.connect(from, to, slot);
+                }
+            }
+        }
+
+        match action {
+            Some(Action::Remove(id)) => self.remove_node(id),
+            Some(Action::Disconnect(id, slot)) => {
+                if let Some(node) = self.nodes.iter_mut().find(|node| node.id == id) {
+                    node.inputs[slot].link = None;
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+/// 节点自身参数的控件
+fn param_ui(ui: &mut Ui, node: &mut Node, rows: &[Rect]) {
+    let id = ui.id().with(("graph_param", node.id));
+    let rect = |i: usize| rows[i].shrink2(vec2(8.0, 2.0));
+    match &mut node.kind {
+        NodeKind::Noise { basis } => {
+            ui.put(rect(0), |ui: &mut Ui| basis_combo(ui, id, basis));
+        }
+        NodeKind::Fbm {
+            basis,
+            octaves,
+            lacunarity,
+            gain,
+        } => {
+            ui.put(rect(0), |ui: &mut Ui| basis_combo(ui, id, basis));
+            ui.put(
+                rect(1),
+                egui::DragValue::new(octaves)
+                    .range(1..=10)
+                    .prefix("octaves: "),
+            );
+            ui.put(
+                rect(2),
+                egui::DragValue::new(lacunarity)
+                    .range(0.2..=8.4)
+                    .speed(0.01)
+                    .prefix("lacunarity: "),
+            );
+            ui.put(
+                rect(3),
+                egui::DragValue::new(gain)
+                    .range(0.15..=1.0)
+                    .speed(0.01)
+                    .prefix("gain: "),
+            );
+        }
+        NodeKind::Math(op) => {
+            ui.put(rect(0), |ui: &mut Ui| {
+                egui::ComboBox::from_id_salt(id)
+                    .width(rect(0).width())
+                    .selected_text(op.name())
+                    .show_ui(ui, |ui| {
+                        for item in MathOp::ALL {
+                            ui.selectable_value(op, item, item.name());
+                        }
+                    })
+                    .response
+            });
+            // 一元运算的输入 b 不显示，也不保留连线
+            if op.is_unary() {
+                node.inputs[1].link = None;
+            }
+        }
+        NodeKind::ColorRamp { color0, color1 } => {
+            ui.put(rect(0), |ui: &mut Ui| {
+                ui.horizontal(|ui| {
+                    ui.color_edit_button_rgb(color0);
+                    ui.label("→");
+                    ui.color_edit_button_rgb(color1);
+                })
+                .response
+            });
+        }
+        NodeKind::Blend(mode) => {
+            ui.put(rect(0), |ui: &mut Ui| {
+                egui::ComboBox::from_id_salt(id)
+                    .width(rect(0).width())
+                    .selected_text(mode.name())
+                    .show_ui(ui, |ui| {
+                        for item in BlendMode::ALL {
+                            ui.selectable_value(mode, item, item.name());
+                        }
+                    })
+                    .response
+            });
+        }
+        _ => {}
+    }
+}
+
+fn basis_combo(ui: &mut Ui, id: egui::Id, basis: &mut i32) -> egui::Response {
+    egui::ComboBox::from_id_salt(id)
+        .width(ui.available_width())
+        .selected_text(BASIS_NAMES[*basis as usize])
+        .show_ui(ui, |ui| {
+            for (i, name) in BASIS_NAMES.iter().enumerate() {
+                ui.selectable_value(basis, i as i32, *name);
+            }
+        })
+        .response
+}
+
+fn draw_link(painter: &egui::Painter, from: Pos2, to: Pos2, stroke: Stroke) {
+    let control = vec2(((to.x - from.x) * 0.5).abs().max(30.0), 0.0);
+    painter.add(egui::epaint::CubicBezierShape::from_points_stroke(
+        [from, from + control, to - control, to],
+        false,
+        Color32::TRANSPARENT,
+        stroke,
+    ));
+}
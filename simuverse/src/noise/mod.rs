@@ -2,18 +2,41 @@
 //!
 //! 相关论文：http://staffwww.itn.liu.se/%7Estegu/simplexnoise/simplexnoise.pdf
 
-use alloc::{vec, vec::Vec};
+use alloc::{format, string::String, vec, vec::Vec};
+
+mod d2_noise_texture;
+pub use d2_noise_texture::D2NoiseTexture;
 
 mod d3_noise_texture;
 pub use d3_noise_texture::D3NoiseTexture;
 
+#[cfg(not(target_arch = "wasm32"))]
+mod noise_export;
+
+mod material_graph;
+pub(crate) use material_graph::MaterialGraph;
+
+mod material_graph_editor;
+
 mod sphere_display;
 
+mod terrain_display;
+
+mod volume_display;
+
 mod texture_simulator;
 pub use texture_simulator::TextureSimulator;
 
+pub(crate) const BASIS_NAMES: [&str; 5] = ["Perlin", "Simplex", "Worley F1", "Worley F2", "Value"];
+
+/// 渐变的色标数上限，与 shader 中的数组长度一致
+pub(crate) const MAX_COLOR_STOPS: usize = 8;
+
+/// 节点图材质的类型值
+pub(crate) const GRAPH_MATERIAL: i32 = 4;
+
 #[repr(C)]
-#[derive(Default, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Default, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
 pub(crate) struct TexGeneratorParams {
     pub bg_color: [f32; 4],
     pub front_color: [f32; 4],
@@ -22,23 +45,104 @@ pub(crate) struct TexGeneratorParams {
     pub lacunarity: f32,
     pub gain: f32,
     pub ty: i32,
-    pub _padding: [i32; 3],
+    // 基础噪声：0 Perlin | 1 Simplex | 2 Worley F1 | 3 Worley F2 | 4 Value
+    // 在创建 shader 时插入，shader 中不使用
+    pub basis: i32,
+    // 域扭曲的迭代次数，0 表示不扭曲
+    pub warp_iterations: i32,
+    pub warp_strength: f32,
+    // 排列表及梯度表的种子，shader 中不使用
+    pub seed: u32,
+    // 多色标渐变，为 0 时只使用 bg_color 与 front_color
+    pub stop_count: i32,
+    // 每个色标的插值方式，各占 2 位
+    pub stop_modes: u32,
+    pub _padding: u32,
+    // rgb 为颜色，w 为位置
+    pub stops: [[f32; 4]; MAX_COLOR_STOPS],
 }
 
-pub(crate) fn is_the_same_color(lh: [f32; 4], rh: [f32; 4]) -> bool {
-    for i in 0..4 {
-        if !is_the_same_f32(lh[i], rh[i]) {
-            return false;
+impl TexGeneratorParams {
+    pub fn new(setting: &crate::NoiseSetting) -> Self {
+        let rgba = |c: [f32; 3]| [c[0], c[1], c[2], 1.0];
+        let mut params = Self {
+            bg_color: rgba(setting.back_color),
+            front_color: rgba(setting.front_color),
+            noise_scale: setting.noise_scale,
+            octave: setting.octave,
+            lacunarity: setting.lacunarity,
+            gain: setting.gain,
+            ty: setting.simu_ty.unwrap_or(0),
+            basis: setting.basis,
+            warp_iterations: setting.warp_iterations,
+            warp_strength: setting.warp_strength,
+            seed: setting.seed,
+            stop_count: 0,
+            stop_modes: 0,
+            _padding: 0,
+            stops: [[0.0; 4]; MAX_COLOR_STOPS],
+        };
+        for (i, stop) in setting.color_stops.iter().take(MAX_COLOR_STOPS).enumerate() {
+            params.stops[i] = [stop.color[0], stop.color[1], stop.color[2], stop.position];
+            params.stop_modes |= (stop.interpolation & 3) << (2 * i);
+            params.stop_count += 1;
         }
+        params
+    }
+}
+
+/// 插入到噪声 shader 中的函数：所选基础噪声的 `basis_noise` 及节点图材质的 `graph_color`
+///
+/// 用 uniform 在 shader 中分支选择基础噪声时，每一层 fbm 都会内联全部的基础噪声，编译非常慢
+pub(crate) fn material_code(setting: &crate::NoiseSetting) -> String {
+    let graph_code = if setting.simu_ty == Some(GRAPH_MATERIAL) {
+        setting.graph.to_wgsl()
+    } else {
+        String::from(
+            "fn graph_color(pos: vec3<f32>, time: f32) -> vec3<f32> {\n    return vec3<f32>(0.0);\n}\n",
+        )
+    };
+    format!(
+        "fn basis_noise(pos: vec3<f32>) -> f32 {{\n    return {};\n}}\n{graph_code}",
+        basis_expr(setting.basis, "pos")
+    )
+}
+
+/// 基础噪声在 pos 处的值，大致在 [-1, 1]
+pub(crate) fn basis_expr(basis: i32, pos: &str) -> String {
+    match basis {
+        1 => format!("simplex_noise({pos})"),
+        // 大致映射到 [-1, 1]
+        2 => format!("(worley_noise({pos}).x * 2.0 - 1.0)"),
+        3 => format!("(worley_noise({pos}).y * 1.5 - 1.0)"),
+        4 => format!("value_noise({pos})"),
+        _ => format!("noise({pos})"),
     }
-    true
 }
 
-pub(crate) fn is_the_same_f32(l: f32, r: f32) -> bool {
-    if (l - r).abs() > 0.00001 {
-        return false;
+pub(crate) fn create_noise_shader(
+    device: &wgpu::Device,
+    shader_name: &'static str,
+    code: &str,
+) -> wgpu::ShaderModule {
+    crate::insert_code_then_create(device, shader_name, Some(code), None)
+}
+
+/// 烘焙纹理时可平铺的方向
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct BakeUniform {
+    tile_axes: [f32; 3],
+    _padding: f32,
+}
+
+impl BakeUniform {
+    fn new(tile_axes: glam::BVec3) -> Self {
+        Self {
+            tile_axes: glam::Vec3::select(tile_axes, glam::Vec3::ONE, glam::Vec3::ZERO).into(),
+            _padding: 0.0,
+        }
     }
-    true
 }
 
 static PERMULATION: [i32; 512] = [
@@ -88,29 +192,106 @@ static GRADIENT: [[f32; 4]; 16] = [
     [0.0, -1.0, -1.0, 0.0],
 ];
 
-pub fn create_permulation_buf(device: &wgpu::Device) -> crate::util::BufferObj {
+/// 种子为 0 时使用经典的排列表，否则用种子打乱
+fn permulation_table(seed: u32) -> [i32; 512] {
+    let mut table = PERMULATION;
+    if seed != 0 {
+        shuffle(&mut table[..256], &mut SplitMix64(seed as u64));
+        table.copy_within(..256, 256);
+    }
+    table
+}
+
+pub(crate) fn gradient_data(seed: u32) -> [[f32; 4]; 16] {
+    let mut table = GRADIENT;
+    if seed != 0 {
+        shuffle(&mut table, &mut SplitMix64(!(seed as u64)));
+    }
+    table
+}
+
+/// 不使用 rand 的随机数生成器，保证同一个种子在各平台及各版本中得到相同的噪声
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+// Fisher–Yates
+fn shuffle<T>(list: &mut [T], rng: &mut SplitMix64) {
+    for i in (1..list.len()).rev() {
+        let j = (rng.next() % (i as u64 + 1)) as usize;
+        list.swap(i, j);
+    }
+}
+
+pub(crate) fn permulation_data(seed: u32) -> Vec<[i32; 4]> {
+    let table = permulation_table(seed);
     let mut list: Vec<[i32; 4]> = vec![];
     // column
     for y in 0..256 {
         // row
         for x in 0..256 {
             // hash coordinates for 6 of th 8 cube corner
-            let a = PERMULATION[x] + y;
-            let aa = PERMULATION[a as usize];
-            let ab = PERMULATION[a as usize + 1];
-            let b = PERMULATION[x + 1] + y;
-            let ba = PERMULATION[b as usize];
-            let bb = PERMULATION[b as usize + 1];
+            let a = table[x] + y;
+            let aa = table[a as usize];
+            let ab = table[a as usize + 1];
+            let b = table[x + 1] + y;
+            let ba = table[b as usize];
+            let bb = table[b as usize + 1];
             list.push([aa, ab, ba, bb]);
         }
     }
-    let mut buf = crate::util::BufferObj::create_storage_buffer(device, &list, None);
+    list
+}
+
+pub fn create_permulation_buf(device: &wgpu::Device, seed: u32) -> crate::util::BufferObj {
+    let mut buf =
+        crate::util::BufferObj::create_storage_buffer(device, &permulation_data(seed), None);
     buf.read_only = true;
     buf
 }
 
-pub fn create_gradient_buf(device: &wgpu::Device) -> crate::util::BufferObj {
-    let mut buf = crate::util::BufferObj::create_storage_buffer(device, &GRADIENT, None);
+pub fn create_gradient_buf(device: &wgpu::Device, seed: u32) -> crate::util::BufferObj {
+    let mut buf = crate::util::BufferObj::create_storage_buffer(device, &gradient_data(seed), None);
     buf.read_only = true;
     buf
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_zero_keeps_classic_tables() {
+        assert_eq!(permulation_table(0), PERMULATION);
+        assert_eq!(gradient_data(0), GRADIENT);
+    }
+
+    #[test]
+    fn same_seed_same_tables() {
+        assert_eq!(permulation_table(42), permulation_table(42));
+        assert_eq!(gradient_data(42), gradient_data(42));
+        assert_ne!(permulation_table(1), permulation_table(2));
+        assert_ne!(permulation_table(1), PERMULATION);
+        assert_ne!(gradient_data(1), gradient_data(2));
+    }
+
+    #[test]
+    fn permulation_table_is_mirrored() {
+        for seed in [0, 1, 12345, u32::MAX] {
+            let table = permulation_table(seed);
+            assert_eq!(table[..256], table[256..]);
+            // 前半部分是 0 ~ 255 的一个排列
+            let mut sorted = table[..256].to_vec();
+            sorted.sort();
+            assert!(sorted.iter().enumerate().all(|(i, v)| *v == i as i32));
+        }
+    }
+}
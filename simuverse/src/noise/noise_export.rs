@@ -0,0 +1,61 @@
+use super::{D2NoiseTexture, D3NoiseTexture, TexGeneratorParams};
+use crate::util::texture_readback::TextureReadback;
+use alloc::{format, string::String, vec::Vec};
+use std::path::{Path, PathBuf};
+
+/// 以当前的噪声参数烘焙纹理并保存为 PNG，返回保存的文件数
+///
+/// 3D 纹理逐层保存为 `<文件名>_<z>.png`
+pub(crate) fn bake_to_png(
+    app: &app_surface::AppSurface,
+    params: &TexGeneratorParams,
+    setting: &crate::NoiseSetting,
+) -> Result<usize, String> {
+    if setting.bake_path.is_empty() {
+        return Err(String::from("no output file"));
+    }
+    let path = Path::new(&setting.bake_path).with_extension("png");
+    let size = setting.bake_size;
+    let code = super::material_code(setting);
+    let limits = app.device.limits();
+    let (tex, max_size) = if setting.bake_dimension == 3 {
+        let max_size = limits.max_texture_dimension_3d;
+        let tex = (size <= max_size)
+            .then(|| D3NoiseTexture::create(app, params, &code, size, setting.tileable).tex);
+        (tex, max_size)
+    } else {
+        let max_size = limits.max_texture_dimension_2d;
+        let tex = (size <= max_size)
+            .then(|| D2NoiseTexture::create(app, params, &code, size, setting.tileable).tex);
+        (tex, max_size)
+    };
+    let Some(tex) = tex else {
+        return Err(format!("size {size} exceeds the device limit {max_size}"));
+    };
+
+    let mut encoder = app
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("noise export encoder"),
+        });
+    let layer_count = tex.tex.depth_or_array_layers();
+    let readbacks: Vec<TextureReadback> = (0..layer_count)
+        .map(|z| TextureReadback::new(&app.device, &mut encoder, &tex.tex, z))
+        .collect();
+    app.queue.submit(Some(encoder.finish()));
+
+    for (z, readback) in readbacks.into_iter().enumerate() {
+        let file = if layer_count == 1 {
+            path.clone()
+        } else {
+            slice_path(&path, z)
+        };
+        readback.save_png(&app.device, &file)?;
+    }
+    Ok(layer_count as usize)
+}
+
+fn slice_path(path: &Path, z: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{stem}_{z:03}.png"))
+}
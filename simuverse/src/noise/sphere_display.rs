@@ -1,17 +1,23 @@
 use crate::{
-    create_shader_module,
     geometries::Sphere,
     node::{BindGroupData, ViewNode, ViewNodeBuilder},
-    util::BufferObj,
+    noise::create_noise_shader,
+    util::{BufferObj, OrbitCamera},
 };
-use alloc::vec;
+use alloc::{string::String, vec};
 use app_surface::AppSurface;
 use wgpu::ShaderStages;
+use winit::event::MouseButton;
 
 pub struct SphereDisplay {
     gen_tex_node: ViewNode,
-    p_matrix: glam::Mat4,
-    mv_matrix: glam::Mat4,
+    // 当前 shader 中插入的函数
+    code: String,
+    pub camera: OrbitCamera,
+    // 自转的角度
+    spin: f32,
+    // 每帧动画时间的增量，暂停时为 0
+    pub time_step: f32,
     mvp_uniform: crate::MVPMatUniform,
     mvp_buf: BufferObj,
 }
@@ -22,32 +28,72 @@ impl SphereDisplay {
         uniform_buf: &BufferObj,
         permulation_buf: &BufferObj,
         gradient_buf: &BufferObj,
+        code: String,
     ) -> Self {
-        let (p_matrix, mut mv_matrix, _factor) = crate::util::matrix_helper::perspective_mvp(
-            glam::Vec2::new(app.config.width as f32, app.config.height as f32),
+        let viewport = glam::Vec2::new(app.config.width as f32, app.config.height as f32);
+        let (p_matrix, mv_matrix) = Self::get_matrices(viewport);
+        let camera = OrbitCamera::new(p_matrix, mv_matrix, viewport, MouseButton::Left);
+        let mvp_uniform = camera.mvp_uniform(glam::Mat4::IDENTITY);
+        let mvp_buf = BufferObj::create_uniform_buffer(&app.device, &mvp_uniform, Some("mvp_buf"));
+
+        let gen_tex_node = Self::create_node(
+            app,
+            &mvp_buf,
+            uniform_buf,
+            permulation_buf,
+            gradient_buf,
+            &code,
         );
-        let transelate = glam::Mat4::from_translation(glam::Vec3::new(0., 0., -1.));
-        mv_matrix *= transelate;
 
-        let normal: [[f32; 4]; 4] = mv_matrix.inverse().transpose().to_cols_array_2d();
-        let mvp_uniform = crate::MVPMatUniform {
-            mv: mv_matrix.to_cols_array_2d(),
-            proj: p_matrix.to_cols_array_2d(),
-            mvp: (p_matrix * mv_matrix).to_cols_array_2d(),
-            mv_no_rotation: mv_matrix.to_cols_array_2d(),
-            normal,
-            u_time: 0.0,
-            _padding: [0.0; 3],
-        };
-        let mvp_buf = BufferObj::create_uniform_buffer(&app.device, &mvp_uniform, Some("mvp_buf"));
+        Self {
+            gen_tex_node,
+            code,
+            camera,
+            spin: 0.0,
+            time_step: 0.016,
+            mvp_uniform,
+            mvp_buf,
+        }
+    }
 
-        let sphere_tex_shader = create_shader_module(&app.device, "noise/sphere_tex", None);
+    /// 基础噪声或节点图变化后重新创建 shader
+    pub fn set_code(
+        &mut self,
+        app: &AppSurface,
+        uniform_buf: &BufferObj,
+        permulation_buf: &BufferObj,
+        gradient_buf: &BufferObj,
+        code: String,
+    ) {
+        if code == self.code {
+            return;
+        }
+        self.gen_tex_node = Self::create_node(
+            app,
+            &self.mvp_buf,
+            uniform_buf,
+            permulation_buf,
+            gradient_buf,
+            &code,
+        );
+        self.code = code;
+    }
+
+    fn create_node(
+        app: &AppSurface,
+        mvp_buf: &BufferObj,
+        uniform_buf: &BufferObj,
+        permulation_buf: &BufferObj,
+        gradient_buf: &BufferObj,
+        code: &str,
+    ) -> ViewNode {
+        let sphere_tex_shader = create_noise_shader(&app.device, "noise/sphere_tex", code);
 
         let (vertices, indices) = Sphere::new(1.0, 50, 34).generate_vertices();
 
         // generate sphere textue
         let bg_data = BindGroupData {
-            uniforms: vec![&mvp_buf, uniform_buf],
+            uniforms: vec![mvp_buf, uniform_buf],
             storage_buffers: vec![permulation_buf, gradient_buf],
             visibilitys: vec![
                 ShaderStages::VERTEX | ShaderStages::FRAGMENT,
@@ -57,19 +103,22 @@ impl SphereDisplay {
             ],
             ..Default::default()
         };
-        let builder =
-            ViewNodeBuilder::<crate::util::vertex::PosNormalUv>::new(bg_data, &sphere_tex_shader)
-                .with_vertices_and_indices((vertices, indices))
-                .with_color_format(app.config.format);
-        let gen_tex_node = builder.build(&app.device);
+        ViewNodeBuilder::<crate::util::vertex::PosNormalUv>::new(bg_data, &sphere_tex_shader)
+            .with_vertices_and_indices((vertices, indices))
+            .with_color_format(app.config.format)
+            .build(&app.device)
+    }
 
-        Self {
-            gen_tex_node,
-            p_matrix,
-            mv_matrix,
-            mvp_uniform,
-            mvp_buf,
-        }
+    pub fn resize(&mut self, app: &AppSurface) {
+        let viewport = glam::Vec2::new(app.config.width as f32, app.config.height as f32);
+        let (p_matrix, mv_matrix) = Self::get_matrices(viewport);
+        self.camera.resize(p_matrix, mv_matrix, viewport);
+    }
+
+    fn get_matrices(viewport: glam::Vec2) -> (glam::Mat4, glam::Mat4) {
+        let (p_matrix, mv_matrix, _factor) = crate::util::matrix_helper::perspective_mvp(viewport);
+        let transelate = glam::Mat4::from_translation(glam::Vec3::new(0., 0., -1.));
+        (p_matrix, mv_matrix * transelate)
     }
 
     pub fn gen_texture(&self, _app: &AppSurface) {
@@ -91,10 +140,12 @@ impl SphereDisplay {
         app: &AppSurface,
         rpass: &mut wgpu::RenderPass<'b>,
     ) {
-        self.mv_matrix *= glam::Mat4::from_rotation_y(0.005);
-        self.mvp_uniform.mv = self.mv_matrix.to_cols_array_2d();
-        self.mvp_uniform.mvp = (self.p_matrix * self.mv_matrix).to_cols_array_2d();
-        self.mvp_uniform.normal = self.mv_matrix.inverse().transpose().to_cols_array_2d();
+        self.spin += 0.005;
+        let u_time = self.mvp_uniform.u_time;
+        self.mvp_uniform = self
+            .camera
+            .mvp_uniform(glam::Mat4::from_rotation_y(self.spin));
+        self.mvp_uniform.u_time = u_time;
         app.queue.write_buffer(
             &self.mvp_buf.buffer,
             0,
@@ -102,6 +153,6 @@ impl SphereDisplay {
         );
         self.gen_tex_node.draw_by_pass(rpass);
 
-        self.mvp_uniform.u_time += 0.016;
+        self.mvp_uniform.u_time += self.time_step;
     }
 }
@@ -0,0 +1,201 @@
+use crate::{
+    geometries::{Plane, Sphere},
+    node::{BindGroupData, ViewNode, ViewNodeBuilder},
+    noise::create_noise_shader,
+    util::{
+        BufferObj, OrbitCamera,
+        vertex::{PosNormalUv, PosUv},
+    },
+};
+use alloc::{string::String, vec};
+use app_surface::AppSurface;
+use wgpu::ShaderStages;
+use winit::event::MouseButton;
+
+#[repr(C)]
+#[derive(Default, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct TerrainParams {
+    height_scale: f32,
+    sea_level: f32,
+    // 0 平面地形 | 1 星球
+    planet: u32,
+    _padding: u32,
+}
+
+/// 以 fBm 噪声为高度场的地形：平面上沿 z 轴位移，或星球表面沿法线位移
+///
+/// 位移、法线及按高度着色都在 shader 中计算，与球面材质共用 TexGeneratorParams
+pub struct TerrainDisplay {
+    display_node: ViewNode,
+    // 当前 shader 中插入的函数
+    code: String,
+    planet: bool,
+    pub camera: OrbitCamera,
+    // 星球自转的角度
+    spin: f32,
+    // 每帧动画时间的增量，暂停时为 0
+    pub time_step: f32,
+    mvp_uniform: crate::MVPMatUniform,
+    mvp_buf: BufferObj,
+    terrain_data: TerrainParams,
+    terrain_buf: BufferObj,
+}
+
+impl TerrainDisplay {
+    pub fn new(
+        app: &AppSurface,
+        uniform_buf: &BufferObj,
+        permulation_buf: &BufferObj,
+        gradient_buf: &BufferObj,
+        code: String,
+        planet: bool,
+    ) -> Self {
+        let viewport = glam::Vec2::new(app.config.width as f32, app.config.height as f32);
+        let (p_matrix, mv_matrix) = Self::get_matrices(viewport);
+        let camera = OrbitCamera::new(p_matrix, mv_matrix, viewport, MouseButton::Left);
+        let mvp_uniform = camera.mvp_uniform(glam::Mat4::IDENTITY);
+        let mvp_buf = BufferObj::create_uniform_buffer(&app.device, &mvp_uniform, Some("mvp_buf"));
+
+        let terrain_data = TerrainParams {
+            planet: planet as u32,
+            ..Default::default()
+        };
+        let terrain_buf =
+            BufferObj::create_uniform_buffer(&app.device, &terrain_data, Some("terrain_buf"));
+
+        let display_node = Self::create_node(
+            app,
+            [&mvp_buf, uniform_buf, &terrain_buf],
+            [permulation_buf, gradient_buf],
+            &code,
+            planet,
+        );
+
+        Self {
+            display_node,
+            code,
+            planet,
+            camera,
+            spin: 0.0,
+            time_step: 0.016,
+            mvp_uniform,
+            mvp_buf,
+            terrain_data,
+            terrain_buf,
+        }
+    }
+
+    pub fn is_planet(&self) -> bool {
+        self.planet
+    }
+
+    pub fn set_terrain(&mut self, app: &AppSurface, height_scale: f32, sea_level: f32) {
+        let data = TerrainParams {
+            height_scale,
+            sea_level,
+            ..self.terrain_data
+        };
+        if data != self.terrain_data {
+            self.terrain_data = data;
+            app.queue.write_buffer(
+                &self.terrain_buf.buffer,
+                0,
+                bytemuck::bytes_of(&self.terrain_data),
+            );
+        }
+    }
+
+    /// 基础噪声或节点图变化后重新创建 shader
+    pub fn set_code(
+        &mut self,
+        app: &AppSurface,
+        uniform_buf: &BufferObj,
+        permulation_buf: &BufferObj,
+        gradient_buf: &BufferObj,
+        code: String,
+    ) {
+        if code == self.code {
+            return;
+        }
+        self.display_node = Self::create_node(
+            app,
+            [&self.mvp_buf, uniform_buf, &self.terrain_buf],
+            [permulation_buf, gradient_buf],
+            &code,
+            self.planet,
+        );
+        self.code = code;
+    }
+
+    fn create_node(
+        app: &AppSurface,
+        uniforms: [&BufferObj; 3],
+        storage_buffers: [&BufferObj; 2],
+        code: &str,
+        planet: bool,
+    ) -> ViewNode {
+        let shader = create_noise_shader(&app.device, "noise/terrain", code);
+
+        // 高度及法线在顶点着色器中计算，噪声相关的缓冲区两个阶段都可见
+        let bg_data = BindGroupData {
+            uniforms: uniforms.to_vec(),
+            storage_buffers: storage_buffers.to_vec(),
+            visibilitys: vec![ShaderStages::VERTEX | ShaderStages::FRAGMENT; 5],
+            ..Default::default()
+        };
+        // 两种顶点的 location(0) 都是位置，shader 只用到位置
+        if planet {
+            ViewNodeBuilder::<PosNormalUv>::new(bg_data, &shader)
+                .with_vertices_and_indices(Sphere::new(1.0, 192, 128).generate_vertices())
+                .with_color_format(app.config.format)
+                .build(&app.device)
+        } else {
+            ViewNodeBuilder::<PosUv>::new(bg_data, &shader)
+                .with_vertices_and_indices(Plane::new(192, 192).generate_vertices())
+                .with_color_format(app.config.format)
+                .build(&app.device)
+        }
+    }
+
+    pub fn resize(&mut self, app: &AppSurface) {
+        let viewport = glam::Vec2::new(app.config.width as f32, app.config.height as f32);
+        let (p_matrix, mv_matrix) = Self::get_matrices(viewport);
+        self.camera.resize(p_matrix, mv_matrix, viewport);
+    }
+
+    fn get_matrices(viewport: glam::Vec2) -> (glam::Mat4, glam::Mat4) {
+        let (p_matrix, mv_matrix, _factor) = crate::util::matrix_helper::perspective_mvp(viewport);
+        let transelate = glam::Mat4::from_translation(glam::Vec3::new(0., 0., -1.));
+        (p_matrix, mv_matrix * transelate)
+    }
+
+    fn model_matrix(&self) -> glam::Mat4 {
+        if self.planet {
+            glam::Mat4::from_rotation_y(self.spin)
+        } else {
+            // 平面向后倾斜，看起来像地面
+            glam::Mat4::from_rotation_x(-1.0)
+        }
+    }
+
+    pub fn draw_by_pass<'a, 'b: 'a>(
+        &'b mut self,
+        app: &AppSurface,
+        rpass: &mut wgpu::RenderPass<'b>,
+    ) {
+        if self.planet {
+            self.spin += 0.003;
+        }
+        let u_time = self.mvp_uniform.u_time;
+        self.mvp_uniform = self.camera.mvp_uniform(self.model_matrix());
+        self.mvp_uniform.u_time = u_time;
+        app.queue.write_buffer(
+            &self.mvp_buf.buffer,
+            0,
+            bytemuck::bytes_of(&self.mvp_uniform),
+        );
+        self.display_node.draw_by_pass(rpass);
+
+        self.mvp_uniform.u_time += self.time_step;
+    }
+}
@@ -4,82 +4,201 @@ use crate::{
     util::BufferObj,
 };
 
-use super::sphere_display::SphereDisplay;
+use super::{
+    sphere_display::SphereDisplay, terrain_display::TerrainDisplay, volume_display::VolumeDisplay,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use alloc::format;
+use app_surface::AppSurface;
+use winit::{
+    dpi::PhysicalPosition,
+    event::{ElementState, MouseButton, MouseScrollDelta, TouchPhase},
+};
 
 pub struct TextureSimulator {
     uniform_data: super::TexGeneratorParams,
     uniform_buf: BufferObj,
+    permulation_buf: BufferObj,
+    gradient_buf: BufferObj,
     sphere: SphereDisplay,
+    // 地形及星球视图，显示球面材质时为 None
+    terrain: Option<TerrainDisplay>,
+    // 3D 纹理的体积视图
+    volume: Option<VolumeDisplay>,
     regenerate_tex: bool,
 }
 
 impl TextureSimulator {
-    pub fn new(app: &app_surface::AppSurface) -> TextureSimulator {
+    pub fn new(app: &app_surface::AppSurface, setting: &crate::NoiseSetting) -> TextureSimulator {
         let uniform_data = super::TexGeneratorParams::default();
         let uniform_buf = BufferObj::create_uniform_buffer(&app.device, &uniform_data, None);
-        let permulation_buf = create_permulation_buf(&app.device);
-        let gradient_buf = create_gradient_buf(&app.device);
+        let permulation_buf = create_permulation_buf(&app.device, uniform_data.seed);
+        let gradient_buf = create_gradient_buf(&app.device, uniform_data.seed);
 
-        let sphere = SphereDisplay::new(app, &uniform_buf, &permulation_buf, &gradient_buf);
+        let sphere = SphereDisplay::new(
+            app,
+            &uniform_buf,
+            &permulation_buf,
+            &gradient_buf,
+            super::material_code(setting),
+        );
 
         TextureSimulator {
             uniform_data,
             uniform_buf,
+            permulation_buf,
+            gradient_buf,
             sphere,
+            terrain: None,
+            volume: None,
             regenerate_tex: true,
         }
     }
+
+    /// 当前视图的相机
+    fn camera(&mut self) -> &mut crate::util::OrbitCamera {
+        if let Some(terrain) = self.terrain.as_mut() {
+            &mut terrain.camera
+        } else if let Some(volume) = self.volume.as_mut() {
+            &mut volume.camera
+        } else {
+            &mut self.sphere.camera
+        }
+    }
 }
 
 impl Simulator for TextureSimulator {
+    fn mouse_input(&mut self, _app: &AppSurface, state: &ElementState, button: &MouseButton) {
+        self.camera().mouse_input(state, button);
+    }
+
+    fn mouse_wheel(
+        &mut self,
+        _app: &AppSurface,
+        delta: &MouseScrollDelta,
+        _touch_phase: &TouchPhase,
+    ) {
+        self.camera().mouse_wheel(delta);
+    }
+
+    fn cursor_moved(&mut self, _app: &AppSurface, position: PhysicalPosition<f64>) {
+        self.camera()
+            .cursor_moved(glam::Vec2::new(position.x as f32, position.y as f32));
+    }
+
+    fn resize(&mut self, app: &AppSurface) -> bool {
+        self.sphere.resize(app);
+        if let Some(terrain) = self.terrain.as_mut() {
+            terrain.resize(app);
+        }
+        if let Some(volume) = self.volume.as_mut() {
+            volume.resize(app);
+        }
+        true
+    }
+
     fn update_by(
         &mut self,
         app: &app_surface::AppSurface,
         control_panel: &mut crate::ControlPanel,
     ) {
-        use super::{is_the_same_color, is_the_same_f32};
+        #[cfg(not(target_arch = "wasm32"))]
+        if control_panel.noise_setting.bake_requested {
+            let setting = &mut control_panel.noise_setting;
+            setting.bake_requested = false;
+            let params = super::TexGeneratorParams::new(setting);
+            setting.bake_status = match super::noise_export::bake_to_png(app, &params, setting) {
+                Ok(count) => format!("Saved {count} file(s) to {}", setting.bake_path),
+                Err(e) => {
+                    log::warn!("failed to export noise texture: {e}");
+                    format!("Export failed: {e}")
+                }
+            };
+        }
 
         let setting = &control_panel.noise_setting;
-        let bg_color = [
-            setting.back_color[0],
-            setting.back_color[1],
-            setting.back_color[2],
-            1.0,
-        ];
-        let front_color = [
-            setting.front_color[0],
-            setting.front_color[1],
-            setting.front_color[2],
-            1.0,
-        ];
-        let mut is_changed = false;
-        if !is_the_same_color(self.uniform_data.bg_color, bg_color)
-            || !is_the_same_color(self.uniform_data.front_color, front_color)
-            || !is_the_same_f32(self.uniform_data.noise_scale, setting.noise_scale)
-            || !is_the_same_f32(self.uniform_data.lacunarity, setting.lacunarity)
-            || !is_the_same_f32(self.uniform_data.gain, setting.gain)
-            || self.uniform_data.octave != setting.octave
-            || self.uniform_data.ty != setting.simu_ty.unwrap()
-        {
-            is_changed = true;
+        let params = super::TexGeneratorParams::new(setting);
+        if params != self.uniform_data {
+            // 种子变化时重建排列表及梯度表
+            if self.uniform_data.seed != params.seed {
+                app.queue.write_buffer(
+                    &self.permulation_buf.buffer,
+                    0,
+                    bytemuck::cast_slice(&super::permulation_data(params.seed)),
+                );
+                app.queue.write_buffer(
+                    &self.gradient_buf.buffer,
+                    0,
+                    bytemuck::cast_slice(&super::gradient_data(params.seed)),
+                );
+            }
+            self.uniform_data = params;
+            app.queue.write_buffer(
+                &self.uniform_buf.buffer,
+                0,
+                bytemuck::bytes_of(&self.uniform_data),
+            );
         }
 
-        if !is_changed {
-            return;
+        let time_step = if setting.paused {
+            0.0
+        } else {
+            0.016 * setting.time_speed
+        };
+        // 基础噪声及节点图不在 uniform 中，由生成的代码判断是否需要重新创建 shader
+        let code = super::material_code(setting);
+
+        if setting.view == 3 {
+            match self.volume.as_mut() {
+                Some(volume) => volume.update(app, &params, code.clone(), setting),
+                None => {
+                    let mut volume = VolumeDisplay::new(app, &params, code.clone());
+                    volume.update(app, &params, code.clone(), setting);
+                    self.volume = Some(volume);
+                }
+            }
+        } else {
+            self.volume = None;
         }
 
-        self.uniform_data.ty = setting.simu_ty.unwrap();
-        self.uniform_data.bg_color = bg_color;
-        self.uniform_data.front_color = front_color;
-        self.uniform_data.noise_scale = setting.noise_scale;
-        self.uniform_data.octave = setting.octave;
-        self.uniform_data.lacunarity = setting.lacunarity;
-        self.uniform_data.gain = setting.gain;
-        app.queue.write_buffer(
-            &self.uniform_buf.buffer,
-            0,
-            bytemuck::bytes_of(&self.uniform_data),
-        );
+        let planet = setting.view == 2;
+        if setting.view == 0 || setting.view == 3 {
+            self.terrain = None;
+        } else if self
+            .terrain
+            .as_ref()
+            .is_none_or(|t| t.is_planet() != planet)
+        {
+            self.terrain = Some(TerrainDisplay::new(
+                app,
+                &self.uniform_buf,
+                &self.permulation_buf,
+                &self.gradient_buf,
+                code.clone(),
+                planet,
+            ));
+        }
+
+        if let Some(terrain) = self.terrain.as_mut() {
+            terrain.time_step = time_step;
+            terrain.set_terrain(app, setting.height_scale, setting.sea_level);
+            terrain.set_code(
+                app,
+                &self.uniform_buf,
+                &self.permulation_buf,
+                &self.gradient_buf,
+                code,
+            );
+        } else {
+            self.sphere.time_step = time_step;
+            self.sphere.set_code(
+                app,
+                &self.uniform_buf,
+                &self.permulation_buf,
+                &self.gradient_buf,
+                code,
+            );
+        }
     }
 
     fn update_workgroup_count(
@@ -101,6 +220,12 @@ impl Simulator for TextureSimulator {
             self.regenerate_tex = false;
             self.sphere.gen_texture(app);
         }
-        self.sphere.draw_by_pass(app, rpass);
+        if let Some(terrain) = self.terrain.as_mut() {
+            terrain.draw_by_pass(app, rpass);
+        } else if let Some(volume) = self.volume.as_mut() {
+            volume.draw_by_pass(app, rpass);
+        } else {
+            self.sphere.draw_by_pass(app, rpass);
+        }
     }
 }
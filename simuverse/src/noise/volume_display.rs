@@ -0,0 +1,146 @@
+use super::{D3NoiseTexture, TexGeneratorParams};
+use crate::{
+    node::{TransferStop, VolumeNode},
+    util::OrbitCamera,
+};
+use alloc::{string::String, vec::Vec};
+use app_surface::AppSurface;
+use winit::event::MouseButton;
+
+/// 体积纹理的边长
+const VOLUME_SIZE: u32 = 64;
+
+/// 以光线步进显示烘焙的 3D 噪声纹理，如云、烟雾
+///
+/// 材质的亮度作为密度，经传递函数映射为颜色及不透明度
+pub struct VolumeDisplay {
+    volume: D3NoiseTexture,
+    volume_node: VolumeNode,
+    // 烘焙当前纹理所用的参数及插入的函数
+    params: TexGeneratorParams,
+    code: String,
+    transfer: Vec<TransferStop>,
+    pub camera: OrbitCamera,
+    spin: f32,
+}
+
+impl VolumeDisplay {
+    pub fn new(app: &AppSurface, params: &TexGeneratorParams, code: String) -> Self {
+        let viewport = glam::Vec2::new(app.config.width as f32, app.config.height as f32);
+        let (p_matrix, mv_matrix) = Self::get_matrices(viewport);
+        let camera = OrbitCamera::new(p_matrix, mv_matrix, viewport, MouseButton::Left);
+
+        let volume = D3NoiseTexture::create(app, params, &code, VOLUME_SIZE, false);
+        let volume_node = VolumeNode::new(app, &volume.tex, [0.2126, 0.7152, 0.0722, 0.0]);
+
+        Self {
+            volume,
+            volume_node,
+            params: *params,
+            code,
+            transfer: Vec::new(),
+            camera,
+            spin: 0.0,
+        }
+    }
+
+    /// 材质参数或插入的函数变化后重新烘焙
+    pub fn update(
+        &mut self,
+        app: &AppSurface,
+        params: &TexGeneratorParams,
+        code: String,
+        setting: &crate::NoiseSetting,
+    ) {
+        if *params != self.params || code != self.code {
+            self.volume = D3NoiseTexture::create(app, params, &code, VOLUME_SIZE, false);
+            self.volume_node.set_volume(app, &self.volume.tex);
+            self.params = *params;
+            self.code = code;
+        }
+        self.volume_node
+            .set_density(app, setting.density_scale, setting.volume_steps);
+
+        let transfer = transfer_function(setting);
+        if transfer != self.transfer {
+            self.volume_node.set_transfer_function(app, &transfer);
+            self.transfer = transfer;
+        }
+    }
+
+    pub fn resize(&mut self, app: &AppSurface) {
+        let viewport = glam::Vec2::new(app.config.width as f32, app.config.height as f32);
+        let (p_matrix, mv_matrix) = Self::get_matrices(viewport);
+        self.camera.resize(p_matrix, mv_matrix, viewport);
+    }
+
+    fn get_matrices(viewport: glam::Vec2) -> (glam::Mat4, glam::Mat4) {
+        let (p_matrix, mv_matrix, _factor) = crate::util::matrix_helper::perspective_mvp(viewport);
+        let transelate = glam::Mat4::from_translation(glam::Vec3::new(0., 0., -1.5));
+        (p_matrix, mv_matrix * transelate)
+    }
+
+    pub fn draw_by_pass<'a, 'b: 'a>(
+        &'b mut self,
+        app: &AppSurface,
+        rpass: &mut wgpu::RenderPass<'b>,
+    ) {
+        self.spin += 0.002;
+        let mvp = self
+            .camera
+            .mvp_uniform(glam::Mat4::from_rotation_x(0.4) * glam::Mat4::from_rotation_y(self.spin));
+        self.volume_node.draw_by_pass(app, rpass, &mvp);
+    }
+}
+
+/// 颜色取自渐变（没有色标时为 color0 到 color1），不透明度在 opacity_low 与 opacity_high 之间线性增加
+fn transfer_function(setting: &crate::NoiseSetting) -> Vec<TransferStop> {
+    let colors: Vec<(f32, [f32; 3])> = if setting.color_stops.is_empty() {
+        Vec::from([(0.0, setting.back_color), (1.0, setting.front_color)])
+    } else {
+        setting
+            .color_stops
+            .iter()
+            .map(|stop| (stop.position, stop.color))
+            .collect()
+    };
+    let (low, high) = (
+        setting.opacity_low,
+        setting.opacity_high.max(setting.opacity_low),
+    );
+
+    // 颜色与不透明度都是分段线性的，在所有的分段点上取值即可
+    let mut positions: Vec<f32> = colors.iter().map(|(position, _)| *position).collect();
+    positions.extend([0.0, low, high, 1.0]);
+    positions.sort_by(f32::total_cmp);
+    positions.dedup();
+    positions
+        .into_iter()
+        .map(|x| {
+            let rgb = lerp_colors(&colors, x);
+            let alpha = if high > low {
+                ((x - low) / (high - low)).clamp(0.0, 1.0)
+            } else if x >= high {
+                1.0
+            } else {
+                0.0
+            };
+            TransferStop {
+                position: x,
+                color: [rgb[0], rgb[1], rgb[2], alpha],
+            }
+        })
+        .collect()
+}
+
+fn lerp_colors(colors: &[(f32, [f32; 3])], x: f32) -> [f32; 3] {
+    match colors.iter().position(|(position, _)| *position >= x) {
+        None => colors[colors.len() - 1].1,
+        Some(0) => colors[0].1,
+        Some(index) => {
+            let ((p0, c0), (p1, c1)) = (colors[index - 1], colors[index]);
+            let w = (x - p0) / (p1 - p0).max(0.00001);
+            core::array::from_fn(|c| c0[c] + (c1[c] - c0[c]) * w)
+        }
+    }
+}
@@ -2,36 +2,290 @@ use crate::node::{BindGroupData, ComputeNode, ViewNode, ViewNodeBuilder};
 use crate::util::AnyTexture;
 use crate::util::{BufferObj, vertex::PosParticleIndex};
 
-use super::{ClothFabric, ClothUniform, MeshColoringObj};
+use super::cloth_fabric::ParticleBufferObj;
+use super::cloth_shading;
+use super::cloth_tearing::{self, TORN_LIST_SIZE, TearReadback};
+use super::colliders::ColliderDisplay;
+use super::{
+    BinUniform, ClothFabric, ClothShadingUniform, ClothUniform, ColliderUniform, GrabState,
+    GrabUniform, WindUniform,
+};
 
 use alloc::{vec, vec::Vec};
 use app_surface::AppSurface;
 
+// 风力强度为 1 时的风速
+const MAX_WIND_SPEED: f32 = 6.0;
+
 pub struct Cloth {
     mvp_uniform_data: crate::MVPMatUniform,
-    mvp_buf: BufferObj,
     cloth_uniform_data: ClothUniform,
-    cloth_uniform_buf: BufferObj,
+    buffers: ClothBuffers,
+    // 粒子的初始状态及网格、约束，用于重置、编辑固定点及撕裂
+    fabric: ClothFabric,
+    texture: AnyTexture,
+    sampler: wgpu::Sampler,
+
+    // 固定粒子的左右摆动
+    swing_x: f32,
+    swing_dir: f32,
+    swing_start_x: f32,
+    // 暂停模拟
+    pub paused: bool,
+
+    // 鼠标抓取
+    grab_uniform_data: GrabUniform,
+    pick_node: ComputeNode,
+    pick_resolve_node: ComputeNode,
+    attach_solver: ComputeNode,
+    is_picking: bool,
+    is_dragging: bool,
+
+    // 碰撞
+    particle_spacing: f32,
+    collider_uniform_data: ColliderUniform,
+    collision_solver: ComputeNode,
+    collider_display: ColliderDisplay,
+    // 自碰撞：每帧重建空间哈希，然后在每次迭代中求解
+    hash_clear_node: ComputeNode,
+    hash_insert_node: ComputeNode,
+    self_collision_solver: ComputeNode,
 
-    stretch_mesh_coloring: Vec<MeshColoringObj>,
-    bend_mesh_coloring: Vec<MeshColoringObj>,
+    // 撕裂
+    tear_readback: TearReadback,
+    // 网格已被撕裂，重置时需要重新生成布料
+    pub torn: bool,
 
     // 外力
     external_force_node: ComputeNode,
+    // 风与空气动力
+    wind_uniform_data: WindUniform,
+    wind_node: ComputeNode,
 
     // 预测位置并重置约束的 lambda 等参数
     predict_and_reset: ComputeNode,
     stretch_solver: ComputeNode,
     bend_solver: ComputeNode,
+
+    // 每次求解后重新计算顶点法线
+    normal_node: ComputeNode,
+    shading_uniform_data: ClothShadingUniform,
     display_node: ViewNode,
+    // 网格线
+    wireframe_node: ViewNode,
+    show_mesh: bool,
     frame_count: usize,
     // 迭代次数
     pbd_iter_count: usize,
     delta_time: f32,
 }
 
+/// 布料的计算及渲染节点所绑定的缓冲区
+struct ClothBuffers {
+    particle_num: u32,
+    triangle_num: u32,
+    mvp_buf: BufferObj,
+    cloth_uniform_buf: BufferObj,
+    shading_uniform_buf: BufferObj,
+    // 撕裂分裂出新粒子时，与粒子数相关的缓冲区需要重新分配
+    particle_buf: BufferObj,
+    normal_buf: BufferObj,
+    self_collision_buf: BufferObj,
+    rest_pos_buf: BufferObj,
+    triangle_offsets_buf: BufferObj,
+    // 撕裂只会移除约束、替换索引，以下缓冲区原位更新
+    index_buf: BufferObj,
+    triangle_list_buf: BufferObj,
+    constraint_buf: BufferObj,
+    bend_constraints_buf: BufferObj,
+    stretch_coloring_buf: BufferObj,
+    bend_coloring_buf: BufferObj,
+    torn_buf: BufferObj,
+    swing_buf: BufferObj,
+    grab_uniform_buf: BufferObj,
+    grab_state_buf: BufferObj,
+    wind_uniform_buf: BufferObj,
+    permulation_buf: BufferObj,
+    gradient_buf: BufferObj,
+    collider_uniform_buf: BufferObj,
+    bin_uniform_buf: BufferObj,
+    bin_counts_buf: BufferObj,
+    bin_entries_buf: BufferObj,
+}
+
+/// 绑定了粒子缓冲区的计算及渲染节点
+#[derive(Clone, Copy)]
+enum ClothPass {
+    ExternalForce,
+    Predict,
+    Stretch,
+    Bend,
+    Pick,
+    // 法线及抓取结果共用
+    Normal,
+    Attach,
+    Wind,
+    Collision,
+    HashInsert,
+    SelfCollision,
+    Wireframe,
+}
+
+impl ClothBuffers {
+    /// 创建节点与重建绑定组共用同一份绑定数据
+    fn bind_group_data(&self, pass: ClothPass) -> BindGroupData<'_> {
+        let particle_workgroup = (self.particle_num.div_ceil(64), 1, 1);
+        match pass {
+            ClothPass::ExternalForce => BindGroupData {
+                workgroup_count: particle_workgroup,
+                uniforms: vec![&self.cloth_uniform_buf],
+                storage_buffers: vec![&self.swing_buf, &self.particle_buf],
+                ..Default::default()
+            },
+            ClothPass::Predict => BindGroupData {
+                workgroup_count: (self.particle_num.div_ceil(32), 1, 1),
+                uniforms: vec![&self.cloth_uniform_buf],
+                storage_buffers: vec![&self.particle_buf, &self.constraint_buf],
+                ..Default::default()
+            },
+            ClothPass::Stretch => BindGroupData {
+                uniforms: vec![&self.cloth_uniform_buf],
+                dynamic_uniforms: vec![&self.stretch_coloring_buf],
+                storage_buffers: vec![&self.particle_buf, &self.constraint_buf, &self.torn_buf],
+                ..Default::default()
+            },
+            ClothPass::Bend => BindGroupData {
+                uniforms: vec![&self.cloth_uniform_buf],
+                dynamic_uniforms: vec![&self.bend_coloring_buf],
+                storage_buffers: vec![&self.particle_buf, &self.bend_constraints_buf],
+                ..Default::default()
+            },
+            ClothPass::Pick => BindGroupData {
+                workgroup_count: (self.triangle_num.div_ceil(64), 1, 1),
+                uniforms: vec![&self.grab_uniform_buf],
+                storage_buffers: vec![&self.particle_buf, &self.index_buf, &self.grab_state_buf],
+                ..Default::default()
+            },
+            ClothPass::Normal => BindGroupData {
+                workgroup_count: particle_workgroup,
+                storage_buffers: vec![
+                    &self.particle_buf,
+                    &self.index_buf,
+                    &self.triangle_offsets_buf,
+                    &self.triangle_list_buf,
+                    &self.normal_buf,
+                ],
+                ..Default::default()
+            },
+            ClothPass::Attach => BindGroupData {
+                workgroup_count: (1, 1, 1),
+                uniforms: vec![&self.cloth_uniform_buf, &self.grab_uniform_buf],
+                storage_buffers: vec![&self.particle_buf, &self.grab_state_buf],
+                ..Default::default()
+            },
+            ClothPass::Wind => BindGroupData {
+                workgroup_count: particle_workgroup,
+                uniforms: vec![&self.cloth_uniform_buf, &self.wind_uniform_buf],
+                storage_buffers: vec![
+                    &self.particle_buf,
+                    &self.permulation_buf,
+                    &self.gradient_buf,
+                ],
+                ..Default::default()
+            },
+            ClothPass::Collision => BindGroupData {
+                workgroup_count: particle_workgroup,
+                uniforms: vec![&self.cloth_uniform_buf, &self.collider_uniform_buf],
+                storage_buffers: vec![&self.particle_buf, &self.self_collision_buf],
+                ..Default::default()
+            },
+            ClothPass::HashInsert => BindGroupData {
+                workgroup_count: particle_workgroup,
+                uniforms: vec![&self.bin_uniform_buf],
+                storage_buffers: vec![
+                    &self.particle_buf,
+                    &self.bin_counts_buf,
+                    &self.bin_entries_buf,
+                ],
+                ..Default::default()
+            },
+            ClothPass::SelfCollision => BindGroupData {
+                workgroup_count: particle_workgroup,
+                uniforms: vec![&self.collider_uniform_buf, &self.bin_uniform_buf],
+                storage_buffers: vec![
+                    &self.particle_buf,
+                    &self.bin_counts_buf,
+                    &self.bin_entries_buf,
+                    &self.self_collision_buf,
+                    &self.rest_pos_buf,
+                ],
+                ..Default::default()
+            },
+            ClothPass::Wireframe => BindGroupData {
+                uniforms: vec![&self.mvp_buf, &self.cloth_uniform_buf],
+                storage_buffers: vec![&self.particle_buf],
+                visibilitys: vec![wgpu::ShaderStages::VERTEX; 3],
+                ..Default::default()
+            },
+        }
+    }
+
+    fn display_bind_group_data<'a>(
+        &'a self,
+        texture: &'a AnyTexture,
+        sampler: &'a wgpu::Sampler,
+    ) -> BindGroupData<'a> {
+        BindGroupData {
+            uniforms: vec![
+                &self.mvp_buf,
+                &self.cloth_uniform_buf,
+                &self.shading_uniform_buf,
+            ],
+            storage_buffers: vec![&self.particle_buf, &self.normal_buf],
+            inout_tv: vec![(texture, None)],
+            samplers: vec![sampler],
+            visibilitys: vec![
+                wgpu::ShaderStages::VERTEX,
+                wgpu::ShaderStages::VERTEX,
+                wgpu::ShaderStages::FRAGMENT,
+                wgpu::ShaderStages::VERTEX,
+                wgpu::ShaderStages::VERTEX,
+                wgpu::ShaderStages::FRAGMENT,
+                wgpu::ShaderStages::FRAGMENT,
+            ],
+            ..Default::default()
+        }
+    }
+
+    /// 着色分组数据按动态 uniform 的对齐写入，弯曲约束每次迭代使用不同的 invert_iter
+    fn write_colorings(&self, app_view: &AppSurface, fabric: &ClothFabric, pbd_iter_count: usize) {
+        let dynamic_offset =
+            app_view.device.limits().min_uniform_buffer_offset_alignment as wgpu::BufferAddress;
+        let mut offset = 0;
+        for mc in fabric.stretch_constraints.0.iter() {
+            app_view.queue.write_buffer(
+                &self.stretch_coloring_buf.buffer,
+                offset,
+                bytemuck::cast_slice(&mc.get_push_constants_data()),
+            );
+            offset += dynamic_offset;
+        }
+        offset = 0;
+        for i in 0..pbd_iter_count {
+            for mc in fabric.bend_constraints.0.iter() {
+                app_view.queue.write_buffer(
+                    &self.bend_coloring_buf.buffer,
+                    offset,
+                    bytemuck::bytes_of(&mc.get_bending_dynamic_uniform(i as i32)),
+                );
+                offset += dynamic_offset;
+            }
+        }
+    }
+}
+
 impl Cloth {
-    pub fn new(app_view: &AppSurface, fabric: ClothFabric, _texture: Option<&AnyTexture>) -> Self {
+    pub fn new(app_view: &AppSurface, fabric: ClothFabric, texture: &AnyTexture) -> Self {
         let viewport_size =
             glam::Vec2::new(app_view.config.width as f32, app_view.config.height as f32);
         let mvp_uniform_data = Self::get_mvp_uniform_data(viewport_size);
@@ -57,6 +311,7 @@ impl Cloth {
             compliance: 0.0000000016 / (delta_time * delta_time),
             stiffness: 0.05,
             dt: delta_time,
+            tear_threshold: 0.0,
         };
         let cloth_uniform_buf = BufferObj::create_uniform_buffer(
             &app_view.device,
@@ -73,16 +328,6 @@ impl Cloth {
             true,
             Some("stretch_coloring_buf"),
         );
-        let mut offset = 0;
-        for mc in fabric.stretch_constraints.0.iter() {
-            app_view.queue.write_buffer(
-                &stretch_coloring_buf.buffer,
-                offset,
-                bytemuck::cast_slice(&mc.get_push_constants_data()),
-            );
-            offset += dynamic_offset;
-        }
-
         let bend_coloring_buf = BufferObj::create_empty_uniform_buffer(
             &app_view.device,
             fabric.bend_constraints.0.len() as u64 * dynamic_offset * pbd_iter_count as u64,
@@ -90,75 +335,202 @@ impl Cloth {
             true,
             Some("bend_coloring_buf"),
         );
-        offset = 0;
-        for i in 0..pbd_iter_count {
-            for mc in fabric.bend_constraints.0.iter() {
-                app_view.queue.write_buffer(
-                    &bend_coloring_buf.buffer,
-                    offset,
-                    bytemuck::bytes_of(&mc.get_bending_dynamic_uniform(i)),
-                );
-                offset += dynamic_offset;
-            }
-        }
 
-        let mut particle_buf = BufferObj::create_storage_buffer(
+        // 撕裂分裂出新粒子时，需要拷贝到重新分配的缓冲区
+        let particle_buf = BufferObj::create_buffer(
             &app_view.device,
-            &fabric.particles,
+            Some(&fabric.particles),
+            None,
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
             Some("particle buf"),
         );
+        let particle_num = fabric.particles.len() as u32;
 
-        let velocity_buf = BufferObj::create_empty_storage_buffer(
+        let swing_buf =
+            BufferObj::create_empty_storage_buffer(&app_view.device, 16, false, Some("swing_buf"));
+
+        let constraint_buf = BufferObj::create_storage_buffer(
+            &app_view.device,
+            &fabric.stretch_constraints.1,
+            Some("constraint_buf"),
+        );
+        let bend_constraints_buf = BufferObj::create_storage_buffer(
             &app_view.device,
-            16,
+            &fabric.bend_constraints.1,
+            Some("bend_constraints_buf"),
+        );
+        let torn_buf = BufferObj::create_empty_storage_buffer(
+            &app_view.device,
+            TORN_LIST_SIZE,
+            true,
+            Some("torn_buf"),
+        );
+
+        // 鼠标抓取
+        let grab_uniform_data = GrabUniform {
+            ray_origin: [0.0; 4],
+            ray_dir: [0.0; 4],
+            compliance: 0.0000001,
+            padding: [0.0; 3],
+        };
+        let grab_uniform_buf = BufferObj::create_uniform_buffer(
+            &app_view.device,
+            &grab_uniform_data,
+            Some("grab uniform"),
+        );
+        let grab_state_buf = BufferObj::create_empty_storage_buffer(
+            &app_view.device,
+            core::mem::size_of::<GrabState>() as u64,
+            true,
+            Some("grab_state_buf"),
+        );
+        let index_buf = BufferObj::create_storage_buffer(
+            &app_view.device,
+            &fabric.vertices.1,
+            Some("cloth index buf"),
+        );
+
+        // 顶点法线
+        let (triangle_offsets, triangle_list) =
+            cloth_shading::vertex_triangle_lists(fabric.particles.len(), &fabric.vertices.1);
+        let mut triangle_offsets_buf = BufferObj::create_storage_buffer(
+            &app_view.device,
+            &triangle_offsets,
+            Some("triangle_offsets_buf"),
+        );
+        triangle_offsets_buf.read_only = true;
+        let mut triangle_list_buf = BufferObj::create_storage_buffer(
+            &app_view.device,
+            &triangle_list,
+            Some("triangle_list_buf"),
+        );
+        triangle_list_buf.read_only = true;
+        let normal_buf = BufferObj::create_empty_storage_buffer(
+            &app_view.device,
+            particle_num as u64 * 16,
             false,
-            Some("velocity_buf"),
+            Some("normal_buf"),
         );
 
-        let external_force_shader = crate::util::shader::create_shader_module(
+        // 风与空气动力
+        let particle_spacing = fabric.particle_spacing;
+        let wind_uniform_data = WindUniform {
+            wind: [0.0; 4],
+            noise_offset: [0.0, 0.0, 0.0, 1.5],
+            drag: 2.0,
+            lift: 1.0,
+            rest_area: particle_spacing * particle_spacing * 0.5,
+            padding: 0.0,
+        };
+        let wind_uniform_buf = BufferObj::create_uniform_buffer(
             &app_view.device,
-            "pbd/cloth_external_force",
-            None,
+            &wind_uniform_data,
+            Some("wind uniform"),
         );
-        let bind_group_data = BindGroupData {
-            workgroup_count: (1, 1, 1),
-            uniforms: vec![&cloth_uniform_buf],
-            storage_buffers: vec![&velocity_buf, &particle_buf],
-            ..Default::default()
+        // 风场使用经典的排列表
+        let permulation_buf = crate::noise::create_permulation_buf(&app_view.device, 0);
+        let gradient_buf = crate::noise::create_gradient_buf(&app_view.device, 0);
+
+        // 碰撞
+        let collider_uniform_data: ColliderUniform = bytemuck::Zeroable::zeroed();
+        let collider_uniform_buf = BufferObj::create_uniform_buffer(
+            &app_view.device,
+            &collider_uniform_data,
+            Some("collider uniform"),
+        );
+        let self_collision_buf = BufferObj::create_empty_storage_buffer(
+            &app_view.device,
+            (particle_num * 16) as u64,
+            false,
+            Some("self_collision_buf"),
+        );
+        let rest_pos_buf = Self::create_rest_pos_buf(&app_view.device, &fabric);
+
+        // 空间哈希的网格单元与粒子间距一致，容器数取粒子数 2 倍以上的 2 的幂
+        let bin_num = (particle_num * 2).next_power_of_two();
+        let bin_uniform = BinUniform {
+            bin_num: [bin_num as i32, 1, 1, 0],
+            bin_max_index: [bin_num as i32 - 1, 0, 0, 0],
+            bin_size: [particle_spacing, particle_spacing, particle_spacing, 0.0],
+            pos_offset: [100.0, 100.0, 100.0, 0.0],
+            max_bin_count: 16,
+            padding: [0.0; 3],
         };
-        let external_force_node =
-            ComputeNode::new(&app_view.device, &bind_group_data, &external_force_shader);
+        let bin_uniform_buf =
+            BufferObj::create_uniform_buffer(&app_view.device, &bin_uniform, Some("bin uniform"));
+        let bin_counts_buf = BufferObj::create_empty_storage_buffer(
+            &app_view.device,
+            bin_num as u64 * 4,
+            false,
+            Some("bin_counts_buf"),
+        );
+        let bin_entries_buf = BufferObj::create_empty_storage_buffer(
+            &app_view.device,
+            (bin_num * bin_uniform.max_bin_count as u32) as u64 * 4,
+            false,
+            Some("bin_entries_buf"),
+        );
 
-        let constraint_buf = BufferObj::create_storage_buffer(
+        let shading_uniform_data = ClothShadingUniform::new(
+            &crate::PBDSetting::new(),
+            glam::Mat4::from_cols_array_2d(&mvp_uniform_data.mv),
+        );
+        let shading_uniform_buf = BufferObj::create_uniform_buffer(
             &app_view.device,
-            &fabric.stretch_constraints.1,
-            Some("constraint_buf"),
+            &shading_uniform_data,
+            Some("cloth shading uniform"),
         );
 
-        let bend_constraints_buf = BufferObj::create_storage_buffer(
+        let mut buffers = ClothBuffers {
+            particle_num,
+            triangle_num: (fabric.vertices.1.len() / 3) as u32,
+            mvp_buf,
+            cloth_uniform_buf,
+            shading_uniform_buf,
+            particle_buf,
+            normal_buf,
+            self_collision_buf,
+            rest_pos_buf,
+            triangle_offsets_buf,
+            index_buf,
+            triangle_list_buf,
+            constraint_buf,
+            bend_constraints_buf,
+            stretch_coloring_buf,
+            bend_coloring_buf,
+            torn_buf,
+            swing_buf,
+            grab_uniform_buf,
+            grab_state_buf,
+            wind_uniform_buf,
+            permulation_buf,
+            gradient_buf,
+            collider_uniform_buf,
+            bin_uniform_buf,
+            bin_counts_buf,
+            bin_entries_buf,
+        };
+        buffers.write_colorings(app_view, &fabric, pbd_iter_count);
+
+        let external_force_shader = crate::util::shader::create_shader_module(
             &app_view.device,
-            &fabric.bend_constraints.1,
-            Some("bend_constraints_buf"),
+            "pbd/cloth_external_force",
+            None,
         );
+        let external_force_node = ComputeNode::new(
+            &app_view.device,
+            &buffers.bind_group_data(ClothPass::ExternalForce),
+            &external_force_shader,
+        );
+
         let predict_and_reset_shader = crate::util::shader::create_shader_module(
             &app_view.device,
             "pbd/xxpbd/cloth_predict",
             None,
         );
-        let mut bind_group_data = BindGroupData {
-            workgroup_count: (
-                ((fabric.horizontal_num * fabric.vertical_num + 31) as f32 / 32.0).floor() as u32,
-                1,
-                1,
-            ),
-            uniforms: vec![&cloth_uniform_buf],
-            storage_buffers: vec![&particle_buf, &constraint_buf],
-            ..Default::default()
-        };
-
         let predict_and_reset = ComputeNode::new(
             &app_view.device,
-            &bind_group_data,
+            &buffers.bind_group_data(ClothPass::Predict),
             &predict_and_reset_shader,
         );
 
@@ -167,12 +539,9 @@ impl Cloth {
             "pbd/xxpbd/cloth_stretch_solver",
             None,
         );
-
-        bind_group_data.dynamic_uniforms = vec![(&stretch_coloring_buf)];
-        bind_group_data.workgroup_count = (0, 0, 0);
         let stretch_solver = ComputeNode::new_with_dynamic_uniforms(
             &app_view.device,
-            &bind_group_data,
+            &buffers.bind_group_data(ClothPass::Stretch),
             &constraint_solver_shader,
         );
 
@@ -181,75 +550,176 @@ impl Cloth {
             "pbd/xxpbd/cloth_bending_solver",
             None,
         );
+        let bend_solver = ComputeNode::new_with_dynamic_uniforms(
+            &app_view.device,
+            &buffers.bind_group_data(ClothPass::Bend),
+            &bend_solver_shader,
+        );
+
+        let pick_shader =
+            crate::util::shader::create_shader_module(&app_view.device, "pbd/cloth_pick", None);
+        let pick_node = ComputeNode::new(
+            &app_view.device,
+            &buffers.bind_group_data(ClothPass::Pick),
+            &pick_shader,
+        );
+
+        let normal_shader =
+            crate::util::shader::create_shader_module(&app_view.device, "pbd/cloth_normal", None);
+        let normal_node = ComputeNode::new(
+            &app_view.device,
+            &buffers.bind_group_data(ClothPass::Normal),
+            &normal_shader,
+        );
+        let pick_resolve_shader = crate::util::shader::create_shader_module(
+            &app_view.device,
+            "pbd/cloth_pick_resolve",
+            None,
+        );
+        let pick_resolve_node = ComputeNode::new(
+            &app_view.device,
+            &buffers.bind_group_data(ClothPass::Normal),
+            &pick_resolve_shader,
+        );
+
+        let attach_solver_shader = crate::util::shader::create_shader_module(
+            &app_view.device,
+            "pbd/xxpbd/cloth_attach_solver",
+            None,
+        );
+        let attach_solver = ComputeNode::new(
+            &app_view.device,
+            &buffers.bind_group_data(ClothPass::Attach),
+            &attach_solver_shader,
+        );
+
+        let wind_shader =
+            crate::util::shader::create_shader_module(&app_view.device, "pbd/cloth_wind", None);
+        let wind_node = ComputeNode::new(
+            &app_view.device,
+            &buffers.bind_group_data(ClothPass::Wind),
+            &wind_shader,
+        );
+
+        let collision_solver_shader = crate::util::shader::create_shader_module(
+            &app_view.device,
+            "pbd/xxpbd/cloth_collision_solver",
+            None,
+        );
+        let collision_solver = ComputeNode::new(
+            &app_view.device,
+            &buffers.bind_group_data(ClothPass::Collision),
+            &collision_solver_shader,
+        );
+
+        let hash_clear_shader = crate::util::shader::create_shader_module(
+            &app_view.device,
+            "pbd/cloth_hash_clear",
+            None,
+        );
         let bind_group_data = BindGroupData {
-            uniforms: vec![&cloth_uniform_buf],
-            dynamic_uniforms: vec![&bend_coloring_buf],
-            storage_buffers: vec![&particle_buf, &bend_constraints_buf],
+            workgroup_count: (bin_num.div_ceil(64), 1, 1),
+            uniforms: vec![&buffers.bin_uniform_buf],
+            storage_buffers: vec![&buffers.bin_counts_buf],
             ..Default::default()
         };
-        let bend_solver = ComputeNode::new_with_dynamic_uniforms(
+        let hash_clear_node =
+            ComputeNode::new(&app_view.device, &bind_group_data, &hash_clear_shader);
+        let hash_insert_shader = crate::util::shader::create_shader_module(
             &app_view.device,
-            &bind_group_data,
-            &bend_solver_shader,
+            "pbd/cloth_hash_insert",
+            None,
         );
-        #[cfg(target_arch = "wasm32")]
-        let texture = _texture.unwrap();
-        #[cfg(not(target_arch = "wasm32"))]
-        let (cloth_texture, _) = pollster::block_on(crate::util::load_texture::from_path(
-            "cloth_500x500.png",
-            app_view,
-            wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
-            false,
-        ));
-        #[cfg(not(target_arch = "wasm32"))]
-        let texture = &cloth_texture;
+        let hash_insert_node = ComputeNode::new(
+            &app_view.device,
+            &buffers.bind_group_data(ClothPass::HashInsert),
+            &hash_insert_shader,
+        );
+        let self_collision_shader = crate::util::shader::create_shader_module(
+            &app_view.device,
+            "pbd/xxpbd/cloth_self_collision_solver",
+            None,
+        );
+        let self_collision_solver = ComputeNode::new(
+            &app_view.device,
+            &buffers.bind_group_data(ClothPass::SelfCollision),
+            &self_collision_shader,
+        );
+        let collider_display = ColliderDisplay::new(app_view, &buffers.mvp_buf);
 
+        // 渲染节点在顶点着色器中只读粒子及法线
+        buffers.particle_buf.read_only = true;
+        buffers.normal_buf.read_only = true;
         let sampler = app_view
             .device
             .create_sampler(&wgpu::SamplerDescriptor::default());
-        let display_shader =
-            crate::util::shader::create_shader_module(&app_view.device, "pbd/cloth_display", None);
-        particle_buf.read_only = true;
-        let bind_group_data = BindGroupData {
-            uniforms: vec![&mvp_buf, &cloth_uniform_buf],
-            storage_buffers: vec![&particle_buf],
-            inout_tv: vec![(texture, None)],
-            samplers: vec![&sampler],
-            visibilitys: vec![
-                wgpu::ShaderStages::VERTEX,
-                wgpu::ShaderStages::VERTEX,
-                wgpu::ShaderStages::VERTEX,
-                wgpu::ShaderStages::FRAGMENT,
-                wgpu::ShaderStages::FRAGMENT,
-            ],
-            ..Default::default()
-        };
-        let display_node_builder =
-            ViewNodeBuilder::<PosParticleIndex>::new(bind_group_data, &display_shader)
-                .with_use_depth_stencil(true)
-                .with_polygon_mode(wgpu::PolygonMode::Fill)
-                .with_cull_mode(None)
-                .with_vertices_and_indices((fabric.vertices.0, fabric.vertices.1))
-                .with_color_format(app_view.config.format);
+        let display_node =
+            Self::create_display_node(app_view, &buffers, texture, &sampler, &fabric);
 
-        let display_node = display_node_builder.build(&app_view.device);
+        let wireframe_shader = crate::util::shader::create_shader_module(
+            &app_view.device,
+            "pbd/cloth_wireframe",
+            None,
+        );
+        let wireframe_node = ViewNodeBuilder::<PosParticleIndex>::new(
+            buffers.bind_group_data(ClothPass::Wireframe),
+            &wireframe_shader,
+        )
+        .with_use_depth_stencil(true)
+        .with_primitive_topology(wgpu::PrimitiveTopology::LineList)
+        .with_cull_mode(None)
+        .with_vertices_and_indices((
+            fabric.vertices.0.clone(),
+            cloth_shading::wireframe_indices(&fabric.vertices.1),
+        ))
+        .with_color_format(app_view.config.format)
+        .build(&app_view.device);
+        // 以布料右边缘的位置来控制摆动范围
+        let swing_start_x = fabric.rest_bounds().1.x;
+        let tear_readback = TearReadback::new(&app_view.device);
 
         Self {
-            mvp_buf,
             mvp_uniform_data,
-            cloth_uniform_buf,
             cloth_uniform_data,
+            buffers,
+            fabric,
+            texture: texture.clone(),
+            sampler,
+            swing_x: swing_start_x,
+            swing_dir: 1.0,
+            swing_start_x,
+            paused: false,
+            grab_uniform_data,
+            pick_node,
+            pick_resolve_node,
+            attach_solver,
+            is_picking: false,
+            is_dragging: false,
+
+            particle_spacing,
+            collider_uniform_data,
+            collision_solver,
+            collider_display,
+            hash_clear_node,
+            hash_insert_node,
+            self_collision_solver,
 
             external_force_node,
+            wind_uniform_data,
+            wind_node,
 
-            stretch_mesh_coloring: fabric.stretch_constraints.0,
-            bend_mesh_coloring: fabric.bend_constraints.0,
+            tear_readback,
+            torn: false,
             predict_and_reset,
             stretch_solver,
             bend_solver,
+            normal_node,
+            shading_uniform_data,
             display_node,
+            wireframe_node,
+            show_mesh: false,
             frame_count: 0,
-            pbd_iter_count: pbd_iter_count as usize,
+            pbd_iter_count,
             delta_time,
         }
     }
@@ -260,21 +730,233 @@ impl Cloth {
         let compliance =
             control_panel.pbd_setting.compliance * 0.00000016 / (self.delta_time * self.delta_time);
         let stiffness = control_panel.pbd_setting.stiffness;
+        let tear_threshold = if control_panel.pbd_setting.tearable {
+            control_panel.pbd_setting.tear_threshold
+        } else {
+            0.0
+        };
         if (new_damping - self.cloth_uniform_data.damping).abs() > 0.00001
             || (new_gravity - self.cloth_uniform_data.gravity).abs() > 0.00001
             || (compliance - self.cloth_uniform_data.compliance).abs() > 0.00000000001
             || (stiffness - self.cloth_uniform_data.stiffness).abs() > 0.00001
+            || tear_threshold != self.cloth_uniform_data.tear_threshold
         {
             self.cloth_uniform_data.damping = new_damping;
             self.cloth_uniform_data.gravity = new_gravity;
             self.cloth_uniform_data.compliance = compliance;
             self.cloth_uniform_data.stiffness = stiffness;
+            self.cloth_uniform_data.tear_threshold = tear_threshold;
             app.queue.write_buffer(
-                &self.cloth_uniform_buf.buffer,
+                &self.buffers.cloth_uniform_buf.buffer,
                 0,
                 bytemuck::bytes_of(&self.cloth_uniform_data),
             );
         }
+
+        let collider_uniform_data =
+            ColliderUniform::new(&control_panel.pbd_setting, self.particle_spacing);
+        if collider_uniform_data != self.collider_uniform_data {
+            self.collider_uniform_data = collider_uniform_data;
+            app.queue.write_buffer(
+                &self.buffers.collider_uniform_buf.buffer,
+                0,
+                bytemuck::bytes_of(&self.collider_uniform_data),
+            );
+            self.collider_display
+                .update(app, &control_panel.pbd_setting);
+        }
+
+        let shading_uniform_data = ClothShadingUniform::new(
+            &control_panel.pbd_setting,
+            glam::Mat4::from_cols_array_2d(&self.mvp_uniform_data.mv),
+        );
+        if shading_uniform_data != self.shading_uniform_data {
+            self.shading_uniform_data = shading_uniform_data;
+            app.queue.write_buffer(
+                &self.buffers.shading_uniform_buf.buffer,
+                0,
+                bytemuck::bytes_of(&self.shading_uniform_data),
+            );
+        }
+        self.show_mesh = control_panel.pbd_setting.show_mesh;
+
+        if !self.paused {
+            self.update_wind(app, &control_panel.pbd_setting);
+            if self.frame_count > 10 {
+                self.update_swing(app);
+            }
+        }
+    }
+
+    /// 计算固定粒子在下一帧的位移
+    fn update_swing(&mut self, app: &AppSurface) {
+        if self.swing_x < -0.1 {
+            self.swing_dir = 1.0;
+        } else if self.swing_x > 2.17 {
+            self.swing_dir = -1.0;
+        }
+        let velocity = [0.015 * self.swing_dir, 0.0, -0.00105 * self.swing_dir, 0.0];
+        self.swing_x += velocity[0];
+        app.queue.write_buffer(
+            &self.buffers.swing_buf.buffer,
+            0,
+            bytemuck::cast_slice(&velocity),
+        );
+    }
+
+    /// 更新风速，并让湍流噪声随风移动
+    fn update_wind(&mut self, app: &AppSurface, setting: &crate::PBDSetting) {
+        let angle = setting.wind_direction.to_radians();
+        // 0 度指向 +x，90 度指向屏幕里面
+        let wind = glam::Vec3::new(angle.cos(), 0.0, -angle.sin())
+            * setting.wind_strength
+            * MAX_WIND_SPEED;
+        let scale = self.wind_uniform_data.noise_offset[3];
+        let offset =
+            glam::Vec4::from(self.wind_uniform_data.noise_offset).truncate() - wind * 0.016 * scale;
+        self.wind_uniform_data.wind = wind.extend(setting.gustiness).into();
+        // 噪声在 [0, 256) 范围内循环
+        self.wind_uniform_data.noise_offset =
+            offset.map(|v| v.rem_euclid(256.0)).extend(scale).into();
+        app.queue.write_buffer(
+            &self.buffers.wind_uniform_buf.buffer,
+            0,
+            bytemuck::bytes_of(&self.wind_uniform_data),
+        );
+    }
+
+    /// 恢复到静止状态
+    pub fn reset(&mut self, app: &AppSurface) {
+        app.queue.write_buffer(
+            &self.buffers.particle_buf.buffer,
+            0,
+            bytemuck::cast_slice(&self.fabric.particles),
+        );
+        self.swing_x = self.swing_start_x;
+        self.swing_dir = 1.0;
+        self.frame_count = 0;
+    }
+
+    /// 屏幕坐标对应的模型空间射线：(起点, 单位方向)
+    fn cursor_ray(&self, viewport: glam::Vec2, pos: glam::Vec2) -> (glam::Vec3, glam::Vec3) {
+        let mvp = glam::Mat4::from_cols_array_2d(&self.mvp_uniform_data.mvp);
+        let inv_mvp = mvp.inverse();
+        let ndc = glam::Vec2::new(
+            pos.x / viewport.x * 2.0 - 1.0,
+            1.0 - pos.y / viewport.y * 2.0,
+        );
+        let near = inv_mvp.project_point3(ndc.extend(0.0));
+        let far = inv_mvp.project_point3(ndc.extend(1.0));
+        (near, (far - near).normalize_or_zero())
+    }
+
+    /// 从鼠标位置抓取布料上的粒子，拾取在下一次 compute 时于 GPU 上完成
+    pub fn grab_begin(&mut self, app: &AppSurface, pos: glam::Vec2) {
+        self.update_grab_ray(app, pos);
+        let state = GrabState {
+            t_bits: u32::MAX,
+            particle: -1,
+            depth: 0.0,
+            padding: 0.0,
+        };
+        app.queue.write_buffer(
+            &self.buffers.grab_state_buf.buffer,
+            0,
+            bytemuck::bytes_of(&state),
+        );
+        self.is_picking = true;
+        self.is_dragging = true;
+    }
+
+    pub fn grab_move(&mut self, app: &AppSurface, pos: glam::Vec2) {
+        if self.is_dragging {
+            self.update_grab_ray(app, pos);
+        }
+    }
+
+    pub fn grab_end(&mut self) {
+        self.is_picking = false;
+        self.is_dragging = false;
+    }
+
+    fn update_grab_ray(&mut self, app: &AppSurface, pos: glam::Vec2) {
+        let viewport = glam::Vec2::new(app.config.width as f32, app.config.height as f32);
+        let (origin, dir) = self.cursor_ray(viewport, pos);
+        self.grab_uniform_data.ray_origin = origin.extend(1.0).into();
+        self.grab_uniform_data.ray_dir = dir.extend(0.0).into();
+        app.queue.write_buffer(
+            &self.buffers.grab_uniform_buf.buffer,
+            0,
+            bytemuck::bytes_of(&self.grab_uniform_data),
+        );
+    }
+
+    /// 屏幕坐标对应的静止状态布料上的 uv 坐标
+    pub fn rest_uv_at(&self, viewport: glam::Vec2, pos: glam::Vec2) -> Option<glam::Vec2> {
+        let (origin, dir) = self.cursor_ray(viewport, pos);
+        if dir.z.abs() < f32::EPSILON {
+            return None;
+        }
+        // 静止的布料位于 z = 0 平面上
+        let p = origin + dir * (-origin.z / dir.z);
+
+        // u 向右，v 向下
+        let (min, max) = self.fabric.rest_bounds();
+        let uv = glam::Vec2::new(
+            (p.x - min.x) / (max.x - min.x),
+            (max.y - p.y) / (max.y - min.y),
+        );
+        let range = -0.02..=1.02;
+        if range.contains(&uv.x) && range.contains(&uv.y) {
+            Some(uv.clamp(glam::Vec2::ZERO, glam::Vec2::ONE))
+        } else {
+            None
+        }
+    }
+
+    /// 静止状态下固定粒子的屏幕坐标
+    pub fn rest_pinned_positions(&self, viewport: glam::Vec2) -> Vec<glam::Vec2> {
+        let mvp = glam::Mat4::from_cols_array_2d(&self.mvp_uniform_data.mvp);
+        self.fabric
+            .particles
+            .iter()
+            .filter(|p| p.uv_mass[2] == 0.0)
+            .map(|p| {
+                let ndc = mvp.project_point3(glam::Vec3::new(p.pos[0], p.pos[1], p.pos[2]));
+                glam::Vec2::new(
+                    (ndc.x + 1.0) * 0.5 * viewport.x,
+                    (1.0 - ndc.y) * 0.5 * viewport.y,
+                )
+            })
+            .collect()
+    }
+
+    /// 更换布料的纹理，模拟状态保持不变
+    pub fn set_texture(&mut self, app: &AppSurface, texture: &AnyTexture) {
+        self.display_node =
+            Self::create_display_node(app, &self.buffers, texture, &self.sampler, &self.fabric);
+        self.texture = texture.clone();
+    }
+
+    fn create_display_node(
+        app_view: &AppSurface,
+        buffers: &ClothBuffers,
+        texture: &AnyTexture,
+        sampler: &wgpu::Sampler,
+        fabric: &ClothFabric,
+    ) -> ViewNode {
+        let display_shader =
+            crate::util::shader::create_shader_module(&app_view.device, "pbd/cloth_display", None);
+        ViewNodeBuilder::<PosParticleIndex>::new(
+            buffers.display_bind_group_data(texture, sampler),
+            &display_shader,
+        )
+        .with_use_depth_stencil(true)
+        .with_polygon_mode(wgpu::PolygonMode::Fill)
+        .with_cull_mode(None)
+        .with_vertices_and_indices(fabric.vertices.clone())
+        .with_color_format(app_view.config.format)
+        .build(&app_view.device)
     }
 
     pub fn resize(&mut self, app: &app_surface::AppSurface) -> bool {
@@ -283,15 +965,192 @@ impl Cloth {
             app.config.height as f32,
         ));
         app.queue.write_buffer(
-            &self.mvp_buf.buffer,
+            &self.buffers.mvp_buf.buffer,
             0,
             bytemuck::bytes_of(&self.mvp_uniform_data),
         );
         true
     }
 
+    /// 使用轨道相机的视角，抓取及固定点的投影也随之变化
+    pub fn set_mvp(&mut self, app: &AppSurface, mvp_uniform_data: &crate::MVPMatUniform) {
+        self.mvp_uniform_data = *mvp_uniform_data;
+        app.queue.write_buffer(
+            &self.buffers.mvp_buf.buffer,
+            0,
+            bytemuck::bytes_of(&self.mvp_uniform_data),
+        );
+    }
+
     pub fn compute(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        if self.paused {
+            // 重置到静止状态后也需要更新法线
+            self.normal_node.compute(encoder);
+            return;
+        }
         self.step_solver(encoder);
+        self.normal_node.compute(encoder);
+        if self.cloth_uniform_data.tear_threshold > 0.0 {
+            self.tear_readback
+                .copy(encoder, &self.buffers.torn_buf.buffer);
+        }
+    }
+
+    /// 读回断开的约束，有新的撕裂时在 CPU 上分裂网格，然后只把变化的索引、约束及着色分组数据
+    /// 写入已有的缓冲区；粒子缓冲区保留当前的模拟状态，只在分裂出新粒子时扩容
+    pub fn tear_if_needed(&mut self, app: &AppSurface) {
+        let Some(torn) = self.tear_readback.poll(&app.device) else {
+            return;
+        };
+        let (fabric, sources) = cloth_tearing::tear_fabric(&self.fabric, &torn);
+        let mut encoder = app
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("tear encoder"),
+            });
+        if !sources.is_empty() {
+            self.grow_particles(app, &mut encoder, &fabric, &sources);
+            let buffers = &self.buffers;
+            let (triangle_offsets, triangle_list) =
+                cloth_shading::vertex_triangle_lists(fabric.particles.len(), &fabric.vertices.1);
+            let topology: [(&BufferObj, &[u32]); 3] = [
+                (&buffers.index_buf, &fabric.vertices.1),
+                (&buffers.triangle_offsets_buf, &triangle_offsets),
+                (&buffers.triangle_list_buf, &triangle_list),
+            ];
+            for (buf, data) in topology {
+                app.queue
+                    .write_buffer(&buf.buffer, 0, bytemuck::cast_slice(data));
+            }
+            self.display_node.update_vertices_and_indices(
+                &app.device,
+                &app.queue,
+                fabric.vertices.clone(),
+            );
+            self.wireframe_node.update_vertices_and_indices(
+                &app.device,
+                &app.queue,
+                (
+                    fabric.vertices.0.clone(),
+                    cloth_shading::wireframe_indices(&fabric.vertices.1),
+                ),
+            );
+        }
+
+        // 约束只会减少，着色分组也不会增加，原有的缓冲区足够容纳
+        app.queue.write_buffer(
+            &self.buffers.constraint_buf.buffer,
+            0,
+            bytemuck::cast_slice(&fabric.stretch_constraints.1),
+        );
+        app.queue.write_buffer(
+            &self.buffers.bend_constraints_buf.buffer,
+            0,
+            bytemuck::cast_slice(&fabric.bend_constraints.1),
+        );
+        self.buffers
+            .write_colorings(app, &fabric, self.pbd_iter_count);
+        // 读回之后新断开的约束已随约束缓冲区一起恢复，清空列表
+        encoder.clear_buffer(&self.buffers.torn_buf.buffer, 0, None);
+        app.queue.submit(Some(encoder.finish()));
+
+        self.fabric = fabric;
+        self.torn = true;
+    }
+
+    /// 重新分配与粒子数相关的缓冲区：已有粒子的状态原样拷贝，新粒子拷贝自分裂前的粒子；
+    /// 然后重建引用这些缓冲区的绑定组，管线保持不变
+    fn grow_particles(
+        &mut self,
+        app: &AppSurface,
+        encoder: &mut wgpu::CommandEncoder,
+        fabric: &ClothFabric,
+        sources: &[u32],
+    ) {
+        let device = &app.device;
+        let old_num = self.buffers.particle_num as u64;
+        let particle_num = old_num + sources.len() as u64;
+        let stride = core::mem::size_of::<ParticleBufferObj>() as u64;
+        let mut particle_buf = BufferObj::create_empty_storage_buffer(
+            device,
+            particle_num * stride,
+            true,
+            Some("particle buf"),
+        );
+        let old_buf = &self.buffers.particle_buf.buffer;
+        encoder.copy_buffer_to_buffer(old_buf, 0, &particle_buf.buffer, 0, old_num * stride);
+        for (i, source) in sources.iter().enumerate() {
+            encoder.copy_buffer_to_buffer(
+                old_buf,
+                *source as u64 * stride,
+                &particle_buf.buffer,
+                (old_num + i as u64) * stride,
+                stride,
+            );
+        }
+        // 与 new() 中创建渲染节点时的状态一致
+        particle_buf.read_only = true;
+        let mut normal_buf = BufferObj::create_empty_storage_buffer(
+            device,
+            particle_num * 16,
+            false,
+            Some("normal_buf"),
+        );
+        normal_buf.read_only = true;
+        let mut triangle_offsets_buf = BufferObj::create_empty_storage_buffer(
+            device,
+            (particle_num + 1) * 4,
+            false,
+            Some("triangle_offsets_buf"),
+        );
+        triangle_offsets_buf.read_only = true;
+
+        let buffers = &mut self.buffers;
+        buffers.particle_num = particle_num as u32;
+        buffers.particle_buf = particle_buf;
+        buffers.normal_buf = normal_buf;
+        buffers.triangle_offsets_buf = triangle_offsets_buf;
+        buffers.self_collision_buf = BufferObj::create_empty_storage_buffer(
+            device,
+            particle_num * 16,
+            false,
+            Some("self_collision_buf"),
+        );
+        buffers.rest_pos_buf = Self::create_rest_pos_buf(device, fabric);
+
+        let buffers = &self.buffers;
+        let nodes = [
+            (&mut self.external_force_node, ClothPass::ExternalForce),
+            (&mut self.predict_and_reset, ClothPass::Predict),
+            (&mut self.stretch_solver, ClothPass::Stretch),
+            (&mut self.bend_solver, ClothPass::Bend),
+            (&mut self.pick_node, ClothPass::Pick),
+            (&mut self.normal_node, ClothPass::Normal),
+            (&mut self.pick_resolve_node, ClothPass::Normal),
+            (&mut self.attach_solver, ClothPass::Attach),
+            (&mut self.wind_node, ClothPass::Wind),
+            (&mut self.collision_solver, ClothPass::Collision),
+            (&mut self.hash_insert_node, ClothPass::HashInsert),
+            (&mut self.self_collision_solver, ClothPass::SelfCollision),
+        ];
+        for (node, pass) in nodes {
+            node.rebind(device, &buffers.bind_group_data(pass));
+        }
+        self.wireframe_node
+            .rebind(device, &buffers.bind_group_data(ClothPass::Wireframe));
+        self.display_node.rebind(
+            device,
+            &buffers.display_bind_group_data(&self.texture, &self.sampler),
+        );
+    }
+
+    /// 自碰撞用静止状态下的距离排除相邻的粒子
+    fn create_rest_pos_buf(device: &wgpu::Device, fabric: &ClothFabric) -> BufferObj {
+        let rest_positions: Vec<[f32; 4]> = fabric.particles.iter().map(|p| p.pos).collect();
+        let mut buf =
+            BufferObj::create_storage_buffer(device, &rest_positions, Some("rest_pos_buf"));
+        buf.read_only = true;
+        buf
     }
 
     pub fn draw_by_rpass<'b, 'a: 'b>(
@@ -300,7 +1159,11 @@ impl Cloth {
         rpass: &mut wgpu::RenderPass<'b>,
         _setting: &mut crate::SettingObj,
     ) {
+        self.collider_display.draw_by_pass(rpass);
         self.display_node.draw_by_pass(rpass);
+        if self.show_mesh {
+            self.wireframe_node.draw_by_pass(rpass);
+        }
     }
 
     fn step_solver(&mut self, encoder: &mut wgpu::CommandEncoder) {
@@ -311,6 +1174,21 @@ impl Cloth {
             ..Default::default()
         });
 
+        if self.is_picking {
+            self.pick_node.compute_by_pass(&mut cpass);
+            self.pick_resolve_node.compute_by_pass(&mut cpass);
+            self.is_picking = false;
+        }
+
+        let self_collision = self.collider_uniform_data.is_self_collision();
+        let has_collision = self_collision || self.collider_uniform_data.has_collider();
+        self.wind_node.compute_by_pass(&mut cpass);
+
+        if self_collision {
+            self.hash_clear_node.compute_by_pass(&mut cpass);
+            self.hash_insert_node.compute_by_pass(&mut cpass);
+        }
+
         let dynamic_offset = 256;
         for i in 0..self.pbd_iter_count {
             // 下一次迭代的开始，先更新粒子速度
@@ -319,7 +1197,7 @@ impl Cloth {
             cpass.set_pipeline(&self.stretch_solver.pipeline);
             cpass.set_bind_group(0, &self.stretch_solver.bg_setting.bind_group, &[]);
             let mut index = 0;
-            for mc in self.stretch_mesh_coloring.iter() {
+            for mc in self.fabric.stretch_constraints.0.iter() {
                 if let Some(bg) = &self.stretch_solver.dy_uniform_bg {
                     cpass.set_bind_group(1, &bg.bind_group, &[index * dynamic_offset]);
                 }
@@ -328,12 +1206,12 @@ impl Cloth {
             }
 
             let bending_dynamic_uniform_offset =
-                (i * self.bend_mesh_coloring.len() * dynamic_offset as usize)
+                (i * self.fabric.bend_constraints.0.len() * dynamic_offset as usize)
                     as wgpu::DynamicOffset;
             cpass.set_pipeline(&self.bend_solver.pipeline);
             cpass.set_bind_group(0, &self.bend_solver.bg_setting.bind_group, &[]);
             index = 0;
-            for mc in self.bend_mesh_coloring.iter() {
+            for mc in self.fabric.bend_constraints.0.iter() {
                 if let Some(bg) = &self.bend_solver.dy_uniform_bg {
                     cpass.set_bind_group(
                         1,
@@ -344,6 +1222,17 @@ impl Cloth {
                 cpass.dispatch_workgroups(mc.thread_group.0, mc.thread_group.1, 1);
                 index += 1;
             }
+
+            if self_collision {
+                self.self_collision_solver.compute_by_pass(&mut cpass);
+            }
+            if has_collision {
+                self.collision_solver.compute_by_pass(&mut cpass);
+            }
+
+            if self.is_dragging {
+                self.attach_solver.compute_by_pass(&mut cpass);
+            }
         }
 
         if self.frame_count > 10 {
@@ -353,7 +1242,7 @@ impl Cloth {
         self.frame_count += 1;
     }
 
-    fn get_mvp_uniform_data(viewport: glam::Vec2) -> crate::MVPMatUniform {
+    pub(super) fn get_mvp_uniform_data(viewport: glam::Vec2) -> crate::MVPMatUniform {
         let (proj_mat, mut mv_mat, _factor) = crate::util::matrix_helper::perspective_mvp(viewport);
         mv_mat *= glam::Mat4::from_translation(glam::Vec3::new(0.0, 0.0, -0.4));
         crate::MVPMatUniform {
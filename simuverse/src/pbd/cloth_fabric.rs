@@ -1,6 +1,6 @@
 use super::{BendingConstraintObj, MeshColoringObj, StretchConstraintObj};
 use crate::util::vertex::PosParticleIndex;
-use alloc::{vec, vec::Vec};
+use alloc::{string::String, vec, vec::Vec};
 
 #[repr(C)]
 #[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
@@ -31,9 +31,107 @@ pub struct ParticleBufferObj {
 // │ /    │    \ │ /    │
 // └──────┴──────┴──────┘
 
+/// 布料的固定方式
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClothPinPattern {
+    TopEdge = 0,
+    TwoCorners,
+    FourCorners,
+    None,
+    // 用鼠标选取的粒子
+    Custom,
+}
+
+impl ClothPinPattern {
+    pub fn from_u32(ty: u32) -> Self {
+        match ty {
+            1 => ClothPinPattern::TwoCorners,
+            2 => ClothPinPattern::FourCorners,
+            3 => ClothPinPattern::None,
+            4 => ClothPinPattern::Custom,
+            _ => ClothPinPattern::TopEdge,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ClothPinPattern::TopEdge => "Top edge",
+            ClothPinPattern::TwoCorners => "Two corners",
+            ClothPinPattern::FourCorners => "Four corners",
+            ClothPinPattern::None => "None",
+            ClothPinPattern::Custom => "Custom",
+        }
+    }
+}
+
+/// 布料网格的来源
+#[derive(Clone, PartialEq, Debug)]
+pub enum ClothMesh {
+    // 矩形网格
+    Grid,
+    // CAD 中 ObjApp 加载的模型文件路径，为空时是内置的茶壶
+    CADModel(String),
+    // 用户的 OBJ 文件路径
+    File(String),
+}
+
+/// 布料纹理的来源
+#[derive(Clone, PartialEq, Debug)]
+pub enum ClothTexture {
+    // assets 目录中的默认图片
+    Default,
+    // 用户的图片文件路径
+    File(String),
+    // 由噪声面板当前的材质烘焙
+    Noise,
+}
+
+/// 生成布料所需的参数
+#[derive(Clone, PartialEq, Debug)]
+pub struct ClothFabricDesc {
+    pub mesh: ClothMesh,
+    // 矩形网格的粒子数
+    pub horizontal_num: usize,
+    pub vertical_num: usize,
+    // 布料宽度与视口宽度之比
+    pub size: f32,
+    pub pin_pattern: ClothPinPattern,
+    // 自定义固定点在布料上的 uv 坐标，与分辨率无关
+    pub custom_pins: Vec<[f32; 2]>,
+}
+
+impl ClothFabricDesc {
+    fn grid_pinned_flags(&self) -> Vec<bool> {
+        let (num_x, num_y) = (self.horizontal_num, self.vertical_num);
+        let mut flags = vec![false; num_x * num_y];
+        let is_corner_col = |w: usize| w == 0 || w == num_x - 1;
+        for h in 0..num_y {
+            for w in 0..num_x {
+                flags[h * num_x + w] = match self.pin_pattern {
+                    ClothPinPattern::TopEdge => h == 0,
+                    ClothPinPattern::TwoCorners => h == 0 && is_corner_col(w),
+                    ClothPinPattern::FourCorners => (h == 0 || h == num_y - 1) && is_corner_col(w),
+                    _ => false,
+                };
+            }
+        }
+        if self.pin_pattern == ClothPinPattern::Custom {
+            for uv in self.custom_pins.iter() {
+                let w = (uv[0].clamp(0.0, 1.0) * (num_x - 1) as f32).round() as usize;
+                let h = (uv[1].clamp(0.0, 1.0) * (num_y - 1) as f32).round() as usize;
+                flags[h * num_x + w] = true;
+            }
+        }
+        flags
+    }
+}
+
 pub struct ClothFabric {
+    // 矩形网格每行、每列的粒子数；任意三角形网格的顶点只用 x 作为粒子索引，为 (粒子数, 1)
     pub horizontal_num: usize,
     pub vertical_num: usize,
+    // 静止状态下相邻粒子的间距
+    pub particle_spacing: f32,
     pub vertices: (Vec<PosParticleIndex>, Vec<u32>),
     pub particles: Vec<ParticleBufferObj>,
     pub stretch_constraints: (Vec<MeshColoringObj>, Vec<StretchConstraintObj>),
@@ -41,12 +139,13 @@ pub struct ClothFabric {
 }
 impl ClothFabric {
     pub fn gen_fabric(
-        horizontal_num: usize,
-        vertical_num: usize,
+        desc: &ClothFabricDesc,
         horizontal_pixel: f32,
         vertical_pixel: f32,
         a_pixel_on_ndc: f32,
     ) -> Self {
+        let (horizontal_num, vertical_num) = (desc.horizontal_num, desc.vertical_num);
+        let pinned_flags = desc.grid_pinned_flags();
         let mut vertex_data: Vec<PosParticleIndex> =
             Vec::with_capacity(horizontal_num * vertical_num);
         let mut index_data: Vec<u32> = Vec::new();
@@ -88,11 +187,12 @@ impl ClothFabric {
                     0.0,
                     0.0,
                 ];
-                // 上边两个角固定：粒子质量为 无穷大
+                // 固定的粒子：质量为 无穷大
                 // 每个顶点的质量等于与之相连的每个三角形质量的 1/3 之后
-                let invert_mass = if h == 0 && (w == 0 || w == horizontal_num - 1) {
+                let pinned = pinned_flags[h * horizontal_num + w];
+                let invert_mass = if pinned {
                     0.0
-                } else if w == 0 || w == (horizontal_num - 1) || h == (vertical_num - 1) {
+                } else if w == 0 || w == (horizontal_num - 1) || h == 0 || h == (vertical_num - 1) {
                     // 边界上的点，只有两个三角形与之相连
                     0.2
                 } else {
@@ -101,10 +201,15 @@ impl ClothFabric {
                 particles.push(ParticleBufferObj {
                     pos: p,
                     old_pos: p,
-                    // 重力加速度不能太小，会导致布料飘来飘去，没有重量感
-                    // accelerate: [0.0, 0.0, 0.0, 0.0],
-                    accelerate: [0.0, -3.98, 0.0, 0.11],
-                    uv_mass: [uv_x_step * w as f32, uv_y_step * h as f32, invert_mass, 0.0],
+                    // 风力与空气动力产生的加速度，每帧由 GPU 计算
+                    accelerate: [0.0; 4],
+                    // w 为 1 表示顶行的固定粒子，随摆动一起移动
+                    uv_mass: [
+                        uv_x_step * w as f32,
+                        uv_y_step * h as f32,
+                        invert_mass,
+                        (pinned && h == 0) as u32 as f32,
+                    ],
                     connect: [0; 4],
                 })
             }
@@ -133,6 +238,7 @@ impl ClothFabric {
         Self {
             horizontal_num,
             vertical_num,
+            particle_spacing: horizontal_step,
             vertices: (vertex_data, index_data),
             particles,
             stretch_constraints,
@@ -141,6 +247,19 @@ impl ClothFabric {
     }
 }
 
+impl ClothFabric {
+    /// 静止状态下粒子在 xy 平面上的包围盒：(最小值, 最大值)
+    pub fn rest_bounds(&self) -> (glam::Vec2, glam::Vec2) {
+        self.particles.iter().fold(
+            (glam::Vec2::splat(f32::MAX), glam::Vec2::splat(f32::MIN)),
+            |(min, max), p| {
+                let pos = glam::Vec2::new(p.pos[0], p.pos[1]);
+                (min.min(pos), max.max(pos))
+            },
+        )
+    }
+}
+
 fn cal_connected_particles(
     particles: &mut [ParticleBufferObj],
     horizontal_num: usize,
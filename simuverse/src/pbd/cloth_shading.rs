@@ -0,0 +1,58 @@
+use super::ClothShadingUniform;
+use crate::PBDSetting;
+use alloc::{collections::BTreeSet, vec, vec::Vec};
+
+impl ClothShadingUniform {
+    /// mv: 布料的模型视图矩阵，光源方向由方位角及仰角确定
+    pub fn new(setting: &PBDSetting, mv: glam::Mat4) -> Self {
+        let (azimuth, elevation) = (
+            setting.light_azimuth.to_radians(),
+            setting.light_elevation.to_radians(),
+        );
+        // 方位角为 0 时光源位于相机一侧
+        let dir = glam::Vec3::new(
+            elevation.cos() * azimuth.sin(),
+            elevation.sin(),
+            elevation.cos() * azimuth.cos(),
+        );
+        Self {
+            light_dir: mv.transform_vector3(dir).normalize().extend(0.0).into(),
+            front_color: glam::Vec3::from(setting.front_color).extend(1.0).into(),
+            back_color: glam::Vec3::from(setting.back_color).extend(1.0).into(),
+        }
+    }
+}
+
+/// 每个粒子所属的三角形，offsets[i]..offsets[i + 1] 为粒子 i 在列表中的范围
+pub(super) fn vertex_triangle_lists(particle_num: usize, indices: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let mut counts = vec![0_u32; particle_num + 1];
+    for v in indices.iter() {
+        counts[*v as usize + 1] += 1;
+    }
+    let mut offsets = counts;
+    for i in 1..offsets.len() {
+        offsets[i] += offsets[i - 1];
+    }
+    let mut cursor = offsets.clone();
+    // 保证缓冲区不为空
+    let mut list = vec![0_u32; indices.len().max(1)];
+    for (t, tri) in indices.chunks_exact(3).enumerate() {
+        for v in tri.iter() {
+            list[cursor[*v as usize] as usize] = t as u32;
+            cursor[*v as usize] += 1;
+        }
+    }
+    (offsets, list)
+}
+
+/// 三角形的边去重后作为线段列表的索引，用于绘制网格线
+pub(super) fn wireframe_indices(indices: &[u32]) -> Vec<u32> {
+    let mut edges = BTreeSet::new();
+    for tri in indices.chunks_exact(3) {
+        for i in 0..3 {
+            let (a, b) = (tri[i], tri[(i + 1) % 3]);
+            edges.insert((a.min(b), a.max(b)));
+        }
+    }
+    edges.into_iter().flat_map(|(a, b)| [a, b]).collect()
+}
@@ -0,0 +1,563 @@
+//! 布料撕裂
+//!
+//! GPU 上的拉伸约束求解器把超过撕裂阈值的约束标记为断开，并记录到 `TornList` 中；
+//! CPU 端定期读回断开的约束，在静止状态的网格上分裂粒子、更新三角形索引及约束，
+//! 然后只把变化的数据写入布料已有的缓冲区，粒子的模拟状态保留在 GPU 上。
+
+use super::cloth_fabric::ParticleBufferObj;
+use super::gen_cloth_constraints::gen_coloring_and_flat_data;
+use super::{BendingConstraintObj, ClothFabric, MeshColoringObj, StretchConstraintObj};
+use crate::util::vertex::PosParticleIndex;
+use alloc::{collections::BTreeSet, sync::Arc, vec, vec::Vec};
+use core::sync::atomic::{AtomicU8, Ordering};
+
+// 与 cloth_stretch_solver.wgsl 中的 MAX_TORN_COUNT 一致
+const MAX_TORN_COUNT: usize = 256;
+// TornList 的 count 及 3 个 padding
+const TORN_LIST_HEADER: usize = 4;
+pub(super) const TORN_LIST_SIZE: u64 = ((TORN_LIST_HEADER + MAX_TORN_COUNT) * 4) as u64;
+
+// 每隔多少帧读回一次
+const READBACK_INTERVAL: usize = 6;
+
+const MAP_PENDING: u8 = 0;
+const MAP_OK: u8 = 1;
+const MAP_FAILED: u8 = 2;
+
+enum ReadbackState {
+    Idle,
+    // 拷贝命令已录制，等待提交
+    Copied,
+    Mapping(Arc<AtomicU8>),
+}
+
+pub(super) struct TearReadback {
+    staging_buf: wgpu::Buffer,
+    state: ReadbackState,
+    frame_count: usize,
+}
+
+impl TearReadback {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let staging_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("tear readback buf"),
+            size: TORN_LIST_SIZE,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        Self {
+            staging_buf,
+            state: ReadbackState::Idle,
+            frame_count: 0,
+        }
+    }
+
+    /// 录制拷贝命令，上一次读回完成之前不会再次拷贝
+    pub fn copy(&mut self, encoder: &mut wgpu::CommandEncoder, torn_buf: &wgpu::Buffer) {
+        if !matches!(self.state, ReadbackState::Idle) {
+            return;
+        }
+        self.frame_count += 1;
+        if !self.frame_count.is_multiple_of(READBACK_INTERVAL) {
+            return;
+        }
+        encoder.copy_buffer_to_buffer(torn_buf, 0, &self.staging_buf, 0, TORN_LIST_SIZE);
+        self.state = ReadbackState::Copied;
+    }
+
+    /// 需在拷贝命令提交之后调用；读回完成且有断开的约束时返回这些约束的索引
+    pub fn poll(&mut self, device: &wgpu::Device) -> Option<Vec<u32>> {
+        match &self.state {
+            ReadbackState::Idle => None,
+            ReadbackState::Copied => {
+                let status = Arc::new(AtomicU8::new(MAP_PENDING));
+                let callback_status = status.clone();
+                self.staging_buf
+                    .map_async(wgpu::MapMode::Read, .., move |res| {
+                        let value = if res.is_ok() { MAP_OK } else { MAP_FAILED };
+                        callback_status.store(value, Ordering::Release);
+                    });
+                self.state = ReadbackState::Mapping(status);
+                None
+            }
+            ReadbackState::Mapping(status) => {
+                let _ = device.poll(wgpu::PollType::Poll);
+                match status.load(Ordering::Acquire) {
+                    MAP_PENDING => return None,
+                    MAP_FAILED => {
+                        log::warn!("failed to read back torn constraints");
+                        self.state = ReadbackState::Idle;
+                        return None;
+                    }
+                    _ => {}
+                }
+                let torn = {
+                    let data = self.staging_buf.slice(..).get_mapped_range();
+                    let header: &[u32] = bytemuck::cast_slice(&data);
+                    let count = (header[0] as usize).min(MAX_TORN_COUNT);
+                    (count > 0).then(|| header[TORN_LIST_HEADER..TORN_LIST_HEADER + count].to_vec())
+                };
+                self.staging_buf.unmap();
+                self.state = ReadbackState::Idle;
+                torn
+            }
+        }
+    }
+}
+
+/// 从断开的约束生成撕裂后的布料；新粒子追加在粒子列表末尾，
+/// 同时返回每个新粒子分裂自的原有粒子，GPU 上的新粒子从这些粒子拷贝当前的状态
+pub(super) fn tear_fabric(fabric: &ClothFabric, torn: &[u32]) -> (ClothFabric, Vec<u32>) {
+    let mut mesh = TearMesh::new(fabric);
+    for index in torn.iter() {
+        mesh.tear(*index as usize);
+    }
+    mesh.remove_detached_constraints();
+    let sources = core::mem::take(&mut mesh.sources);
+    (mesh.into_fabric(fabric), sources)
+}
+
+fn edge(a: usize, b: usize) -> (usize, usize) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+struct TearMesh {
+    particles: Vec<ParticleBufferObj>,
+    // 撕裂之前的粒子数，及其后每个新粒子分裂自的原有粒子
+    base_num: usize,
+    sources: Vec<u32>,
+    vertices: Vec<PosParticleIndex>,
+    triangles: Vec<[u32; 3]>,
+    // 与粒子相连的三角形
+    vertex_triangles: Vec<Vec<usize>>,
+    // (着色分组, 约束)，已移除的约束为 None
+    stretch: Vec<(usize, Option<StretchConstraintObj>)>,
+    bend: Vec<(usize, Option<BendingConstraintObj>)>,
+    vertex_stretch: Vec<Vec<usize>>,
+    vertex_bend: Vec<Vec<usize>>,
+    // 未断开的拉伸约束所在的边
+    live_edges: BTreeSet<(usize, usize)>,
+    // 分裂的粒子及其周围的粒子
+    touched: BTreeSet<usize>,
+}
+
+impl TearMesh {
+    fn new(fabric: &ClothFabric) -> Self {
+        let particles = &fabric.particles;
+        let triangles: Vec<[u32; 3]> = fabric
+            .vertices
+            .1
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .collect();
+        let mut vertex_triangles = vec![vec![]; particles.len()];
+        for (i, t) in triangles.iter().enumerate() {
+            for v in t {
+                vertex_triangles[*v as usize].push(i);
+            }
+        }
+
+        let stretch = with_groups(&fabric.stretch_constraints);
+        let mut vertex_stretch = vec![vec![]; particles.len()];
+        let mut live_edges = BTreeSet::new();
+        for (i, (_, c)) in stretch.iter().enumerate() {
+            let c = c.unwrap();
+            let (p0, p1) = (c.particle0 as usize, c.particle1 as usize);
+            vertex_stretch[p0].push(i);
+            vertex_stretch[p1].push(i);
+            live_edges.insert(edge(p0, p1));
+        }
+
+        let bend = with_groups(&fabric.bend_constraints);
+        let mut vertex_bend = vec![vec![]; particles.len()];
+        for (i, (_, c)) in bend.iter().enumerate() {
+            let c = c.unwrap();
+            for v in [c.v, c.b0, c.b1] {
+                vertex_bend[v as usize].push(i);
+            }
+        }
+
+        Self {
+            particles: particles.clone(),
+            base_num: particles.len(),
+            sources: vec![],
+            vertices: fabric.vertices.0.clone(),
+            triangles,
+            vertex_triangles,
+            stretch,
+            bend,
+            vertex_stretch,
+            vertex_bend,
+            live_edges,
+            touched: BTreeSet::new(),
+        }
+    }
+
+    fn tear(&mut self, index: usize) {
+        let Some(c) = self.stretch.get_mut(index).and_then(|(_, c)| c.take()) else {
+            return;
+        };
+        let (p0, p1) = (c.particle0 as usize, c.particle1 as usize);
+        self.live_edges.remove(&edge(p0, p1));
+        self.split(p0);
+        self.split(p1);
+    }
+
+    /// 按未断开的边把粒子周围的三角形分成若干连通的组，除第一组外，每组使用一个新的粒子
+    fn split(&mut self, v: usize) {
+        let fan = self.vertex_triangles[v].clone();
+        let mut component = vec![usize::MAX; fan.len()];
+        let mut count = 0;
+        for i in 0..fan.len() {
+            if component[i] != usize::MAX {
+                continue;
+            }
+            component[i] = count;
+            let mut stack = vec![i];
+            while let Some(j) = stack.pop() {
+                for k in 0..fan.len() {
+                    if component[k] == usize::MAX && self.connected_around(v, fan[j], fan[k]) {
+                        component[k] = count;
+                        stack.push(k);
+                    }
+                }
+            }
+            count += 1;
+        }
+        if count < 2 {
+            return;
+        }
+
+        let mut owners = vec![v];
+        let source = if v < self.base_num {
+            v as u32
+        } else {
+            self.sources[v - self.base_num]
+        };
+        for _ in 1..count {
+            let n = self.particles.len();
+            self.particles.push(self.particles[v]);
+            self.sources.push(source);
+            self.vertices.push(PosParticleIndex::new([n as u32, 0, 0]));
+            self.vertex_triangles.push(vec![]);
+            self.vertex_stretch.push(vec![]);
+            self.vertex_bend.push(vec![]);
+            owners.push(n);
+        }
+        self.vertex_triangles[v].clear();
+        for t in fan.iter() {
+            self.touched
+                .extend(self.triangles[*t].iter().map(|i| *i as usize));
+        }
+        self.touched.extend(owners.iter());
+        for (t, c) in fan.iter().zip(component.iter()) {
+            let owner = owners[*c];
+            for index in self.triangles[*t].iter_mut() {
+                if *index as usize == v {
+                    *index = owner as u32;
+                }
+            }
+            self.vertex_triangles[owner].push(*t);
+        }
+
+        // 约束分配给与其另一端相连的粒子，找不到时移除
+        for ci in core::mem::take(&mut self.vertex_stretch[v]) {
+            let Some(mut c) = self.stretch[ci].1 else {
+                continue;
+            };
+            let other = if c.particle0 as usize == v {
+                c.particle1
+            } else {
+                c.particle0
+            } as usize;
+            self.live_edges.remove(&edge(v, other));
+            match owners.iter().find(|o| self.is_attached(**o, other)) {
+                Some(&owner) => {
+                    if c.particle0 as usize == v {
+                        c.particle0 = owner as i32;
+                    } else {
+                        c.particle1 = owner as i32;
+                    }
+                    self.stretch[ci].1 = Some(c);
+                    self.vertex_stretch[owner].push(ci);
+                    self.live_edges.insert(edge(owner, other));
+                }
+                None => self.stretch[ci].1 = None,
+            }
+        }
+        for ci in core::mem::take(&mut self.vertex_bend[v]) {
+            let Some(mut c) = self.bend[ci].1 else {
+                continue;
+            };
+            let others: Vec<usize> = [c.v, c.b0, c.b1]
+                .iter()
+                .map(|i| *i as usize)
+                .filter(|i| *i != v)
+                .collect();
+            let owner = owners
+                .iter()
+                .find(|o| others.iter().all(|other| self.is_attached(**o, *other)));
+            match owner {
+                Some(&owner) => {
+                    for i in [&mut c.v, &mut c.b0, &mut c.b1] {
+                        if *i as usize == v {
+                            *i = owner as i32;
+                        }
+                    }
+                    self.bend[ci].1 = Some(c);
+                    self.vertex_bend[owner].push(ci);
+                }
+                None => self.bend[ci].1 = None,
+            }
+        }
+    }
+
+    /// 裂缝附近不含分裂粒子的约束也可能跨越裂缝（比如跨越两个格子的弯曲约束），
+    /// 移除其中粒子不再相连的约束
+    fn remove_detached_constraints(&mut self) {
+        for i in 0..self.stretch.len() {
+            if let Some(c) = self.stretch[i].1 {
+                let (p0, p1) = (c.particle0 as usize, c.particle1 as usize);
+                if self.is_detached(&[p0, p1]) {
+                    self.stretch[i].1 = None;
+                }
+            }
+        }
+        for i in 0..self.bend.len() {
+            if let Some(c) = self.bend[i].1 {
+                let list = [c.v as usize, c.b0 as usize, c.b1 as usize];
+                if self.is_detached(&list) {
+                    self.bend[i].1 = None;
+                }
+            }
+        }
+    }
+
+    fn is_detached(&self, list: &[usize]) -> bool {
+        if !list.iter().any(|p| self.touched.contains(p)) {
+            return false;
+        }
+        // 每个粒子至少与另一个粒子相连
+        list.iter()
+            .any(|a| !list.iter().any(|b| a != b && self.is_attached(*a, *b)))
+    }
+
+    // 两个三角形共享一条以 v 为端点且未断开的边
+    fn connected_around(&self, v: usize, t0: usize, t1: usize) -> bool {
+        t0 != t1
+            && self.triangles[t0].iter().any(|w| {
+                *w as usize != v
+                    && self.triangles[t1].contains(w)
+                    && self.live_edges.contains(&edge(v, *w as usize))
+            })
+    }
+
+    // owner 周围的三角形包含 other，或者与包含 other 的三角形共享一条边
+    fn is_attached(&self, owner: usize, other: usize) -> bool {
+        self.vertex_triangles[owner].iter().any(|t| {
+            let tri = &self.triangles[*t];
+            tri.contains(&(other as u32))
+                || self.vertex_triangles[other].iter().any(|t2| {
+                    let shared = tri
+                        .iter()
+                        .filter(|i| self.triangles[*t2].contains(i))
+                        .count();
+                    shared >= 2
+                })
+        })
+    }
+
+    fn into_fabric(self, fabric: &ClothFabric) -> ClothFabric {
+        ClothFabric {
+            horizontal_num: fabric.horizontal_num,
+            vertical_num: fabric.vertical_num,
+            particle_spacing: fabric.particle_spacing,
+            vertices: (self.vertices, self.triangles.concat()),
+            particles: self.particles,
+            stretch_constraints: regroup(self.stretch),
+            bend_constraints: regroup(self.bend),
+        }
+    }
+}
+
+fn with_groups<T: Copy>(constraints: &(Vec<MeshColoringObj>, Vec<T>)) -> Vec<(usize, Option<T>)> {
+    let mut list = Vec::with_capacity(constraints.1.len());
+    for (g, mc) in constraints.0.iter().enumerate() {
+        let range = mc.offset as usize..(mc.offset + mc.group_len) as usize;
+        list.extend(constraints.1[range].iter().map(|c| (g, Some(*c))));
+    }
+    list
+}
+
+/// 保留约束原有的着色分组：移除约束或把约束中的粒子替换为分裂出的新粒子，都不会让同组的约束共享粒子
+fn regroup<T>(list: Vec<(usize, Option<T>)>) -> (Vec<MeshColoringObj>, Vec<T>)
+where
+    T: bytemuck::Pod + bytemuck::Zeroable,
+{
+    let mut groups: Vec<Vec<T>> = vec![];
+    for (g, c) in list {
+        let Some(c) = c else {
+            continue;
+        };
+        if groups.len() <= g {
+            groups.resize_with(g + 1, Vec::new);
+        }
+        groups[g].push(c);
+    }
+    groups.retain(|g| !g.is_empty());
+    gen_coloring_and_flat_data(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pbd::{ClothFabricDesc, ClothMesh, ClothPinPattern};
+
+    const NUM_X: usize = 6;
+    const NUM_Y: usize = 5;
+
+    fn grid(pin_pattern: ClothPinPattern) -> ClothFabric {
+        let desc = ClothFabricDesc {
+            mesh: ClothMesh::Grid,
+            horizontal_num: NUM_X,
+            vertical_num: NUM_Y,
+            size: 1.0,
+            pin_pattern,
+            custom_pins: vec![],
+        };
+        ClothFabric::gen_fabric(&desc, 500.0, 400.0, 0.002)
+    }
+
+    // 两端粒子满足条件的拉伸约束的索引
+    fn stretch_indices(fabric: &ClothFabric, f: impl Fn(usize, usize) -> bool) -> Vec<u32> {
+        let list = fabric.stretch_constraints.1.iter().enumerate();
+        list.filter(|(_, c)| f(c.particle0 as usize, c.particle1 as usize))
+            .map(|(i, _)| i as u32)
+            .collect()
+    }
+
+    // 沿第 row 行切开：断开这一行上所有的水平边
+    fn cut_row(fabric: &ClothFabric, row: usize) -> Vec<u32> {
+        stretch_indices(fabric, |a, b| a / NUM_X == row && b / NUM_X == row)
+    }
+
+    /// 按共享顶点把三角形划分为连通块，返回每个粒子所在的连通块
+    fn particle_components(fabric: &ClothFabric) -> Vec<usize> {
+        let mut parent: Vec<usize> = (0..fabric.particles.len()).collect();
+        fn find(parent: &mut [usize], i: usize) -> usize {
+            let mut root = i;
+            while parent[root] != root {
+                root = parent[root];
+            }
+            parent[i] = root;
+            root
+        }
+        for tri in fabric.vertices.1.chunks_exact(3) {
+            for v in &tri[1..] {
+                let (a, b) = (
+                    find(&mut parent, tri[0] as usize),
+                    find(&mut parent, *v as usize),
+                );
+                parent[a] = b;
+            }
+        }
+        (0..parent.len()).map(|i| find(&mut parent, i)).collect()
+    }
+
+    fn component_count(fabric: &ClothFabric) -> usize {
+        let components = particle_components(fabric);
+        let used: BTreeSet<usize> = fabric
+            .vertices
+            .1
+            .iter()
+            .map(|v| components[*v as usize])
+            .collect();
+        used.len()
+    }
+
+    /// 约束不跨越裂缝，且同一着色分组内的约束不共享粒子
+    fn assert_valid_constraints(fabric: &ClothFabric) {
+        let components = particle_components(fabric);
+        let stretch = fabric
+            .stretch_constraints
+            .1
+            .iter()
+            .map(|c| vec![c.particle0, c.particle1]);
+        let bend = fabric
+            .bend_constraints
+            .1
+            .iter()
+            .map(|c| vec![c.v, c.b0, c.b1]);
+        for list in stretch.chain(bend) {
+            let list: Vec<usize> = list.iter().map(|i| *i as usize).collect();
+            assert!(list.iter().all(|i| *i < fabric.particles.len()));
+            assert!(list.iter().all(|i| components[*i] == components[list[0]]));
+        }
+        for mc in fabric.stretch_constraints.0.iter() {
+            let range = mc.offset as usize..(mc.offset + mc.group_len) as usize;
+            let mut used = BTreeSet::new();
+            for c in fabric.stretch_constraints.1[range].iter() {
+                assert!(used.insert(c.particle0) && used.insert(c.particle1));
+            }
+        }
+    }
+
+    #[test]
+    fn tear_one_edge() {
+        let fabric = grid(ClothPinPattern::None);
+        let a = 2 * NUM_X + 2;
+        let torn = stretch_indices(&fabric, |p0, p1| edge(p0, p1) == edge(a, a + 1));
+        assert_eq!(torn.len(), 1);
+
+        let (torn_fabric, sources) = tear_fabric(&fabric, &torn);
+        // 内部的一条边断开不会把粒子周围的三角形分开
+        assert!(sources.is_empty());
+        assert_eq!(torn_fabric.particles.len(), fabric.particles.len());
+        assert_eq!(torn_fabric.vertices.1, fabric.vertices.1);
+        assert_eq!(
+            torn_fabric.stretch_constraints.1.len(),
+            fabric.stretch_constraints.1.len() - 1
+        );
+        assert!(stretch_indices(&torn_fabric, |p0, p1| edge(p0, p1) == edge(a, a + 1)).is_empty());
+        assert_valid_constraints(&torn_fabric);
+    }
+
+    #[test]
+    fn cut_across_grid() {
+        let fabric = grid(ClothPinPattern::None);
+        let row = 2;
+        let (torn_fabric, sources) = tear_fabric(&fabric, &cut_row(&fabric, row));
+
+        // 切线上的每个粒子分裂为两个
+        assert_eq!(sources.len(), NUM_X);
+        assert!(sources.iter().all(|s| *s as usize / NUM_X == row));
+        assert_eq!(torn_fabric.particles.len(), NUM_X * NUM_Y + NUM_X);
+        for (i, s) in sources.iter().enumerate() {
+            let p = &torn_fabric.particles[NUM_X * NUM_Y + i];
+            assert_eq!(p.pos, fabric.particles[*s as usize].pos);
+            assert_eq!(p.uv_mass, fabric.particles[*s as usize].uv_mass);
+        }
+        assert_eq!(component_count(&torn_fabric), 2);
+        assert_eq!(torn_fabric.vertices.0.len(), torn_fabric.particles.len());
+        assert_valid_constraints(&torn_fabric);
+    }
+
+    #[test]
+    fn cut_below_pinned_row() {
+        let fabric = grid(ClothPinPattern::TopEdge);
+        let (torn_fabric, sources) = tear_fabric(&fabric, &cut_row(&fabric, 1));
+        assert_eq!(sources.len(), NUM_X);
+        assert_eq!(component_count(&torn_fabric), 2);
+        assert_valid_constraints(&torn_fabric);
+
+        // 固定的粒子不会分裂，被切下的部分不再包含固定粒子
+        let is_pinned = |p: &ParticleBufferObj| p.uv_mass[2] == 0.0;
+        let pinned: Vec<usize> = (0..torn_fabric.particles.len())
+            .filter(|i| is_pinned(&torn_fabric.particles[*i]))
+            .collect();
+        assert_eq!(pinned, (0..NUM_X).collect::<Vec<_>>());
+        let components = particle_components(&torn_fabric);
+        let bottom = components[NUM_X * (NUM_Y - 1)];
+        assert!(pinned.iter().all(|i| components[*i] != bottom));
+    }
+}
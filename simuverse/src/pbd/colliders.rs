@@ -0,0 +1,166 @@
+use super::ColliderUniform;
+use crate::{
+    PBDSetting,
+    geometries::{Cube, Sphere},
+    node::{BindGroupData, ViewNode, ViewNodeBuilder},
+    util::{BufferObj, vertex::PosNormalUv},
+};
+use alloc::{vec, vec::Vec};
+use app_surface::AppSurface;
+
+impl ColliderUniform {
+    /// particle_spacing: 布料静止时相邻粒子的间距
+    pub fn new(setting: &PBDSetting, particle_spacing: f32) -> Self {
+        let half_size = glam::Vec3::from(setting.box_size) * 0.5;
+        Self {
+            sphere: glam::Vec3::from(setting.sphere_center)
+                .extend(setting.sphere_radius)
+                .into(),
+            box_center: glam::Vec3::from(setting.box_center).extend(0.0).into(),
+            box_half_size: half_size.extend(0.0).into(),
+            ground_height: setting.ground_height,
+            ground_enabled: setting.ground as i32,
+            box_enabled: setting.box_collider as i32,
+            sphere_enabled: setting.sphere as i32,
+            friction: setting.friction,
+            thickness: particle_spacing * 0.3,
+            self_collision: setting.self_collision as i32,
+            self_thickness: particle_spacing * 0.9,
+        }
+    }
+
+    pub fn has_collider(&self) -> bool {
+        self.ground_enabled != 0 || self.box_enabled != 0 || self.sphere_enabled != 0
+    }
+
+    pub fn is_self_collision(&self) -> bool {
+        self.self_collision != 0
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct ModelUniform {
+    model: [[f32; 4]; 4],
+    color: [f32; 4],
+}
+
+struct ColliderNode {
+    model_buf: BufferObj,
+    node: ViewNode,
+    visible: bool,
+}
+
+impl ColliderNode {
+    fn new(
+        app: &AppSurface,
+        mvp_buf: &BufferObj,
+        shader: &wgpu::ShaderModule,
+        geometry: (Vec<PosNormalUv>, Vec<u32>),
+        color: [f32; 4],
+    ) -> Self {
+        let model_uniform = ModelUniform {
+            model: glam::Mat4::IDENTITY.to_cols_array_2d(),
+            color,
+        };
+        let model_buf =
+            BufferObj::create_uniform_buffer(&app.device, &model_uniform, Some("collider model"));
+        let bg_data = BindGroupData {
+            uniforms: vec![mvp_buf, &model_buf],
+            visibilitys: vec![
+                wgpu::ShaderStages::VERTEX,
+                wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+            ],
+            ..Default::default()
+        };
+        let node = ViewNodeBuilder::<PosNormalUv>::new(bg_data, shader)
+            .with_vertices_and_indices(geometry)
+            .with_use_depth_stencil(true)
+            .with_cull_mode(None)
+            .with_color_format(app.config.format)
+            .build(&app.device);
+        Self {
+            model_buf,
+            node,
+            visible: false,
+        }
+    }
+
+    fn update(&mut self, app: &AppSurface, visible: bool, model: glam::Mat4) {
+        self.visible = visible;
+        if visible {
+            app.queue.write_buffer(
+                &self.model_buf.buffer,
+                0,
+                bytemuck::cast_slice(&model.to_cols_array()),
+            );
+        }
+    }
+}
+
+/// 碰撞体的显示
+pub struct ColliderDisplay {
+    ground: ColliderNode,
+    sphere: ColliderNode,
+    cube: ColliderNode,
+}
+
+impl ColliderDisplay {
+    pub fn new(app: &AppSurface, mvp_buf: &BufferObj) -> Self {
+        let shader =
+            crate::util::shader::create_shader_module(&app.device, "pbd/collider_display", None);
+        let cube = Cube::new(1.0, 1.0, 1.0).generate_vertices();
+        Self {
+            ground: ColliderNode::new(app, mvp_buf, &shader, cube.clone(), [0.45, 0.45, 0.48, 1.0]),
+            sphere: ColliderNode::new(
+                app,
+                mvp_buf,
+                &shader,
+                Sphere::new(1.0, 40, 40).generate_vertices(),
+                [0.8, 0.55, 0.3, 1.0],
+            ),
+            cube: ColliderNode::new(app, mvp_buf, &shader, cube, [0.35, 0.55, 0.8, 1.0]),
+        }
+    }
+
+    pub fn update(&mut self, app: &AppSurface, setting: &PBDSetting) {
+        // 地面是一块很薄的长方体，其上表面位于地面高度
+        let ground_thickness = 0.02;
+        self.ground.update(
+            app,
+            setting.ground,
+            glam::Mat4::from_scale_rotation_translation(
+                glam::Vec3::new(8.0, ground_thickness, 8.0),
+                glam::Quat::IDENTITY,
+                glam::Vec3::new(0.0, setting.ground_height - ground_thickness * 0.5, 0.0),
+            ),
+        );
+        // 显示的比碰撞体略小，避免布料与碰撞体表面的穿插
+        self.sphere.update(
+            app,
+            setting.sphere,
+            glam::Mat4::from_scale_rotation_translation(
+                glam::Vec3::splat(setting.sphere_radius * 0.98),
+                glam::Quat::IDENTITY,
+                glam::Vec3::from(setting.sphere_center),
+            ),
+        );
+        self.cube.update(
+            app,
+            setting.box_collider,
+            glam::Mat4::from_scale_rotation_translation(
+                glam::Vec3::from(setting.box_size) * 0.98,
+                glam::Quat::IDENTITY,
+                glam::Vec3::from(setting.box_center),
+            ),
+        );
+    }
+
+    pub fn draw_by_pass<'a, 'b: 'a>(&'b self, rpass: &mut wgpu::RenderPass<'b>) {
+        for collider in [&self.ground, &self.sphere, &self.cube] {
+            if collider.visible {
+                collider.node.draw_by_pass(rpass);
+            }
+        }
+    }
+}
@@ -0,0 +1,444 @@
+//! 基于位置的流体
+//!
+//! Macklin & Müller 2013, Position Based Fluids。每个子步先预测位置，用计数排序构建空间哈希，
+//! 然后迭代求解密度约束（含人工压强），最后由位置更新速度，并加入 XSPH 粘性及涡度约束。
+//! 粒子的质量均为 1，邻域搜索沿用布料自碰撞的 bin hash。
+
+use crate::node::{BindGroupData, ComputeNode, ViewNode, ViewNodeBuilder};
+use crate::util::{BufferObj, vertex::PosUv};
+
+use super::{BinUniform, Cloth, FluidUniform, PBDBody};
+
+use alloc::{vec, vec::Vec};
+use app_surface::AppSurface;
+
+// 容器的范围
+const CONTAINER_MIN: glam::Vec3 = glam::Vec3::new(-0.8, -0.7, -0.3);
+const CONTAINER_MAX: glam::Vec3 = glam::Vec3::new(0.8, 0.7, 0.3);
+// 初始水柱占容器的比例，靠在容器左侧（溃坝）
+const DAM_EXTENT: glam::Vec3 = glam::Vec3::new(0.4, 0.8, 1.0);
+
+/// 生成流体所需的参数
+#[derive(Clone, PartialEq, Debug)]
+pub struct FluidDesc {
+    pub particle_count: usize,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct FluidParticle {
+    // 预测位置
+    pos: [f32; 4],
+    // 子步开始时的位置
+    old_pos: [f32; 4],
+    vel: [f32; 4],
+}
+
+pub struct Fluid {
+    mvp_uniform_data: crate::MVPMatUniform,
+    mvp_buf: BufferObj,
+    fluid_uniform_data: FluidUniform,
+    fluid_uniform_buf: BufferObj,
+    particle_buf: BufferObj,
+    // 粒子的初始状态，用于重置
+    rest_particles: Vec<FluidParticle>,
+
+    predict_node: ComputeNode,
+    // 空间哈希的计数排序
+    hash_clear_node: ComputeNode,
+    hash_count_node: ComputeNode,
+    prefix_sum_node: ComputeNode,
+    hash_scatter_node: ComputeNode,
+    lambda_node: ComputeNode,
+    delta_node: ComputeNode,
+    apply_node: ComputeNode,
+    velocity_node: ComputeNode,
+    vorticity_node: ComputeNode,
+    confine_node: ComputeNode,
+    display_node: ViewNode,
+    particle_num: u32,
+    substep_count: usize,
+    solver_iter_count: usize,
+}
+
+impl Fluid {
+    pub fn new(app_view: &AppSurface, desc: &FluidDesc) -> Self {
+        let viewport_size =
+            glam::Vec2::new(app_view.config.width as f32, app_view.config.height as f32);
+        let mvp_uniform_data = Cloth::get_mvp_uniform_data(viewport_size);
+        let mvp_buf = BufferObj::create_uniform_buffer(&app_view.device, &mvp_uniform_data, None);
+
+        let (rest_particles, spacing) = gen_dam_particles(desc.particle_count);
+        let particle_num = rest_particles.len() as u32;
+        // 粒子越密，每个子步内的移动距离相对粒子间距越大，需要更多子步才能保持稳定
+        let substep_count = ((0.12 / spacing).ceil() as usize).clamp(2, 8);
+        let solver_iter_count = 4;
+        let fluid_uniform_data = fluid_uniform(spacing, particle_num, substep_count);
+        let fluid_uniform_buf = BufferObj::create_uniform_buffer(
+            &app_view.device,
+            &fluid_uniform_data,
+            Some("fluid uniform"),
+        );
+
+        // 网格单元与核函数半径一致，相邻的 27 个单元即可覆盖邻域
+        let bin_num = (particle_num * 2).next_power_of_two();
+        let h = fluid_uniform_data.kernel_radius;
+        let bin_uniform = BinUniform {
+            bin_num: [bin_num as i32, 1, 1, 0],
+            bin_max_index: [bin_num as i32 - 1, 0, 0, 0],
+            bin_size: [h, h, h, 0.0],
+            pos_offset: [100.0, 100.0, 100.0, 0.0],
+            max_bin_count: 0,
+            padding: [0.0; 3],
+        };
+        let bin_uniform_buf = BufferObj::create_uniform_buffer(
+            &app_view.device,
+            &bin_uniform,
+            Some("fluid bin uniform"),
+        );
+
+        let mut particle_buf = BufferObj::create_storage_buffer(
+            &app_view.device,
+            &rest_particles,
+            Some("fluid particle buf"),
+        );
+        let bin_counts_buf = BufferObj::create_empty_storage_buffer(
+            &app_view.device,
+            bin_num as u64 * 4,
+            false,
+            Some("fluid bin_counts_buf"),
+        );
+        let bin_starts_buf = BufferObj::create_empty_storage_buffer(
+            &app_view.device,
+            (bin_num + 1) as u64 * 4,
+            false,
+            Some("fluid bin_starts_buf"),
+        );
+        let particle_slots_buf = BufferObj::create_empty_storage_buffer(
+            &app_view.device,
+            particle_num as u64 * 4,
+            false,
+            Some("fluid particle_slots_buf"),
+        );
+        let sorted_indices_buf = BufferObj::create_empty_storage_buffer(
+            &app_view.device,
+            particle_num as u64 * 4,
+            false,
+            Some("fluid sorted_indices_buf"),
+        );
+        let lambdas_buf = BufferObj::create_empty_storage_buffer(
+            &app_view.device,
+            particle_num as u64 * 4,
+            false,
+            Some("fluid lambdas_buf"),
+        );
+        let deltas_buf = BufferObj::create_empty_storage_buffer(
+            &app_view.device,
+            particle_num as u64 * 16,
+            false,
+            Some("fluid deltas_buf"),
+        );
+        let omegas_buf = BufferObj::create_empty_storage_buffer(
+            &app_view.device,
+            particle_num as u64 * 16,
+            false,
+            Some("fluid omegas_buf"),
+        );
+
+        let particle_workgroup_count = (particle_num.div_ceil(64), 1, 1);
+        let create_node = |shader_name: &'static str, bind_group_data: &BindGroupData| {
+            let shader =
+                crate::util::shader::create_shader_module(&app_view.device, shader_name, None);
+            ComputeNode::new(&app_view.device, bind_group_data, &shader)
+        };
+
+        let predict_node = create_node(
+            "pbd/fluid/pbf_predict",
+            &BindGroupData {
+                workgroup_count: particle_workgroup_count,
+                uniforms: vec![&fluid_uniform_buf],
+                storage_buffers: vec![&particle_buf],
+                ..Default::default()
+            },
+        );
+        let hash_clear_node = create_node(
+            "pbd/cloth_hash_clear",
+            &BindGroupData {
+                workgroup_count: (bin_num.div_ceil(64), 1, 1),
+                uniforms: vec![&bin_uniform_buf],
+                storage_buffers: vec![&bin_counts_buf],
+                ..Default::default()
+            },
+        );
+        let hash_count_node = create_node(
+            "pbd/fluid/pbf_hash_count",
+            &BindGroupData {
+                workgroup_count: particle_workgroup_count,
+                uniforms: vec![&bin_uniform_buf],
+                storage_buffers: vec![&particle_buf, &bin_counts_buf, &particle_slots_buf],
+                ..Default::default()
+            },
+        );
+        let prefix_sum_node = create_node(
+            "pbd/fluid/pbf_prefix_sum",
+            &BindGroupData {
+                workgroup_count: (1, 1, 1),
+                uniforms: vec![&bin_uniform_buf],
+                storage_buffers: vec![&bin_counts_buf, &bin_starts_buf],
+                ..Default::default()
+            },
+        );
+        let hash_scatter_node = create_node(
+            "pbd/fluid/pbf_hash_scatter",
+            &BindGroupData {
+                workgroup_count: particle_workgroup_count,
+                uniforms: vec![&bin_uniform_buf],
+                storage_buffers: vec![
+                    &particle_buf,
+                    &bin_starts_buf,
+                    &particle_slots_buf,
+                    &sorted_indices_buf,
+                ],
+                ..Default::default()
+            },
+        );
+
+        // 需要遍历邻域的着色器使用相同的前几个绑定
+        let neighbor_bind_group_data = BindGroupData {
+            workgroup_count: particle_workgroup_count,
+            uniforms: vec![&fluid_uniform_buf, &bin_uniform_buf],
+            storage_buffers: vec![&particle_buf, &bin_starts_buf, &sorted_indices_buf],
+            ..Default::default()
+        };
+        let mut bind_group_data = neighbor_bind_group_data.clone();
+        bind_group_data.storage_buffers.push(&lambdas_buf);
+        let lambda_node = create_node("pbd/fluid/pbf_lambda", &bind_group_data);
+        bind_group_data.storage_buffers.push(&deltas_buf);
+        let delta_node = create_node("pbd/fluid/pbf_delta", &bind_group_data);
+        let apply_node = create_node(
+            "pbd/fluid/pbf_apply",
+            &BindGroupData {
+                workgroup_count: particle_workgroup_count,
+                uniforms: vec![&fluid_uniform_buf],
+                storage_buffers: vec![&particle_buf, &deltas_buf],
+                ..Default::default()
+            },
+        );
+        let velocity_node = create_node(
+            "pbd/fluid/pbf_velocity",
+            &BindGroupData {
+                workgroup_count: particle_workgroup_count,
+                uniforms: vec![&fluid_uniform_buf],
+                storage_buffers: vec![&particle_buf],
+                ..Default::default()
+            },
+        );
+        let mut bind_group_data = neighbor_bind_group_data;
+        bind_group_data
+            .storage_buffers
+            .extend([&omegas_buf, &deltas_buf]);
+        let vorticity_node = create_node("pbd/fluid/pbf_vorticity", &bind_group_data);
+        let confine_node = create_node("pbd/fluid/pbf_confine", &bind_group_data);
+
+        // 以点精灵绘制粒子，每个实例是一个四边形
+        let display_shader =
+            crate::util::shader::create_shader_module(&app_view.device, "pbd/pbf_display", None);
+        particle_buf.read_only = true;
+        let bind_group_data = BindGroupData {
+            uniforms: vec![&mvp_buf, &fluid_uniform_buf],
+            storage_buffers: vec![&particle_buf],
+            visibilitys: vec![
+                wgpu::ShaderStages::VERTEX,
+                wgpu::ShaderStages::VERTEX,
+                wgpu::ShaderStages::VERTEX,
+            ],
+            ..Default::default()
+        };
+        let display_node = ViewNodeBuilder::<PosUv>::new(bind_group_data, &display_shader)
+            .with_use_depth_stencil(true)
+            .with_cull_mode(None)
+            .with_vertices_and_indices(crate::geometries::Plane::new(1, 1).generate_vertices())
+            .with_color_format(app_view.config.format)
+            .build(&app_view.device);
+
+        Self {
+            mvp_uniform_data,
+            mvp_buf,
+            fluid_uniform_data,
+            fluid_uniform_buf,
+            particle_buf,
+            rest_particles,
+            predict_node,
+            hash_clear_node,
+            hash_count_node,
+            prefix_sum_node,
+            hash_scatter_node,
+            lambda_node,
+            delta_node,
+            apply_node,
+            velocity_node,
+            vorticity_node,
+            confine_node,
+            display_node,
+            particle_num,
+            substep_count,
+            solver_iter_count,
+        }
+    }
+}
+
+impl PBDBody for Fluid {
+    fn update_by(&mut self, app: &AppSurface, setting: &crate::PBDSetting) {
+        let mut fluid_uniform_data = self.fluid_uniform_data;
+        fluid_uniform_data.gravity = setting.gravity * -14.0;
+        fluid_uniform_data.damping = (setting.damping - 0.3) * 0.002;
+        fluid_uniform_data.viscosity = setting.viscosity;
+        // 涡度约束的力与核函数半径成正比，保证不同的粒子数下效果一致
+        fluid_uniform_data.vorticity = setting.vorticity * fluid_uniform_data.kernel_radius * 2.0;
+        if fluid_uniform_data != self.fluid_uniform_data {
+            self.fluid_uniform_data = fluid_uniform_data;
+            app.queue.write_buffer(
+                &self.fluid_uniform_buf.buffer,
+                0,
+                bytemuck::bytes_of(&self.fluid_uniform_data),
+            );
+        }
+    }
+
+    fn reset(&mut self, app: &AppSurface) {
+        app.queue.write_buffer(
+            &self.particle_buf.buffer,
+            0,
+            bytemuck::cast_slice(&self.rest_particles),
+        );
+    }
+
+    fn resize(&mut self, app: &AppSurface) -> bool {
+        self.mvp_uniform_data = Cloth::get_mvp_uniform_data(glam::Vec2::new(
+            app.config.width as f32,
+            app.config.height as f32,
+        ));
+        app.queue.write_buffer(
+            &self.mvp_buf.buffer,
+            0,
+            bytemuck::bytes_of(&self.mvp_uniform_data),
+        );
+        true
+    }
+
+    fn set_mvp(&mut self, app: &AppSurface, mvp_uniform_data: &crate::MVPMatUniform) {
+        self.mvp_uniform_data = *mvp_uniform_data;
+        app.queue.write_buffer(
+            &self.mvp_buf.buffer,
+            0,
+            bytemuck::bytes_of(&self.mvp_uniform_data),
+        );
+    }
+
+    fn compute(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("fluid solver pass"),
+            ..Default::default()
+        });
+        for _ in 0..self.substep_count {
+            self.predict_node.compute_by_pass(&mut cpass);
+            // 迭代过程中粒子的移动量远小于网格单元，只在子步开始时构建一次空间哈希
+            self.hash_clear_node.compute_by_pass(&mut cpass);
+            self.hash_count_node.compute_by_pass(&mut cpass);
+            self.prefix_sum_node.compute_by_pass(&mut cpass);
+            self.hash_scatter_node.compute_by_pass(&mut cpass);
+            for _ in 0..self.solver_iter_count {
+                self.lambda_node.compute_by_pass(&mut cpass);
+                self.delta_node.compute_by_pass(&mut cpass);
+                self.apply_node.compute_by_pass(&mut cpass);
+            }
+            self.velocity_node.compute_by_pass(&mut cpass);
+            self.vorticity_node.compute_by_pass(&mut cpass);
+            self.confine_node.compute_by_pass(&mut cpass);
+        }
+    }
+
+    fn draw_by_rpass<'b, 'a: 'b>(&'a self, rpass: &mut wgpu::RenderPass<'b>) {
+        self.display_node
+            .draw_by_instance_count(rpass, self.particle_num);
+    }
+}
+
+/// 粒子按立方晶格排列成靠在容器左侧的水柱，间距由粒子数决定。返回粒子及粒子间距
+fn gen_dam_particles(particle_count: usize) -> (Vec<FluidParticle>, f32) {
+    let dam_size = (CONTAINER_MAX - CONTAINER_MIN) * DAM_EXTENT;
+    let spacing = (dam_size.x * dam_size.y * dam_size.z / particle_count.max(1) as f32).cbrt();
+    let dims = (dam_size / spacing).floor().max(glam::Vec3::ONE).as_uvec3();
+    let origin = CONTAINER_MIN + spacing * 0.5;
+    let mut particles = Vec::with_capacity((dims.x * dims.y * dims.z) as usize);
+    for z in 0..dims.z {
+        for y in 0..dims.y {
+            for x in 0..dims.x {
+                let p = origin + glam::UVec3::new(x, y, z).as_vec3() * spacing;
+                let pos = [p.x, p.y, p.z, 0.0];
+                particles.push(FluidParticle {
+                    pos,
+                    old_pos: pos,
+                    vel: [0.0; 4],
+                });
+            }
+        }
+    }
+    (particles, spacing)
+}
+
+fn fluid_uniform(spacing: f32, particle_num: u32, substep_count: usize) -> FluidUniform {
+    // 核函数半径取粒子间距的 2 倍，邻域内约有 30 个粒子
+    let h = spacing * 2.0;
+    // 静止密度及约束梯度的平方和取立方晶格内部粒子的值
+    let (mut rest_density, mut sum_grad2) = (0.0, 0.0);
+    let n = 2;
+    for z in -n..=n {
+        for y in -n..=n {
+            for x in -n..=n {
+                let r = glam::IVec3::new(x, y, z).as_vec3() * spacing;
+                rest_density += poly6(r, h);
+                sum_grad2 += spiky_grad(r, h).length_squared();
+            }
+        }
+    }
+    sum_grad2 /= rest_density * rest_density;
+    let radius = spacing * 0.5;
+    FluidUniform {
+        bounds_min: (CONTAINER_MIN + radius).extend(0.0).into(),
+        bounds_max: (CONTAINER_MAX - radius).extend(0.0).into(),
+        gravity: -9.8,
+        dt: 0.016 / substep_count as f32,
+        kernel_radius: h,
+        rest_density,
+        relaxation: sum_grad2 * 0.01,
+        // λ 的量纲与约束梯度有关，k 按梯度的平方和缩放后与粒子间距无关
+        scorr_k: 0.1 / sum_grad2,
+        // Δq = 0.2h
+        scorr_invert_w: 1.0 / poly6(glam::Vec3::new(0.2 * h, 0.0, 0.0), h),
+        viscosity: 0.01,
+        vorticity: 0.0,
+        damping: 0.0,
+        particle_num,
+        padding: 0.0,
+    }
+}
+
+// 与 pbd/struct/fluid.wgsl 中的核函数一致
+fn poly6(r: glam::Vec3, h: f32) -> f32 {
+    let r2 = r.length_squared();
+    if r2 >= h * h {
+        return 0.0;
+    }
+    let d = h * h - r2;
+    315.0 / (64.0 * core::f32::consts::PI * h.powi(9)) * d * d * d
+}
+
+fn spiky_grad(r: glam::Vec3, h: f32) -> glam::Vec3 {
+    let len = r.length();
+    if len >= h || len < 0.000001 {
+        return glam::Vec3::ZERO;
+    }
+    let d = h - len;
+    -45.0 / (core::f32::consts::PI * h.powi(6)) * d * d * (r / len)
+}
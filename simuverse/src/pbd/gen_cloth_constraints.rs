@@ -1,6 +1,10 @@
 use super::cloth_fabric::ParticleBufferObj;
-use super::{BendingConstraintObj, MeshColoringObj, StretchConstraintObj};
-use alloc::{vec, vec::Vec};
+use super::tet_mesh::TetMesh;
+use super::{
+    BendingConstraintObj, MeshColoringObj, StretchConstraintObj, TwistConstraintObj,
+    VolumeConstraintObj,
+};
+use alloc::{collections::BTreeMap, vec, vec::Vec};
 
 #[allow(dead_code)]
 pub fn generate_bend_constraints(
@@ -191,6 +195,206 @@ pub fn generate_stretch_constraints(
     group_distance_constraints(horizontal_num, &constraints)
 }
 
+// 着色分组及按分组排列的约束
+type ColoredConstraints<T> = (Vec<MeshColoringObj>, Vec<T>);
+
+/// 由任意三角形网格的拓扑生成约束：每条边一个拉伸约束，
+/// 每对共边三角形以其中一个公共顶点及两个对角顶点生成一个弯曲约束
+pub fn generate_mesh_constraints(
+    particles: &[ParticleBufferObj],
+    indices: &[u32],
+) -> (
+    ColoredConstraints<StretchConstraintObj>,
+    ColoredConstraints<BendingConstraintObj>,
+) {
+    // 边 -> 对角顶点
+    let mut edges: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+    for tri in indices.chunks_exact(3) {
+        for i in 0..3 {
+            let (a, b) = (tri[i] as usize, tri[(i + 1) % 3] as usize);
+            let key = if a < b { (a, b) } else { (b, a) };
+            edges
+                .entry(key)
+                .or_default()
+                .push(tri[(i + 2) % 3] as usize);
+        }
+    }
+
+    let mut stretch: Vec<StretchConstraintObj> = Vec::with_capacity(edges.len());
+    let mut bend: Vec<BendingConstraintObj> = Vec::with_capacity(edges.len());
+    for (&(a, b), opposite) in edges.iter() {
+        stretch.push(get_constraint(particles, a, b));
+        // 非流形的边（超过两个三角形共用）不生成弯曲约束
+        if let [c, d] = opposite[..] {
+            let h0 = get_h0(&particles[a], &particles[c], &particles[d]);
+            bend.push(BendingConstraintObj {
+                v: a as i32,
+                b0: c as i32,
+                b1: d as i32,
+                h0,
+            });
+        }
+    }
+
+    let stretch = color_constraints(particles.len(), stretch, |c| {
+        vec![c.particle0 as usize, c.particle1 as usize]
+    });
+    let bend = color_constraints(particles.len(), bend, |c| {
+        vec![c.v as usize, c.b0 as usize, c.b1 as usize]
+    });
+    (stretch, bend)
+}
+
+/// 四面体网格的约束：每条边一个距离约束，每个四面体一个体积约束
+pub fn generate_tet_constraints(
+    particles: &[ParticleBufferObj],
+    mesh: &TetMesh,
+) -> (
+    ColoredConstraints<StretchConstraintObj>,
+    ColoredConstraints<VolumeConstraintObj>,
+) {
+    let mut edges: BTreeMap<(usize, usize), ()> = BTreeMap::new();
+    let mut volumes: Vec<VolumeConstraintObj> = Vec::with_capacity(mesh.tets.len());
+    for tet in mesh.tets.iter() {
+        for i in 0..4 {
+            for j in (i + 1)..4 {
+                let (a, b) = (tet[i] as usize, tet[j] as usize);
+                edges.insert(if a < b { (a, b) } else { (b, a) }, ());
+            }
+        }
+        volumes.push(VolumeConstraintObj {
+            particles: tet.map(|v| v as i32),
+            rest_volume: mesh.tet_volume(tet),
+            lambda: 0.0,
+            padding: [0.0; 2],
+        });
+    }
+    let stretch = edges
+        .keys()
+        .map(|&(a, b)| get_constraint(particles, a, b))
+        .collect();
+
+    let stretch = color_constraints(particles.len(), stretch, |c| {
+        vec![c.particle0 as usize, c.particle1 as usize]
+    });
+    let volumes = color_constraints(particles.len(), volumes, |c| {
+        c.particles.iter().map(|v| *v as usize).collect()
+    });
+    (stretch, volumes)
+}
+
+/// 发丝的约束：相邻粒子间的距离约束，相隔一个、两个粒子的弯曲约束，以及相邻两段之间的扭转约束。
+/// 粒子按发丝依次排列，每根发丝 `strand_len` 个粒子；之后是幽灵粒子，每根发丝 `strand_len - 1` 个，
+/// 与所在段的两个端点之间用距离约束连接
+pub fn generate_strand_constraints(
+    particles: &[ParticleBufferObj],
+    strand_count: usize,
+    strand_len: usize,
+) -> (
+    ColoredConstraints<StretchConstraintObj>,
+    ColoredConstraints<BendingConstraintObj>,
+    ColoredConstraints<TwistConstraintObj>,
+) {
+    let mut stretch: Vec<StretchConstraintObj> = Vec::with_capacity(particles.len() * 2);
+    let mut bend: Vec<BendingConstraintObj> = Vec::with_capacity(particles.len() * 2);
+    let mut twist: Vec<TwistConstraintObj> = Vec::with_capacity(particles.len());
+    let ghost_start = strand_count * strand_len;
+    for s in 0..strand_count {
+        let start = s * strand_len;
+        let ghost = |j: usize| ghost_start + s * (strand_len - 1) + j;
+        for i in start..(start + strand_len - 1) {
+            stretch.push(get_constraint(particles, i, i + 1));
+        }
+        for j in 0..(strand_len - 1) {
+            stretch.push(get_constraint(particles, ghost(j), start + j));
+            stretch.push(get_constraint(particles, ghost(j), start + j + 1));
+        }
+        // 跨度更大的弯曲约束让整根发丝的弯曲更均匀
+        for step in [1, 2] {
+            for v in (start + step)..(start + strand_len - step) {
+                let (b0, b1) = (v - step, v + step);
+                bend.push(BendingConstraintObj {
+                    v: v as i32,
+                    b0: b0 as i32,
+                    b1: b1 as i32,
+                    h0: get_h0(&particles[v], &particles[b0], &particles[b1]),
+                });
+            }
+        }
+        for j in 0..(strand_len - 2) {
+            let p = [start + j, start + j + 1, start + j + 2];
+            let g = [ghost(j), ghost(j + 1)];
+            twist.push(TwistConstraintObj {
+                particles: p.map(|i| i as i32),
+                ghosts: g.map(|i| i as i32),
+                rest_twist: twist_angle(
+                    p.map(|i| glam::Vec4::from(particles[i].pos).truncate()),
+                    g.map(|i| glam::Vec4::from(particles[i].pos).truncate()),
+                ),
+                padding: [0.0; 2],
+            });
+        }
+    }
+
+    let stretch = color_constraints(particles.len(), stretch, |c| {
+        vec![c.particle0 as usize, c.particle1 as usize]
+    });
+    let bend = color_constraints(particles.len(), bend, |c| {
+        vec![c.v as usize, c.b0 as usize, c.b1 as usize]
+    });
+    // 扭转约束只修正幽灵粒子
+    let twist = color_constraints(particles.len(), twist, |c| {
+        c.ghosts.iter().map(|g| *g as usize).collect()
+    });
+    (stretch, bend, twist)
+}
+
+/// 把与 `t0` 垂直的 `u` 沿中心线平行移动到与 `t1` 垂直，即绕 `t0 × t1` 旋转的最小旋转
+pub(super) fn parallel_transport(u: glam::Vec3, t0: glam::Vec3, t1: glam::Vec3) -> glam::Vec3 {
+    let c = 1.0 + t0.dot(t1);
+    if c < f32::EPSILON {
+        return u;
+    }
+    u - u.dot(t1) / c * (t0 + t1)
+}
+
+// 相邻两段的材料标架之间的扭转角：前一段的标架平行移动到后一段上，与后一段的标架之间绕中心线的夹角。
+// 与 strand_twist_solver 着色器里的计算一致
+fn twist_angle(p: [glam::Vec3; 3], g: [glam::Vec3; 2]) -> f32 {
+    let (t0, t1) = ((p[1] - p[0]).normalize(), (p[2] - p[1]).normalize());
+    let frame = |g: glam::Vec3, p: glam::Vec3, t: glam::Vec3| (g - p).reject_from_normalized(t);
+    let transported = parallel_transport(frame(g[0], p[0], t0).normalize(), t0, t1);
+    let u1 = frame(g[1], p[1], t1).normalize();
+    transported.cross(u1).dot(t1).atan2(transported.dot(u1))
+}
+
+// 贪心着色：约束加入其所有顶点都未使用过的编号最小的分组，分组数由网格的顶点度数决定
+fn color_constraints<T>(
+    particle_num: usize,
+    constraints: Vec<T>,
+    vertices_of: impl Fn(&T) -> Vec<usize>,
+) -> (Vec<MeshColoringObj>, Vec<T>)
+where
+    T: bytemuck::Pod + bytemuck::Zeroable,
+{
+    let mut vertex_groups: Vec<Vec<usize>> = vec![vec![]; particle_num];
+    let mut groups: Vec<Vec<T>> = vec![];
+    for c in constraints {
+        let vertices = vertices_of(&c);
+        let g = (0..)
+            .find(|g| vertices.iter().all(|v| !vertex_groups[*v].contains(g)))
+            .unwrap();
+        for v in vertices {
+            vertex_groups[v].push(g);
+        }
+        if groups.len() <= g {
+            groups.push(vec![]);
+        }
+        groups[g].push(c);
+    }
+    gen_coloring_and_flat_data::<T>(groups)
+}
+
 fn gen_bend_constraint(
     particles: &[ParticleBufferObj],
     horizontal_num: usize,
@@ -274,7 +478,9 @@ fn get_constraint(
     }
 }
 
-fn gen_coloring_and_flat_data<T>(constraints: Vec<Vec<T>>) -> (Vec<MeshColoringObj>, Vec<T>)
+pub(super) fn gen_coloring_and_flat_data<T>(
+    constraints: Vec<Vec<T>>,
+) -> (Vec<MeshColoringObj>, Vec<T>)
 where
     T: bytemuck::Pod + bytemuck::Zeroable,
 {
@@ -335,3 +541,170 @@ fn get_h0(v: &ParticleBufferObj, b0: &ParticleBufferObj, b1: &ParticleBufferObj)
     ];
     minus_length(&v.pos, &centroid)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::BTreeSet;
+
+    fn particles(positions: &[[f32; 3]]) -> Vec<ParticleBufferObj> {
+        positions
+            .iter()
+            .map(|p| ParticleBufferObj {
+                pos: [p[0], p[1], p[2], 0.0],
+                old_pos: [p[0], p[1], p[2], 0.0],
+                accelerate: [0.0; 4],
+                uv_mass: [0.0, 0.0, 1.0, 0.0],
+                connect: [0; 4],
+            })
+            .collect()
+    }
+
+    // 同一着色分组内的约束不能共享粒子
+    fn assert_coloring<T: Copy>(
+        (colorings, constraints): &ColoredConstraints<T>,
+        vertices_of: impl Fn(&T) -> Vec<i32>,
+    ) {
+        let mut total = 0;
+        for mc in colorings.iter() {
+            assert_eq!(mc.offset, total);
+            let range = mc.offset as usize..(mc.offset + mc.group_len) as usize;
+            let mut used = BTreeSet::new();
+            for c in constraints[range].iter() {
+                assert!(vertices_of(c).into_iter().all(|v| used.insert(v)));
+            }
+            total += mc.group_len;
+        }
+        assert_eq!(total as usize, constraints.len());
+    }
+
+    #[test]
+    fn quad_constraints() {
+        let particles = particles(&[
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ]);
+        let (stretch, bend) = generate_mesh_constraints(&particles, &[0, 1, 2, 0, 2, 3]);
+
+        // 4 条边及 1 条对角线
+        let mut edges: Vec<(i32, i32, f32)> = stretch
+            .1
+            .iter()
+            .map(|c| {
+                (
+                    c.particle0.min(c.particle1),
+                    c.particle0.max(c.particle1),
+                    c.rest_length,
+                )
+            })
+            .collect();
+        edges.sort_by_key(|e| (e.0, e.1));
+        let expected = [
+            (0, 1, 1.0),
+            (0, 2, 2.0_f32.sqrt()),
+            (0, 3, 1.0),
+            (1, 2, 1.0),
+            (2, 3, 1.0),
+        ];
+        assert_eq!(edges.len(), expected.len());
+        for (e, x) in edges.iter().zip(expected.iter()) {
+            assert_eq!((e.0, e.1), (x.0, x.1));
+            assert!((e.2 - x.2).abs() < 1e-6);
+        }
+
+        // 只有对角线被两个三角形共用
+        assert_eq!(bend.1.len(), 1);
+        let c = bend.1[0];
+        assert!([0, 2].contains(&c.v));
+        let mut opposite = [c.b0, c.b1];
+        opposite.sort();
+        assert_eq!(opposite, [1, 3]);
+        // 静止状态下 v 到三个顶点重心的距离
+        assert!((c.h0 - 2.0_f32.sqrt() / 3.0).abs() < 1e-6);
+
+        assert_coloring(&stretch, |c| vec![c.particle0, c.particle1]);
+        assert_coloring(&bend, |c| vec![c.v, c.b0, c.b1]);
+    }
+
+    #[test]
+    fn closed_mesh_constraints() {
+        // 四面体的表面：每条边都被两个三角形共用
+        let particles = particles(&[
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+        ]);
+        let indices = [0, 2, 1, 0, 1, 3, 1, 2, 3, 0, 3, 2];
+        let (stretch, bend) = generate_mesh_constraints(&particles, &indices);
+        assert_eq!(stretch.1.len(), 6);
+        assert_eq!(bend.1.len(), 6);
+        // 每个拉伸约束都与其它约束共享粒子，需要多个分组
+        assert!(stretch.0.len() >= 3);
+        assert_coloring(&stretch, |c| vec![c.particle0, c.particle1]);
+        assert_coloring(&bend, |c| vec![c.v, c.b0, c.b1]);
+    }
+
+    #[test]
+    fn non_manifold_edge_has_no_bend_constraint() {
+        // 三个三角形共用边 0-1
+        let particles = particles(&[
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.5, 1.0, 0.0],
+            [0.5, -1.0, 0.0],
+            [0.5, 0.0, 1.0],
+        ]);
+        let (stretch, bend) = generate_mesh_constraints(&particles, &[0, 1, 2, 1, 0, 3, 0, 1, 4]);
+        assert_eq!(stretch.1.len(), 7);
+        // 其余的边都只属于一个三角形
+        assert!(bend.1.is_empty());
+    }
+
+    #[test]
+    fn strand_twist_angle() {
+        let p = [glam::Vec3::ZERO, glam::Vec3::X, glam::Vec3::X * 2.0];
+        let angle = 0.3_f32;
+        let g = [
+            glam::Vec3::new(0.5, 1.0, 0.0),
+            glam::Vec3::new(1.5, angle.cos(), angle.sin()),
+        ];
+        assert!((twist_angle(p, g) - angle).abs() < 1e-5);
+
+        // 弯折处平行移动之后的标架没有扭转
+        let p = [
+            glam::Vec3::ZERO,
+            glam::Vec3::X,
+            glam::Vec3::new(1.0, 1.0, 0.0),
+        ];
+        let g = [
+            glam::Vec3::new(0.5, 0.0, 1.0),
+            glam::Vec3::new(1.0, 0.5, 1.0),
+        ];
+        assert!(twist_angle(p, g).abs() < 1e-5);
+    }
+
+    #[test]
+    fn strand_constraints() {
+        // 两根各 4 个粒子的发丝，之后是每段的幽灵粒子
+        let mut positions = vec![];
+        for z in [0.0, 1.0] {
+            positions.extend((0..4).map(|i| [i as f32, 0.0, z]));
+        }
+        for z in [0.0, 1.0] {
+            positions.extend((0..3).map(|i| [i as f32 + 0.5, 0.5, z]));
+        }
+        let particles = particles(&positions);
+        let (stretch, bend, twist) = generate_strand_constraints(&particles, 2, 4);
+        assert_eq!(stretch.1.len(), 2 * (3 + 3 * 2));
+        assert_eq!(bend.1.len(), 2 * 2);
+        assert_eq!(twist.1.len(), 2 * 2);
+        let c = twist.1.iter().find(|c| c.ghosts == [9, 10]).unwrap();
+        assert_eq!(c.particles, [1, 2, 3]);
+        assert!(twist.1.iter().all(|c| c.rest_twist.abs() < 1e-5));
+        assert_coloring(&stretch, |c| vec![c.particle0, c.particle1]);
+        assert_coloring(&twist, |c| c.ghosts.to_vec());
+    }
+}
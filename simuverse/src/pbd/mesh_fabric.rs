@@ -0,0 +1,165 @@
+//! 由任意三角形网格生成布料
+//!
+//! 约束及网格着色只依赖网格的拓扑，可用于任意形状的旗帜、衣服及封闭的网格。
+
+use super::cloth_fabric::ParticleBufferObj;
+use super::triangle_mesh::TriangleMesh;
+use super::{ClothFabric, ClothFabricDesc, ClothPinPattern};
+use crate::util::vertex::PosParticleIndex;
+use alloc::{vec, vec::Vec};
+
+impl ClothFabric {
+    /// 网格居中放置在 z = 0 处，xy 平面上的最大尺寸缩放到 `width`
+    pub fn from_triangle_mesh(desc: &ClothFabricDesc, mesh: &TriangleMesh, width: f32) -> Self {
+        let (min, max) = mesh.positions.iter().fold(
+            (glam::Vec3::splat(f32::MAX), glam::Vec3::splat(f32::MIN)),
+            |(min, max), p| (min.min((*p).into()), max.max((*p).into())),
+        );
+        let center = (min + max) * 0.5;
+        let scale = width / (max - min).truncate().max_element().max(f32::EPSILON);
+        let positions: Vec<glam::Vec3> = mesh
+            .positions
+            .iter()
+            .map(|p| (glam::Vec3::from(*p) - center) * scale)
+            .collect();
+        let (min, max) = ((min - center) * scale, (max - center) * scale);
+        let size = (max - min).truncate().max(glam::Vec2::splat(f32::EPSILON));
+
+        // 每个顶点的面积等于与之相连的每个三角形面积的 1/3 之和
+        let mut vertex_areas = vec![0.0; positions.len()];
+        let mut total_area = 0.0;
+        for tri in mesh.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| tri[i] as usize);
+            let area = (positions[b] - positions[a])
+                .cross(positions[c] - positions[a])
+                .length()
+                * 0.5;
+            total_area += area;
+            for v in [a, b, c] {
+                vertex_areas[v] += area / 3.0;
+            }
+        }
+        let mean_area = total_area / (mesh.indices.len() / 3) as f32;
+        // 与同样三角形面积的矩形网格的粒子间距一致
+        let particle_spacing = (mean_area * 2.0).sqrt();
+
+        let pinned_flags = mesh_pinned_flags(desc, &positions, particle_spacing, min, max);
+        let mut particles: Vec<ParticleBufferObj> = positions
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let uv = match mesh.uvs.get(i) {
+                    Some(uv) => *uv,
+                    // 没有纹理坐标时使用 xy 平面上的投影
+                    None => [(p.x - min.x) / size.x, (max.y - p.y) / size.y],
+                };
+                // 面积与矩形网格内部顶点相同的粒子，其质量的倒数为 0.1
+                let invert_mass = if pinned_flags[i] {
+                    0.0
+                } else {
+                    (0.1 * particle_spacing * particle_spacing / vertex_areas[i]).clamp(0.02, 1.0)
+                };
+                let pos = [p.x, p.y, p.z, 0.0];
+                ParticleBufferObj {
+                    pos,
+                    old_pos: pos,
+                    accelerate: [0.0; 4],
+                    uv_mass: [uv[0], uv[1], invert_mass, 0.0],
+                    connect: [0; 4],
+                }
+            })
+            .collect();
+
+        cal_connected_particles(&mut particles, &mesh.indices);
+
+        let vertex_data = (0..particles.len())
+            .map(|i| PosParticleIndex::new([i as u32, 0, 0]))
+            .collect();
+        let (stretch_constraints, bend_constraints) =
+            super::gen_cloth_constraints::generate_mesh_constraints(&particles, &mesh.indices);
+
+        Self {
+            horizontal_num: particles.len(),
+            vertical_num: 1,
+            particle_spacing,
+            vertices: (vertex_data, mesh.indices.clone()),
+            particles,
+            stretch_constraints,
+            bend_constraints,
+        }
+    }
+}
+
+/// 用与粒子相连的两个三角形计算法线，不属于任何三角形的粒子保持不变
+pub(super) fn cal_connected_particles(particles: &mut [ParticleBufferObj], indices: &[u32]) {
+    let mut vertex_triangles: Vec<Vec<usize>> = vec![vec![]; particles.len()];
+    for (t, tri) in indices.chunks_exact(3).enumerate() {
+        for v in tri.iter() {
+            vertex_triangles[*v as usize].push(t);
+        }
+    }
+    for (v, triangles) in vertex_triangles.iter().enumerate() {
+        if triangles.is_empty() {
+            continue;
+        }
+        let mut connect = [0; 4];
+        for (k, t) in [triangles[0], triangles[triangles.len() / 2]]
+            .into_iter()
+            .enumerate()
+        {
+            let tri = &indices[t * 3..t * 3 + 3];
+            let i = tri.iter().position(|p| *p as usize == v).unwrap();
+            connect[k * 2] = tri[(i + 2) % 3] as i32;
+            connect[k * 2 + 1] = tri[(i + 1) % 3] as i32;
+        }
+        particles[v].connect = connect;
+    }
+}
+
+fn mesh_pinned_flags(
+    desc: &ClothFabricDesc,
+    positions: &[glam::Vec3],
+    spacing: f32,
+    min: glam::Vec3,
+    max: glam::Vec3,
+) -> Vec<bool> {
+    let mut flags = vec![false; positions.len()];
+    // 离 xy 平面上的点最近的粒子，相近的粒子中选取最靠前的一个
+    let mut pin_nearest = |target: glam::Vec2| {
+        let nearest = positions
+            .iter()
+            .map(|p| (p.truncate() - target).length())
+            .fold(f32::MAX, f32::min);
+        let index = (0..positions.len())
+            .filter(|i| (positions[*i].truncate() - target).length() <= nearest + spacing)
+            .max_by(|a, b| positions[*a].z.total_cmp(&positions[*b].z));
+        if let Some(index) = index {
+            flags[index] = true;
+        }
+    };
+    let corners = [
+        glam::Vec2::new(min.x, max.y),
+        glam::Vec2::new(max.x, max.y),
+        glam::Vec2::new(min.x, min.y),
+        glam::Vec2::new(max.x, min.y),
+    ];
+    match desc.pin_pattern {
+        ClothPinPattern::TopEdge => {
+            for (flag, p) in flags.iter_mut().zip(positions.iter()) {
+                *flag = p.y > max.y - spacing;
+            }
+        }
+        ClothPinPattern::TwoCorners => corners[..2].iter().for_each(|c| pin_nearest(*c)),
+        ClothPinPattern::FourCorners => corners.iter().for_each(|c| pin_nearest(*c)),
+        ClothPinPattern::Custom => {
+            for uv in desc.custom_pins.iter() {
+                pin_nearest(glam::Vec2::new(
+                    min.x + uv[0] * (max.x - min.x),
+                    max.y - uv[1] * (max.y - min.y),
+                ));
+            }
+        }
+        ClothPinPattern::None => {}
+    }
+    flags
+}
@@ -2,18 +2,55 @@ use alloc::{vec, vec::Vec};
 use core::fmt::Debug;
 
 mod cloth_fabric;
-pub use cloth_fabric::ClothFabric;
+pub use cloth_fabric::{ClothFabric, ClothFabricDesc, ClothMesh, ClothPinPattern, ClothTexture};
+
+mod triangle_mesh;
+pub use triangle_mesh::TriangleMesh;
+
+mod mesh_fabric;
 
 mod point3d;
 
 mod gen_cloth_constraints;
 
+mod cloth_tearing;
+
+mod cloth_shading;
+
+mod colliders;
+
+mod tet_mesh;
+pub use tet_mesh::{SoftBodyDesc, SoftBodyShape, TetMesh};
+
+mod soft_body;
+use soft_body::SoftBody;
+
+mod strands;
+use strands::Strands;
+pub use strands::StrandsDesc;
+
+mod fluid;
+use fluid::Fluid;
+pub use fluid::FluidDesc;
+
 mod cloth;
 use cloth::Cloth;
 
 mod pbd_simulator;
 pub use pbd_simulator::PBDSimulator;
 
+/// 布料以外的模拟对象，存在时布料不参与模拟及绘制
+trait PBDBody {
+    fn update_by(&mut self, app: &app_surface::AppSurface, setting: &crate::PBDSetting);
+    /// 恢复到初始状态
+    fn reset(&mut self, app: &app_surface::AppSurface);
+    fn resize(&mut self, app: &app_surface::AppSurface) -> bool;
+    /// 使用轨道相机的视角
+    fn set_mvp(&mut self, app: &app_surface::AppSurface, mvp_uniform_data: &crate::MVPMatUniform);
+    fn compute(&mut self, encoder: &mut wgpu::CommandEncoder);
+    fn draw_by_rpass<'b, 'a: 'b>(&'a self, rpass: &mut wgpu::RenderPass<'b>);
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct FrameUniform {
@@ -22,7 +59,7 @@ pub struct FrameUniform {
 }
 
 #[repr(C)]
-#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct ClothUniform {
     // 粒子个数
     num_x: i32,
@@ -32,6 +69,114 @@ pub struct ClothUniform {
     compliance: f32,
     stiffness: f32,
     dt: f32,
+    // 约束长度超过静止长度的此倍数时断开，0 表示不可撕裂
+    tear_threshold: f32,
+}
+
+// 鼠标抓取
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GrabUniform {
+    // 模型空间中的鼠标射线
+    ray_origin: [f32; 4],
+    ray_dir: [f32; 4],
+    compliance: f32,
+    padding: [f32; 3],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GrabState {
+    // 最近交点距离的位表示
+    t_bits: u32,
+    particle: i32,
+    depth: f32,
+    padding: f32,
+}
+
+// 风与空气动力
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct WindUniform {
+    // xyz: 风速矢量, w: 阵风强度
+    wind: [f32; 4],
+    // xyz: 湍流噪声的采样偏移，随时间沿风向移动, w: 噪声的空间缩放
+    noise_offset: [f32; 4],
+    // 阻力系数
+    drag: f32,
+    // 升力系数
+    lift: f32,
+    // 静止状态下单个三角形的面积，用于归一化
+    rest_area: f32,
+    padding: f32,
+}
+
+// 碰撞体及自碰撞参数
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ColliderUniform {
+    // xyz: 球心, w: 半径
+    sphere: [f32; 4],
+    box_center: [f32; 4],
+    // 长方体各轴向上的半边长
+    box_half_size: [f32; 4],
+    ground_height: f32,
+    // 0 | 1
+    ground_enabled: i32,
+    box_enabled: i32,
+    sphere_enabled: i32,
+    // 接触面上切向位移的衰减比例
+    friction: f32,
+    // 粒子与碰撞体之间保持的距离
+    thickness: f32,
+    self_collision: i32,
+    // 自碰撞时粒子之间保持的距离
+    self_thickness: f32,
+}
+
+// 布料的光照及正反面颜色
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ClothShadingUniform {
+    // 视图空间中指向光源的方向
+    light_dir: [f32; 4],
+    front_color: [f32; 4],
+    back_color: [f32; 4],
+}
+
+// 软体的体积约束参数
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SoftBodyUniform {
+    // XPBD 的 α̃ = α / dt²，0 表示体积不可压缩
+    volume_compliance: f32,
+    padding: [f32; 3],
+}
+
+// 基于位置的流体
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct FluidUniform {
+    // 容器的范围，已去掉粒子半径
+    bounds_min: [f32; 4],
+    bounds_max: [f32; 4],
+    gravity: f32,
+    dt: f32,
+    // 核函数半径 h
+    kernel_radius: f32,
+    rest_density: f32,
+    // 密度约束的松弛系数 ε
+    relaxation: f32,
+    // 人工压强 s_corr = -k * (W(r) / W(Δq))^4
+    scorr_k: f32,
+    scorr_invert_w: f32,
+    // XSPH 粘性系数
+    viscosity: f32,
+    // 涡度约束强度
+    vorticity: f32,
+    damping: f32,
+    particle_num: u32,
+    padding: f32,
 }
 
 #[repr(C)]
@@ -71,6 +216,30 @@ impl StretchConstraintObj {
     }
 }
 
+// 四面体的体积约束
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct VolumeConstraintObj {
+    pub particles: [i32; 4],
+    pub rest_volume: f32,
+    // 当前子步内累积的拉格朗日乘子
+    pub lambda: f32,
+    pub padding: [f32; 2],
+}
+
+// 发丝相邻两段之间的扭转约束
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct TwistConstraintObj {
+    // 相邻两段发丝的三个粒子
+    pub particles: [i32; 3],
+    // 两段发丝各自的幽灵粒子
+    pub ghosts: [i32; 2],
+    // 静止状态下的扭转角
+    pub rest_twist: f32,
+    pub padding: [f32; 2],
+}
+
 // 弯曲约束
 #[repr(C)]
 #[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
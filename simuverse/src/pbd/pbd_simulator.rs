@@ -1,58 +1,323 @@
-use super::{Cloth, ClothFabric};
-use crate::{Simulator, util::AnyTexture};
+use super::{
+    Cloth, ClothFabric, ClothFabricDesc, ClothMesh, ClothTexture, Fluid, FluidDesc, PBDBody,
+    SoftBody, SoftBodyDesc, Strands, StrandsDesc, TetMesh, TriangleMesh,
+};
+use crate::{
+    PBDSetting, Simulator,
+    noise::TexGeneratorParams,
+    util::{AnyTexture, OrbitCamera},
+};
+use alloc::{boxed::Box, format, string::String, vec::Vec};
 use app_surface::AppSurface;
 #[cfg(not(target_arch = "wasm32"))]
 use std::{sync::mpsc, thread};
+use winit::{
+    dpi::PhysicalPosition,
+    event::{ElementState, MouseButton, MouseScrollDelta, TouchPhase},
+};
+
+// 软体最长边的长度
+const SOFT_BODY_SIZE: f32 = 0.8;
+// 烘焙噪声纹理的边长
+const NOISE_TEXTURE_SIZE: u32 = 512;
+
+// 布料以外的模拟对象的参数
+#[derive(PartialEq)]
+enum BodyDesc {
+    SoftBody(SoftBodyDesc),
+    Strands(StrandsDesc),
+    Fluid(FluidDesc),
+}
 
 pub struct PBDSimulator {
     pbd_obj: Option<Cloth>,
+    // 选择软体、发丝或流体类型时存在，此时布料不参与模拟及绘制
+    body: Option<Box<dyn PBDBody>>,
+    body_desc: Option<BodyDesc>,
+    viewport_size: glam::Vec2,
+    // 最近一次请求生成的布料参数
+    fabric_desc: ClothFabricDesc,
+    // 等待处理的点击位置
+    clicks: Vec<glam::Vec2>,
+    editing_pins: bool,
+    cursor_pos: glam::Vec2,
+    // 重新生成布料及切换模拟类型时保留视角
+    camera: OrbitCamera,
+    default_texture: AnyTexture,
+    // 布料当前使用的纹理及其来源
+    texture: AnyTexture,
+    texture_desc: ClothTexture,
+    // 纹理来自噪声材质时为烘焙所用的参数及插入 shader 的代码，变化时重新烘焙
+    noise_params: Option<(TexGeneratorParams, String)>,
+    #[cfg(not(target_arch = "wasm32"))]
+    is_generating: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    tx: mpsc::Sender<ClothFabric>,
     #[cfg(not(target_arch = "wasm32"))]
     rx: mpsc::Receiver<ClothFabric>,
 }
 
 impl PBDSimulator {
-    pub fn new(app: &AppSurface, _texture: Option<&AnyTexture>) -> Self {
+    pub fn new(
+        app: &AppSurface,
+        texture: &AnyTexture,
+        setting: &PBDSetting,
+        cad_model_file: &str,
+    ) -> Self {
         let viewport_size = glam::Vec2::new(app.config.width as f32, app.config.height as f32);
+        let fabric_desc = setting.fabric_desc(cad_model_file);
 
         #[cfg(target_arch = "wasm32")]
         {
-            let cloth_fabric = create_cloth_fabric(viewport_size);
+            let cloth_fabric = create_cloth_fabric(viewport_size, &fabric_desc);
             Self {
-                pbd_obj: Some(Cloth::new(app, cloth_fabric, _texture)),
+                pbd_obj: Some(Cloth::new(app, cloth_fabric, texture)),
+                body: None,
+                body_desc: None,
+                viewport_size,
+                fabric_desc,
+                clicks: Vec::new(),
+                editing_pins: false,
+                cursor_pos: glam::Vec2::ZERO,
+                camera: create_camera(viewport_size),
+                default_texture: texture.clone(),
+                texture: texture.clone(),
+                texture_desc: ClothTexture::Default,
+                noise_params: None,
             }
         }
 
         #[cfg(not(target_arch = "wasm32"))]
         {
             let (tx, rx) = mpsc::channel();
-            thread::spawn(move || {
-                let cloth_fabric = create_cloth_fabric(viewport_size);
-                let _ = tx.send(cloth_fabric);
-            });
+            let mut instance = Self {
+                pbd_obj: None,
+                body: None,
+                body_desc: None,
+                viewport_size,
+                fabric_desc,
+                clicks: Vec::new(),
+                editing_pins: false,
+                cursor_pos: glam::Vec2::ZERO,
+                camera: create_camera(viewport_size),
+                default_texture: texture.clone(),
+                texture: texture.clone(),
+                texture_desc: ClothTexture::Default,
+                noise_params: None,
+                is_generating: false,
+                tx,
+                rx,
+            };
+            instance.gen_fabric_in_background();
+            instance
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn gen_fabric_in_background(&mut self) {
+        let tx = self.tx.clone();
+        let viewport_size = self.viewport_size;
+        let desc = self.fabric_desc.clone();
+        thread::spawn(move || {
+            let cloth_fabric = create_cloth_fabric(viewport_size, &desc);
+            let _ = tx.send(cloth_fabric);
+        });
+        self.is_generating = true;
+    }
+
+    /// 布料参数变化后重新生成布料
+    fn regenerate_if_needed(
+        &mut self,
+        app: &AppSurface,
+        setting: &PBDSetting,
+        cad_model_file: &str,
+    ) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Ok(data) = self.rx.try_recv() {
+                self.pbd_obj = Some(Cloth::new(app, data, &self.texture));
+                self.is_generating = false;
+            }
+            // 同一时间只生成一块布料，生成期间的参数变化留到之后处理
+            if self.is_generating {
+                return;
+            }
+        }
 
-            Self { rx, pbd_obj: None }
+        let desc = setting.fabric_desc(cad_model_file);
+        if desc == self.fabric_desc {
+            return;
+        }
+        self.fabric_desc = desc;
+        self.regenerate(app);
+    }
+
+    fn regenerate(&mut self, _app: &AppSurface) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if !self.is_generating {
+            self.gen_fabric_in_background();
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let cloth_fabric = create_cloth_fabric(self.viewport_size, &self.fabric_desc);
+            self.pbd_obj = Some(Cloth::new(_app, cloth_fabric, &self.texture));
+        }
+    }
+
+    /// 纹理来源或噪声材质变化后更换布料的纹理
+    fn update_texture(&mut self, app: &AppSurface, control_panel: &crate::ControlPanel) {
+        let desc = control_panel.pbd_setting.texture_desc();
+        let noise_params = (desc == ClothTexture::Noise).then(|| {
+            let setting = &control_panel.noise_setting;
+            (
+                TexGeneratorParams::new(setting),
+                crate::noise::material_code(setting),
+            )
+        });
+        if desc == self.texture_desc && noise_params == self.noise_params {
+            return;
+        }
+        self.texture = match desc {
+            ClothTexture::Default => self.default_texture.clone(),
+            _ => match load_texture(app, &desc, noise_params.as_ref()) {
+                Ok(texture) => texture,
+                Err(e) => {
+                    log::warn!("failed to load cloth texture, fall back to default: {e}");
+                    self.default_texture.clone()
+                }
+            },
+        };
+        self.texture_desc = desc;
+        self.noise_params = noise_params;
+        if let Some(pbd) = self.pbd_obj.as_mut() {
+            pbd.set_texture(app, &self.texture);
+        }
+    }
+
+    /// 切换模拟类型或参数变化后重新生成软体、发丝及流体
+    fn update_body(&mut self, app: &AppSurface, setting: &PBDSetting) {
+        let desc = match setting.simu_ty {
+            Some(1) => Some(BodyDesc::SoftBody(setting.soft_body_desc())),
+            Some(2) => Some(BodyDesc::Strands(setting.strands_desc())),
+            Some(3) => Some(BodyDesc::Fluid(setting.fluid_desc())),
+            _ => None,
+        };
+        if desc == self.body_desc {
+            return;
+        }
+        self.body = desc.as_ref().map(|desc| -> Box<dyn PBDBody> {
+            match desc {
+                BodyDesc::SoftBody(desc) => Box::new(SoftBody::new(app, &create_tet_mesh(desc))),
+                BodyDesc::Strands(desc) => Box::new(Strands::new(app, desc)),
+                BodyDesc::Fluid(desc) => Box::new(Fluid::new(app, desc)),
+            }
+        });
+        self.body_desc = desc;
+    }
+
+    /// 撕裂后的布料需要重新生成才能恢复到静止状态
+    fn reset_cloth(&mut self, app: &AppSurface) {
+        match self.pbd_obj.as_mut() {
+            Some(pbd) if pbd.torn => self.regenerate(app),
+            Some(pbd) => pbd.reset(app),
+            None => {}
         }
     }
 }
 
 impl Simulator for PBDSimulator {
+    fn on_click(&mut self, _app: &AppSurface, pos: glam::Vec2) {
+        if self.editing_pins {
+            self.clicks.push(pos);
+        }
+    }
+
+    fn mouse_input(&mut self, app: &AppSurface, state: &ElementState, button: &MouseButton) {
+        // 右键旋转、中键平移视角，左键抓取布料
+        if self.camera.mouse_input(state, button) {
+            return;
+        }
+        if *button != MouseButton::Left || self.body.is_some() {
+            return;
+        }
+        let Some(pbd) = self.pbd_obj.as_mut() else {
+            return;
+        };
+        match state {
+            // 编辑固定点时不抓取布料
+            ElementState::Pressed if !self.editing_pins => pbd.grab_begin(app, self.cursor_pos),
+            ElementState::Pressed => {}
+            ElementState::Released => pbd.grab_end(),
+        }
+    }
+
+    fn mouse_wheel(&mut self, _app: &AppSurface, delta: &MouseScrollDelta, _phase: &TouchPhase) {
+        self.camera.mouse_wheel(delta);
+    }
+
+    fn cursor_moved(&mut self, app: &AppSurface, position: PhysicalPosition<f64>) {
+        self.cursor_pos = glam::Vec2::new(position.x as f32, position.y as f32);
+        self.camera.cursor_moved(self.cursor_pos);
+        if let Some(pbd) = self.pbd_obj.as_mut() {
+            pbd.grab_move(app, self.cursor_pos);
+        }
+    }
+
+    fn reset(&mut self, app: &AppSurface) {
+        match self.body.as_mut() {
+            Some(body) => body.reset(app),
+            None => self.reset_cloth(app),
+        }
+    }
+
     fn update_by(
         &mut self,
         app: &app_surface::AppSurface,
         control_panel: &mut crate::ControlPanel,
     ) {
-        if self.pbd_obj.is_none() {
-            #[cfg(not(target_arch = "wasm32"))]
-            {
-                if let Ok(data) = self.rx.try_recv() {
-                    self.pbd_obj = Some(Cloth::new(app, data, None));
-                } else {
-                    // Waiting for cloth data
+        self.update_body(app, &control_panel.pbd_setting);
+        // 新生成的模拟对象使用的是默认视角，所以每帧都更新
+        let mvp_uniform_data = self.camera.mvp_uniform(glam::Mat4::IDENTITY);
+        if let Some(body) = self.body.as_mut() {
+            body.set_mvp(app, &mvp_uniform_data);
+            body.update_by(app, &control_panel.pbd_setting);
+            return;
+        }
+
+        let setting = &mut control_panel.pbd_setting;
+        if self.editing_pins
+            && let Some(pbd) = self.pbd_obj.as_ref()
+        {
+            for pos in self.clicks.drain(..) {
+                if let Some(uv) = pbd.rest_uv_at(self.viewport_size, pos) {
+                    setting.toggle_custom_pin(uv);
                 }
             }
         }
+        self.regenerate_if_needed(app, setting, &control_panel.cad_setting.model_file);
+        self.update_texture(app, control_panel);
+        if let Some(pbd) = self.pbd_obj.as_mut() {
+            pbd.tear_if_needed(app);
+        }
 
+        let setting = &mut control_panel.pbd_setting;
+        if setting.editing_pins && self.pbd_obj.as_ref().is_some_and(|pbd| !pbd.paused) {
+            self.reset_cloth(app);
+        }
         if let Some(pbd) = self.pbd_obj.as_mut() {
+            if setting.editing_pins {
+                setting.pin_markers = pbd.rest_pinned_positions(self.viewport_size);
+            }
+            pbd.paused = setting.editing_pins;
+        }
+        self.editing_pins = setting.editing_pins;
+        if !self.editing_pins {
+            self.clicks.clear();
+        }
+
+        if let Some(pbd) = self.pbd_obj.as_mut() {
+            pbd.set_mvp(app, &mvp_uniform_data);
             pbd.update_by(app, control_panel);
         }
     }
@@ -65,14 +330,23 @@ impl Simulator for PBDSimulator {
     }
 
     fn resize(&mut self, app: &app_surface::AppSurface) -> bool {
+        self.viewport_size = glam::Vec2::new(app.config.width as f32, app.config.height as f32);
+        let (proj, mv) = camera_matrices(self.viewport_size);
+        self.camera.resize(proj, mv, self.viewport_size);
+        let mut resized = false;
+        if let Some(body) = self.body.as_mut() {
+            resized = body.resize(app);
+        }
         if let Some(pbd) = self.pbd_obj.as_mut() {
-            return pbd.resize(app);
+            resized |= pbd.resize(app);
         }
-        false
+        resized
     }
 
     fn compute(&mut self, encoder: &mut wgpu::CommandEncoder) {
-        if let Some(pbd) = self.pbd_obj.as_mut() {
+        if let Some(body) = self.body.as_mut() {
+            body.compute(encoder);
+        } else if let Some(pbd) = self.pbd_obj.as_mut() {
             pbd.compute(encoder);
         }
     }
@@ -83,28 +357,114 @@ impl Simulator for PBDSimulator {
         rpass: &mut wgpu::RenderPass<'b>,
         setting: &mut crate::SettingObj,
     ) {
-        if let Some(pbd) = self.pbd_obj.as_mut() {
+        if let Some(body) = self.body.as_ref() {
+            body.draw_by_rpass(rpass);
+        } else if let Some(pbd) = self.pbd_obj.as_mut() {
             pbd.draw_by_rpass(app, rpass, setting);
         }
     }
 }
 
-fn create_cloth_fabric(viewport_size: glam::Vec2) -> ClothFabric {
-    let horizontal_pixel = viewport_size.x;
-    let vertical_pixel = horizontal_pixel;
+fn camera_matrices(viewport_size: glam::Vec2) -> (glam::Mat4, glam::Mat4) {
+    let mvp_uniform_data = Cloth::get_mvp_uniform_data(viewport_size);
+    (
+        glam::Mat4::from_cols_array_2d(&mvp_uniform_data.proj),
+        glam::Mat4::from_cols_array_2d(&mvp_uniform_data.mv),
+    )
+}
+
+fn create_camera(viewport_size: glam::Vec2) -> OrbitCamera {
+    let (proj, mv) = camera_matrices(viewport_size);
+    OrbitCamera::new(proj, mv, viewport_size, MouseButton::Right)
+}
 
+fn create_cloth_fabric(viewport_size: glam::Vec2, desc: &ClothFabricDesc) -> ClothFabric {
     let fovy: f32 = 75.0 / 180.0 * core::f32::consts::PI;
     let factor = crate::util::matrix_helper::fullscreen_factor(viewport_size, fovy);
     let a_pixel_on_ndc = factor.1 / viewport_size.x;
 
-    let particle_x_num = 50;
-    let particle_y_num = 50;
+    let horizontal_pixel = viewport_size.x * desc.size;
+    if desc.mesh != ClothMesh::Grid {
+        match load_mesh(&desc.mesh) {
+            Ok(mesh) => {
+                return ClothFabric::from_triangle_mesh(
+                    desc,
+                    &mesh,
+                    horizontal_pixel * a_pixel_on_ndc,
+                );
+            }
+            Err(e) => log::warn!("failed to load cloth mesh, fall back to grid: {e}"),
+        }
+    }
+    // 保持格子为正方形
+    let vertical_pixel =
+        horizontal_pixel * (desc.vertical_num - 1) as f32 / (desc.horizontal_num - 1) as f32;
+
+    ClothFabric::gen_fabric(desc, horizontal_pixel, vertical_pixel, a_pixel_on_ndc)
+}
 
-    ClothFabric::gen_fabric(
-        particle_x_num,
-        particle_y_num,
-        horizontal_pixel,
-        vertical_pixel,
-        a_pixel_on_ndc,
-    )
+fn load_mesh(mesh: &ClothMesh) -> Result<TriangleMesh, String> {
+    match mesh {
+        #[cfg(not(target_arch = "wasm32"))]
+        ClothMesh::CADModel(path) if path.is_empty() => {
+            TriangleMesh::from_obj(&String::from_utf8_lossy(crate::truck::TEAPOT_BYTES))
+        }
+        // 布料只支持 OBJ，CAD 中加载的 STL 无法使用
+        #[cfg(not(target_arch = "wasm32"))]
+        ClothMesh::CADModel(path) if !path.to_ascii_lowercase().ends_with(".obj") => {
+            Err(format!("{path}: only OBJ models can be used as cloth"))
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        ClothMesh::CADModel(path) | ClothMesh::File(path) => {
+            let text = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+            TriangleMesh::from_obj(&text)
+        }
+        _ => Err(format!("{mesh:?} is not supported on this platform")),
+    }
+}
+
+fn load_texture(
+    app: &AppSurface,
+    desc: &ClothTexture,
+    noise_params: Option<&(TexGeneratorParams, String)>,
+) -> Result<AnyTexture, String> {
+    match (desc, noise_params) {
+        (ClothTexture::Noise, Some((params, code))) => {
+            Ok(
+                crate::noise::D2NoiseTexture::create(app, params, code, NOISE_TEXTURE_SIZE, false)
+                    .tex,
+            )
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        (ClothTexture::File(path), _) => crate::util::load_texture::from_file(
+            std::path::Path::new(path),
+            app,
+            wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+        ),
+        _ => Err(format!("{desc:?} is not supported on this platform")),
+    }
+}
+
+fn create_tet_mesh(desc: &SoftBodyDesc) -> TetMesh {
+    #[cfg(not(target_arch = "wasm32"))]
+    if let super::SoftBodyShape::TetGen(path) = &desc.shape {
+        match load_tetgen(path) {
+            Ok(mut mesh) => {
+                mesh.fit_to(SOFT_BODY_SIZE);
+                return mesh;
+            }
+            Err(e) => log::warn!("failed to load tet mesh, fall back to box: {e}"),
+        }
+    }
+    TetMesh::voxelize(&desc.shape, desc.resolution, SOFT_BODY_SIZE)
+}
+
+/// 读取 .node 文件及同名的 .ele 文件
+#[cfg(not(target_arch = "wasm32"))]
+fn load_tetgen(path: &str) -> Result<TetMesh, String> {
+    let ele_path = std::path::Path::new(path).with_extension("ele");
+    let node = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    let ele =
+        std::fs::read_to_string(&ele_path).map_err(|e| format!("{}: {e}", ele_path.display()))?;
+    TetMesh::from_tetgen(&node, &ele)
 }
@@ -0,0 +1,435 @@
+//! 四面体网格的软体
+//!
+//! 沿用布料的 XPBD 子步结构：每个子步先预测位置并清零体积约束累积的拉格朗日乘子，
+//! 然后按网格着色分组依次求解边的距离约束、四面体的体积约束，最后处理与碰撞体的碰撞。
+
+use crate::node::{BindGroupData, ComputeNode, ViewNode, ViewNodeBuilder};
+use crate::util::{BufferObj, vertex::PosParticleIndex};
+
+use super::cloth_fabric::ParticleBufferObj;
+use super::colliders::ColliderDisplay;
+use super::{
+    Cloth, ClothUniform, ColliderUniform, MeshColoringObj, PBDBody, SoftBodyUniform, TetMesh,
+    VolumeConstraintObj,
+};
+
+use alloc::{vec, vec::Vec};
+use app_surface::AppSurface;
+
+// 软体生成时的中心高度，落到地面上
+const SPAWN_HEIGHT: f32 = 0.2;
+
+pub struct SoftBody {
+    mvp_uniform_data: crate::MVPMatUniform,
+    mvp_buf: BufferObj,
+    cloth_uniform_data: ClothUniform,
+    cloth_uniform_buf: BufferObj,
+    soft_uniform_data: SoftBodyUniform,
+    soft_uniform_buf: BufferObj,
+    // 体积约束柔度 α 的单位：静止状态下 Σ w|∇C|² 的平均值 × dt²
+    volume_compliance_unit: f32,
+    particle_buf: BufferObj,
+    // 粒子的初始状态，用于重置
+    rest_particles: Vec<ParticleBufferObj>,
+
+    // 碰撞
+    particle_spacing: f32,
+    collider_uniform_data: ColliderUniform,
+    collider_uniform_buf: BufferObj,
+    collision_solver: ComputeNode,
+    collider_display: ColliderDisplay,
+
+    predict_and_reset: ComputeNode,
+    stretch_solver: ComputeNode,
+    stretch_colorings: Vec<MeshColoringObj>,
+    volume_reset: ComputeNode,
+    volume_solver: ComputeNode,
+    volume_colorings: Vec<MeshColoringObj>,
+    display_node: ViewNode,
+    dynamic_offset: u32,
+    pbd_iter_count: usize,
+    delta_time: f32,
+}
+
+impl SoftBody {
+    pub fn new(app_view: &AppSurface, mesh: &TetMesh) -> Self {
+        let viewport_size =
+            glam::Vec2::new(app_view.config.width as f32, app_view.config.height as f32);
+        let mvp_uniform_data = Cloth::get_mvp_uniform_data(viewport_size);
+        let mvp_buf = BufferObj::create_uniform_buffer(&app_view.device, &mvp_uniform_data, None);
+
+        let pbd_iter_count = 15;
+        let delta_time = 0.016 / pbd_iter_count as f32;
+        let cloth_uniform_data = ClothUniform {
+            num_x: 0,
+            num_y: 0,
+            gravity: -70.0 * 0.7,
+            damping: 0.01,
+            compliance: 0.0,
+            stiffness: 0.0,
+            dt: delta_time,
+            tear_threshold: 0.0,
+        };
+        let cloth_uniform_buf = BufferObj::create_uniform_buffer(
+            &app_view.device,
+            &cloth_uniform_data,
+            Some("soft body uniform"),
+        );
+        let soft_uniform_data = SoftBodyUniform {
+            volume_compliance: 0.0,
+            padding: [0.0; 3],
+        };
+        let soft_uniform_buf = BufferObj::create_uniform_buffer(
+            &app_view.device,
+            &soft_uniform_data,
+            Some("soft body volume uniform"),
+        );
+
+        // predict 着色器里重力与质量的倒数相乘，所有粒子使用相同的质量来保证重力加速度一致
+        let mut rest_particles: Vec<ParticleBufferObj> = mesh
+            .positions
+            .iter()
+            .map(|p| {
+                let pos = [p[0], p[1] + SPAWN_HEIGHT, p[2], 0.0];
+                ParticleBufferObj {
+                    pos,
+                    old_pos: pos,
+                    accelerate: [0.0; 4],
+                    uv_mass: [0.0, 0.0, 0.1, 0.0],
+                    connect: [0; 4],
+                }
+            })
+            .collect();
+        let surface = mesh.surface_triangles();
+        super::mesh_fabric::cal_connected_particles(&mut rest_particles, &surface);
+        let ((stretch_colorings, stretch_constraints), (volume_colorings, volume_constraints)) =
+            super::gen_cloth_constraints::generate_tet_constraints(&rest_particles, mesh);
+
+        let mean_volume = volume_constraints
+            .iter()
+            .map(|c| c.rest_volume)
+            .sum::<f32>()
+            / volume_constraints.len().max(1) as f32;
+        // 与四面体体积相同的体素的边长
+        let particle_spacing = (mean_volume * 6.0).cbrt();
+        let volume_compliance_unit =
+            mean_volume_gradient(&rest_particles, &volume_constraints) * delta_time * delta_time;
+
+        let dynamic_offset = app_view.device.limits().min_uniform_buffer_offset_alignment;
+        let stretch_coloring_buf =
+            create_coloring_buf(app_view, &stretch_colorings, dynamic_offset, "stretch");
+        let volume_coloring_buf =
+            create_coloring_buf(app_view, &volume_colorings, dynamic_offset, "volume");
+
+        let mut particle_buf = BufferObj::create_storage_buffer(
+            &app_view.device,
+            &rest_particles,
+            Some("soft body particle buf"),
+        );
+        let constraint_buf = BufferObj::create_storage_buffer(
+            &app_view.device,
+            &stretch_constraints,
+            Some("soft body constraint_buf"),
+        );
+        let volume_constraint_buf = BufferObj::create_storage_buffer(
+            &app_view.device,
+            &volume_constraints,
+            Some("volume_constraint_buf"),
+        );
+
+        let particle_num = rest_particles.len() as u32;
+        let predict_and_reset_shader = crate::util::shader::create_shader_module(
+            &app_view.device,
+            "pbd/xxpbd/cloth_predict",
+            None,
+        );
+        let mut bind_group_data = BindGroupData {
+            workgroup_count: (particle_num.div_ceil(32), 1, 1),
+            uniforms: vec![&cloth_uniform_buf],
+            storage_buffers: vec![&particle_buf, &constraint_buf],
+            ..Default::default()
+        };
+        let predict_and_reset = ComputeNode::new(
+            &app_view.device,
+            &bind_group_data,
+            &predict_and_reset_shader,
+        );
+
+        let stretch_solver_shader = crate::util::shader::create_shader_module(
+            &app_view.device,
+            "pbd/xxpbd/distance_solver",
+            None,
+        );
+        bind_group_data.dynamic_uniforms = vec![&stretch_coloring_buf];
+        bind_group_data.workgroup_count = (0, 0, 0);
+        let stretch_solver = ComputeNode::new_with_dynamic_uniforms(
+            &app_view.device,
+            &bind_group_data,
+            &stretch_solver_shader,
+        );
+
+        let volume_reset_shader = crate::util::shader::create_shader_module(
+            &app_view.device,
+            "pbd/xxpbd/soft_body_volume_reset",
+            None,
+        );
+        let bind_group_data = BindGroupData {
+            workgroup_count: ((volume_constraints.len() as u32).div_ceil(64), 1, 1),
+            storage_buffers: vec![&volume_constraint_buf],
+            ..Default::default()
+        };
+        let volume_reset =
+            ComputeNode::new(&app_view.device, &bind_group_data, &volume_reset_shader);
+
+        let volume_solver_shader = crate::util::shader::create_shader_module(
+            &app_view.device,
+            "pbd/xxpbd/soft_body_volume_solver",
+            None,
+        );
+        let bind_group_data = BindGroupData {
+            uniforms: vec![&soft_uniform_buf],
+            dynamic_uniforms: vec![&volume_coloring_buf],
+            storage_buffers: vec![&particle_buf, &volume_constraint_buf],
+            ..Default::default()
+        };
+        let volume_solver = ComputeNode::new_with_dynamic_uniforms(
+            &app_view.device,
+            &bind_group_data,
+            &volume_solver_shader,
+        );
+
+        // 碰撞
+        let collider_uniform_data: ColliderUniform = bytemuck::Zeroable::zeroed();
+        let collider_uniform_buf = BufferObj::create_uniform_buffer(
+            &app_view.device,
+            &collider_uniform_data,
+            Some("soft body collider uniform"),
+        );
+        let collision_solver_shader = crate::util::shader::create_shader_module(
+            &app_view.device,
+            "pbd/xxpbd/collision_solver",
+            None,
+        );
+        let bind_group_data = BindGroupData {
+            workgroup_count: (particle_num.div_ceil(64), 1, 1),
+            uniforms: vec![&cloth_uniform_buf, &collider_uniform_buf],
+            storage_buffers: vec![&particle_buf],
+            ..Default::default()
+        };
+        let collision_solver =
+            ComputeNode::new(&app_view.device, &bind_group_data, &collision_solver_shader);
+        let collider_display = ColliderDisplay::new(app_view, &mvp_buf);
+
+        let display_shader = crate::util::shader::create_shader_module(
+            &app_view.device,
+            "pbd/soft_body_display",
+            None,
+        );
+        particle_buf.read_only = true;
+        let bind_group_data = BindGroupData {
+            uniforms: vec![&mvp_buf],
+            storage_buffers: vec![&particle_buf],
+            visibilitys: vec![wgpu::ShaderStages::VERTEX, wgpu::ShaderStages::VERTEX],
+            ..Default::default()
+        };
+        let vertices = (0..particle_num)
+            .map(|i| PosParticleIndex::new([i, 0, 0]))
+            .collect();
+        let display_node =
+            ViewNodeBuilder::<PosParticleIndex>::new(bind_group_data, &display_shader)
+                .with_use_depth_stencil(true)
+                .with_cull_mode(None)
+                .with_vertices_and_indices((vertices, surface))
+                .with_color_format(app_view.config.format)
+                .build(&app_view.device);
+
+        Self {
+            mvp_uniform_data,
+            mvp_buf,
+            cloth_uniform_data,
+            cloth_uniform_buf,
+            soft_uniform_data,
+            soft_uniform_buf,
+            volume_compliance_unit,
+            particle_buf,
+            rest_particles,
+            particle_spacing,
+            collider_uniform_data,
+            collider_uniform_buf,
+            collision_solver,
+            collider_display,
+            predict_and_reset,
+            stretch_solver,
+            stretch_colorings,
+            volume_reset,
+            volume_solver,
+            volume_colorings,
+            display_node,
+            dynamic_offset,
+            pbd_iter_count,
+            delta_time,
+        }
+    }
+}
+
+impl PBDBody for SoftBody {
+    fn update_by(&mut self, app: &AppSurface, setting: &crate::PBDSetting) {
+        let mut cloth_uniform_data = self.cloth_uniform_data;
+        cloth_uniform_data.damping = setting.damping * 0.015;
+        cloth_uniform_data.gravity = setting.gravity * -35.0 - 35.0;
+        cloth_uniform_data.compliance =
+            setting.softness * 0.000002 / (self.delta_time * self.delta_time);
+        if cloth_uniform_data != self.cloth_uniform_data {
+            self.cloth_uniform_data = cloth_uniform_data;
+            app.queue.write_buffer(
+                &self.cloth_uniform_buf.buffer,
+                0,
+                bytemuck::bytes_of(&self.cloth_uniform_data),
+            );
+        }
+
+        // XPBD 的柔度 α，以网格自身的梯度项为单位，使软硬程度与网格的分辨率无关。
+        // 设置为 1 时 α̃ = α / dt² 是平均梯度项的 10 倍
+        let alpha = setting.volume_compliance * 10.0 * self.volume_compliance_unit;
+        let volume_compliance = alpha / (self.delta_time * self.delta_time);
+        if volume_compliance != self.soft_uniform_data.volume_compliance {
+            self.soft_uniform_data.volume_compliance = volume_compliance;
+            app.queue.write_buffer(
+                &self.soft_uniform_buf.buffer,
+                0,
+                bytemuck::bytes_of(&self.soft_uniform_data),
+            );
+        }
+
+        let mut collider_uniform_data = ColliderUniform::new(setting, self.particle_spacing);
+        collider_uniform_data.self_collision = 0;
+        if collider_uniform_data != self.collider_uniform_data {
+            self.collider_uniform_data = collider_uniform_data;
+            app.queue.write_buffer(
+                &self.collider_uniform_buf.buffer,
+                0,
+                bytemuck::bytes_of(&self.collider_uniform_data),
+            );
+            self.collider_display.update(app, setting);
+        }
+    }
+
+    fn reset(&mut self, app: &AppSurface) {
+        app.queue.write_buffer(
+            &self.particle_buf.buffer,
+            0,
+            bytemuck::cast_slice(&self.rest_particles),
+        );
+    }
+
+    fn resize(&mut self, app: &AppSurface) -> bool {
+        self.mvp_uniform_data = Cloth::get_mvp_uniform_data(glam::Vec2::new(
+            app.config.width as f32,
+            app.config.height as f32,
+        ));
+        app.queue.write_buffer(
+            &self.mvp_buf.buffer,
+            0,
+            bytemuck::bytes_of(&self.mvp_uniform_data),
+        );
+        true
+    }
+
+    fn set_mvp(&mut self, app: &AppSurface, mvp_uniform_data: &crate::MVPMatUniform) {
+        self.mvp_uniform_data = *mvp_uniform_data;
+        app.queue.write_buffer(
+            &self.mvp_buf.buffer,
+            0,
+            bytemuck::bytes_of(&self.mvp_uniform_data),
+        );
+    }
+
+    fn compute(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("soft body solver pass"),
+            ..Default::default()
+        });
+        let has_collision = self.collider_uniform_data.has_collider();
+        for _ in 0..self.pbd_iter_count {
+            self.predict_and_reset.compute_by_pass(&mut cpass);
+            self.volume_reset.compute_by_pass(&mut cpass);
+            for (node, colorings) in [
+                (&self.stretch_solver, &self.stretch_colorings),
+                (&self.volume_solver, &self.volume_colorings),
+            ] {
+                cpass.set_pipeline(&node.pipeline);
+                cpass.set_bind_group(0, &node.bg_setting.bind_group, &[]);
+                for (index, mc) in colorings.iter().enumerate() {
+                    if let Some(bg) = &node.dy_uniform_bg {
+                        cpass.set_bind_group(
+                            1,
+                            &bg.bind_group,
+                            &[index as u32 * self.dynamic_offset],
+                        );
+                    }
+                    cpass.dispatch_workgroups(mc.thread_group.0, mc.thread_group.1, 1);
+                }
+            }
+            if has_collision {
+                self.collision_solver.compute_by_pass(&mut cpass);
+            }
+        }
+    }
+
+    fn draw_by_rpass<'b, 'a: 'b>(&'a self, rpass: &mut wgpu::RenderPass<'b>) {
+        self.collider_display.draw_by_pass(rpass);
+        self.display_node.draw_by_pass(rpass);
+    }
+}
+
+/// 静止状态下体积约束的 Σ w|∇C|² 的平均值
+fn mean_volume_gradient(
+    particles: &[ParticleBufferObj],
+    constraints: &[VolumeConstraintObj],
+) -> f32 {
+    let sum: f32 = constraints
+        .iter()
+        .map(|c| {
+            let [p0, p1, p2, p3] = c
+                .particles
+                .map(|i| glam::Vec4::from(particles[i as usize].pos).truncate());
+            // 与 soft_body_volume_solver 着色器中的梯度一致
+            let mut grads = [
+                glam::Vec3::ZERO,
+                (p2 - p0).cross(p3 - p0) / 6.0,
+                (p3 - p0).cross(p1 - p0) / 6.0,
+                (p1 - p0).cross(p2 - p0) / 6.0,
+            ];
+            grads[0] = -(grads[1] + grads[2] + grads[3]);
+            grads
+                .iter()
+                .zip(c.particles)
+                .map(|(g, i)| particles[i as usize].uv_mass[2] * g.length_squared())
+                .sum::<f32>()
+        })
+        .sum();
+    sum / constraints.len().max(1) as f32
+}
+
+pub(super) fn create_coloring_buf(
+    app: &AppSurface,
+    colorings: &[MeshColoringObj],
+    dynamic_offset: u32,
+    label: &'static str,
+) -> BufferObj {
+    let buf = BufferObj::create_empty_uniform_buffer(
+        &app.device,
+        colorings.len().max(1) as u64 * dynamic_offset as u64,
+        0,
+        true,
+        Some(label),
+    );
+    for (i, mc) in colorings.iter().enumerate() {
+        app.queue.write_buffer(
+            &buf.buffer,
+            i as u64 * dynamic_offset as u64,
+            bytemuck::cast_slice(&mc.get_push_constants_data()),
+        );
+    }
+    buf
+}
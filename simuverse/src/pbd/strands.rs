@@ -0,0 +1,470 @@
+//! 绳索及头发
+//!
+//! 每根发丝是一串粒子，发根固定。沿用布料的拉伸及弯曲约束：相邻粒子间为距离约束，
+//! 连续三个粒子为三角形弯曲约束。卷曲的效果来自螺旋形的静止形状，弯曲约束会保持这个形状。
+//!
+//! 扭转使用幽灵粒子（Position Based Elastic Rods）：每段发丝附带一个偏离中心线的幽灵粒子，
+//! 与该段的两个端点组成材料标架。扭转约束保持相邻两段的标架之间绕中心线的扭转角，
+//! 静止状态的标架沿中心线平行移动生成，因此没有初始扭转。
+
+use crate::node::{BindGroupData, ComputeNode, ViewNode, ViewNodeBuilder};
+use crate::util::{BufferObj, vertex::PosParticleIndex};
+
+use super::cloth_fabric::ParticleBufferObj;
+use super::colliders::ColliderDisplay;
+use super::gen_cloth_constraints::parallel_transport;
+use super::soft_body::create_coloring_buf;
+use super::{Cloth, ClothUniform, ColliderUniform, MeshColoringObj, PBDBody};
+
+use alloc::{vec, vec::Vec};
+use app_surface::AppSurface;
+
+// 相邻粒子的间距
+const SEGMENT_LENGTH: f32 = 0.04;
+// 发根所在圆环的中心及半径
+const ROOT_CENTER: glam::Vec3 = glam::Vec3::new(0.0, 0.6, 0.0);
+const ROOT_RADIUS: f32 = 0.15;
+// 卷曲为 1 时螺旋的半径及螺距
+const CURL_RADIUS: f32 = 0.05;
+const CURL_PITCH: f32 = 0.16;
+// 幽灵粒子到所在段中心线的距离
+const GHOST_OFFSET: f32 = SEGMENT_LENGTH * 0.5;
+
+/// 生成发丝所需的参数
+#[derive(Clone, PartialEq, Debug)]
+pub struct StrandsDesc {
+    pub count: usize,
+    pub length: f32,
+    // 0 为直发
+    pub curl: f32,
+}
+
+pub struct Strands {
+    mvp_uniform_data: crate::MVPMatUniform,
+    mvp_buf: BufferObj,
+    cloth_uniform_data: ClothUniform,
+    cloth_uniform_buf: BufferObj,
+    particle_buf: BufferObj,
+    // 粒子的初始状态，用于重置
+    rest_particles: Vec<ParticleBufferObj>,
+
+    // 碰撞
+    collider_uniform_data: ColliderUniform,
+    collider_uniform_buf: BufferObj,
+    collision_solver: ComputeNode,
+    collider_display: ColliderDisplay,
+
+    predict_and_reset: ComputeNode,
+    stretch_solver: ComputeNode,
+    stretch_colorings: Vec<MeshColoringObj>,
+    bend_solver: ComputeNode,
+    bend_colorings: Vec<MeshColoringObj>,
+    twist_solver: ComputeNode,
+    twist_colorings: Vec<MeshColoringObj>,
+    display_node: ViewNode,
+    dynamic_offset: u32,
+    pbd_iter_count: usize,
+}
+
+impl Strands {
+    pub fn new(app_view: &AppSurface, desc: &StrandsDesc) -> Self {
+        let viewport_size =
+            glam::Vec2::new(app_view.config.width as f32, app_view.config.height as f32);
+        let mvp_uniform_data = Cloth::get_mvp_uniform_data(viewport_size);
+        let mvp_buf = BufferObj::create_uniform_buffer(&app_view.device, &mvp_uniform_data, None);
+
+        let pbd_iter_count = 15;
+        let delta_time = 0.016 / pbd_iter_count as f32;
+        let cloth_uniform_data = ClothUniform {
+            num_x: 0,
+            num_y: 0,
+            gravity: -70.0 * 0.7,
+            damping: 0.01,
+            // 发丝几乎不可拉伸
+            compliance: 0.0000000016 / (delta_time * delta_time),
+            stiffness: 0.0,
+            dt: delta_time,
+            tear_threshold: 0.0,
+        };
+        let cloth_uniform_buf = BufferObj::create_uniform_buffer(
+            &app_view.device,
+            &cloth_uniform_data,
+            Some("strands uniform"),
+        );
+
+        let (rest_particles, strand_count, strand_len) = gen_strand_particles(desc);
+        let (
+            (stretch_colorings, stretch_constraints),
+            (bend_colorings, bend_constraints),
+            (twist_colorings, twist_constraints),
+        ) = super::gen_cloth_constraints::generate_strand_constraints(
+            &rest_particles,
+            strand_count,
+            strand_len,
+        );
+
+        let dynamic_offset = app_view.device.limits().min_uniform_buffer_offset_alignment;
+        let stretch_coloring_buf = create_coloring_buf(
+            app_view,
+            &stretch_colorings,
+            dynamic_offset,
+            "strand stretch",
+        );
+        let twist_coloring_buf =
+            create_coloring_buf(app_view, &twist_colorings, dynamic_offset, "strand twist");
+        // 弯曲约束的动态 uniform 按迭代次数排列
+        let bend_coloring_buf = BufferObj::create_empty_uniform_buffer(
+            &app_view.device,
+            (bend_colorings.len() * pbd_iter_count).max(1) as u64 * dynamic_offset as u64,
+            0,
+            true,
+            Some("strand bend"),
+        );
+        let mut offset = 0;
+        for i in 0..pbd_iter_count {
+            for mc in bend_colorings.iter() {
+                app_view.queue.write_buffer(
+                    &bend_coloring_buf.buffer,
+                    offset,
+                    bytemuck::bytes_of(&mc.get_bending_dynamic_uniform(i as i32)),
+                );
+                offset += dynamic_offset as u64;
+            }
+        }
+
+        let mut particle_buf = BufferObj::create_storage_buffer(
+            &app_view.device,
+            &rest_particles,
+            Some("strand particle buf"),
+        );
+        let constraint_buf = BufferObj::create_storage_buffer(
+            &app_view.device,
+            &stretch_constraints,
+            Some("strand constraint_buf"),
+        );
+        let bend_constraint_buf = BufferObj::create_storage_buffer(
+            &app_view.device,
+            &bend_constraints,
+            Some("strand bend_constraint_buf"),
+        );
+        let twist_constraint_buf = BufferObj::create_storage_buffer(
+            &app_view.device,
+            &twist_constraints,
+            Some("strand twist_constraint_buf"),
+        );
+
+        let particle_num = rest_particles.len() as u32;
+        let predict_and_reset_shader = crate::util::shader::create_shader_module(
+            &app_view.device,
+            "pbd/xxpbd/cloth_predict",
+            None,
+        );
+        let mut bind_group_data = BindGroupData {
+            workgroup_count: (particle_num.div_ceil(32), 1, 1),
+            uniforms: vec![&cloth_uniform_buf],
+            storage_buffers: vec![&particle_buf, &constraint_buf],
+            ..Default::default()
+        };
+        let predict_and_reset = ComputeNode::new(
+            &app_view.device,
+            &bind_group_data,
+            &predict_and_reset_shader,
+        );
+
+        let stretch_solver_shader = crate::util::shader::create_shader_module(
+            &app_view.device,
+            "pbd/xxpbd/distance_solver",
+            None,
+        );
+        bind_group_data.dynamic_uniforms = vec![&stretch_coloring_buf];
+        bind_group_data.workgroup_count = (0, 0, 0);
+        let stretch_solver = ComputeNode::new_with_dynamic_uniforms(
+            &app_view.device,
+            &bind_group_data,
+            &stretch_solver_shader,
+        );
+
+        let bend_solver_shader = crate::util::shader::create_shader_module(
+            &app_view.device,
+            "pbd/xxpbd/cloth_bending_solver",
+            None,
+        );
+        let bind_group_data = BindGroupData {
+            uniforms: vec![&cloth_uniform_buf],
+            dynamic_uniforms: vec![&bend_coloring_buf],
+            storage_buffers: vec![&particle_buf, &bend_constraint_buf],
+            ..Default::default()
+        };
+        let bend_solver = ComputeNode::new_with_dynamic_uniforms(
+            &app_view.device,
+            &bind_group_data,
+            &bend_solver_shader,
+        );
+
+        let twist_solver_shader = crate::util::shader::create_shader_module(
+            &app_view.device,
+            "pbd/xxpbd/strand_twist_solver",
+            None,
+        );
+        let bind_group_data = BindGroupData {
+            dynamic_uniforms: vec![&twist_coloring_buf],
+            storage_buffers: vec![&particle_buf, &twist_constraint_buf],
+            ..Default::default()
+        };
+        let twist_solver = ComputeNode::new_with_dynamic_uniforms(
+            &app_view.device,
+            &bind_group_data,
+            &twist_solver_shader,
+        );
+
+        // 碰撞
+        let collider_uniform_data: ColliderUniform = bytemuck::Zeroable::zeroed();
+        let collider_uniform_buf = BufferObj::create_uniform_buffer(
+            &app_view.device,
+            &collider_uniform_data,
+            Some("strands collider uniform"),
+        );
+        let collision_solver_shader = crate::util::shader::create_shader_module(
+            &app_view.device,
+            "pbd/xxpbd/collision_solver",
+            None,
+        );
+        let bind_group_data = BindGroupData {
+            workgroup_count: (particle_num.div_ceil(64), 1, 1),
+            uniforms: vec![&cloth_uniform_buf, &collider_uniform_buf],
+            storage_buffers: vec![&particle_buf],
+            ..Default::default()
+        };
+        let collision_solver =
+            ComputeNode::new(&app_view.device, &bind_group_data, &collision_solver_shader);
+        let collider_display = ColliderDisplay::new(app_view, &mvp_buf);
+
+        let display_shader =
+            crate::util::shader::create_shader_module(&app_view.device, "pbd/strand_display", None);
+        particle_buf.read_only = true;
+        let bind_group_data = BindGroupData {
+            uniforms: vec![&mvp_buf],
+            storage_buffers: vec![&particle_buf],
+            visibilitys: vec![wgpu::ShaderStages::VERTEX, wgpu::ShaderStages::VERTEX],
+            ..Default::default()
+        };
+        // 只绘制发丝的粒子，幽灵粒子排在后面
+        let strand_particle_num = (strand_count * strand_len) as u32;
+        let vertices = (0..strand_particle_num)
+            .map(|i| PosParticleIndex::new([i, 0, 0]))
+            .collect();
+        // 每根发丝一个 line strip，用 u32::MAX 断开
+        let indices = (0..strand_particle_num)
+            .collect::<Vec<u32>>()
+            .chunks(strand_len)
+            .flat_map(|strand| strand.iter().copied().chain([u32::MAX]))
+            .collect();
+        let display_node =
+            ViewNodeBuilder::<PosParticleIndex>::new(bind_group_data, &display_shader)
+                .with_use_depth_stencil(true)
+                .with_cull_mode(None)
+                .with_primitive_topology(wgpu::PrimitiveTopology::LineStrip)
+                .with_vertices_and_indices((vertices, indices))
+                .with_color_format(app_view.config.format)
+                .build(&app_view.device);
+
+        Self {
+            mvp_uniform_data,
+            mvp_buf,
+            cloth_uniform_data,
+            cloth_uniform_buf,
+            particle_buf,
+            rest_particles,
+            collider_uniform_data,
+            collider_uniform_buf,
+            collision_solver,
+            collider_display,
+            predict_and_reset,
+            stretch_solver,
+            stretch_colorings,
+            bend_solver,
+            bend_colorings,
+            twist_solver,
+            twist_colorings,
+            display_node,
+            dynamic_offset,
+            pbd_iter_count,
+        }
+    }
+}
+
+impl PBDBody for Strands {
+    fn update_by(&mut self, app: &AppSurface, setting: &crate::PBDSetting) {
+        let mut cloth_uniform_data = self.cloth_uniform_data;
+        cloth_uniform_data.damping = setting.damping * 0.015;
+        cloth_uniform_data.gravity = setting.gravity * -35.0 - 35.0;
+        // 弯曲约束着色器里 stiffness 是允许的弯曲余量，越小发丝越硬
+        cloth_uniform_data.stiffness = (1.0 - setting.strand_stiffness) * 0.05;
+        if cloth_uniform_data != self.cloth_uniform_data {
+            self.cloth_uniform_data = cloth_uniform_data;
+            app.queue.write_buffer(
+                &self.cloth_uniform_buf.buffer,
+                0,
+                bytemuck::bytes_of(&self.cloth_uniform_data),
+            );
+        }
+
+        let mut collider_uniform_data = ColliderUniform::new(setting, SEGMENT_LENGTH);
+        collider_uniform_data.self_collision = 0;
+        if collider_uniform_data != self.collider_uniform_data {
+            self.collider_uniform_data = collider_uniform_data;
+            app.queue.write_buffer(
+                &self.collider_uniform_buf.buffer,
+                0,
+                bytemuck::bytes_of(&self.collider_uniform_data),
+            );
+            self.collider_display.update(app, setting);
+        }
+    }
+
+    fn reset(&mut self, app: &AppSurface) {
+        app.queue.write_buffer(
+            &self.particle_buf.buffer,
+            0,
+            bytemuck::cast_slice(&self.rest_particles),
+        );
+    }
+
+    fn resize(&mut self, app: &AppSurface) -> bool {
+        self.mvp_uniform_data = Cloth::get_mvp_uniform_data(glam::Vec2::new(
+            app.config.width as f32,
+            app.config.height as f32,
+        ));
+        app.queue.write_buffer(
+            &self.mvp_buf.buffer,
+            0,
+            bytemuck::bytes_of(&self.mvp_uniform_data),
+        );
+        true
+    }
+
+    fn set_mvp(&mut self, app: &AppSurface, mvp_uniform_data: &crate::MVPMatUniform) {
+        self.mvp_uniform_data = *mvp_uniform_data;
+        app.queue.write_buffer(
+            &self.mvp_buf.buffer,
+            0,
+            bytemuck::bytes_of(&self.mvp_uniform_data),
+        );
+    }
+
+    fn compute(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("strands solver pass"),
+            ..Default::default()
+        });
+        let has_collision = self.collider_uniform_data.has_collider();
+        let bend_group_num = self.bend_colorings.len() as u32;
+        for i in 0..self.pbd_iter_count as u32 {
+            self.predict_and_reset.compute_by_pass(&mut cpass);
+            for (node, colorings, first_index) in [
+                (&self.stretch_solver, &self.stretch_colorings, 0),
+                (&self.bend_solver, &self.bend_colorings, i * bend_group_num),
+                (&self.twist_solver, &self.twist_colorings, 0),
+            ] {
+                cpass.set_pipeline(&node.pipeline);
+                cpass.set_bind_group(0, &node.bg_setting.bind_group, &[]);
+                for (index, mc) in colorings.iter().enumerate() {
+                    if let Some(bg) = &node.dy_uniform_bg {
+                        cpass.set_bind_group(
+                            1,
+                            &bg.bind_group,
+                            &[(first_index + index as u32) * self.dynamic_offset],
+                        );
+                    }
+                    cpass.dispatch_workgroups(mc.thread_group.0, mc.thread_group.1, 1);
+                }
+            }
+            if has_collision {
+                self.collision_solver.compute_by_pass(&mut cpass);
+            }
+        }
+    }
+
+    fn draw_by_rpass<'b, 'a: 'b>(&'a self, rpass: &mut wgpu::RenderPass<'b>) {
+        self.collider_display.draw_by_pass(rpass);
+        self.display_node.draw_by_pass(rpass);
+    }
+}
+
+/// 发根均匀分布在水平的圆环上，发丝从发根沿半径方向水平伸出，松开后在重力作用下垂落。
+/// 返回粒子、发丝数及每根发丝的粒子数，所有发丝的粒子之后是每段发丝的幽灵粒子
+fn gen_strand_particles(desc: &StrandsDesc) -> (Vec<ParticleBufferObj>, usize, usize) {
+    let strand_len = (desc.length / SEGMENT_LENGTH).round().max(2.0) as usize + 1;
+    let count = desc.count.max(1);
+    let mut particles = Vec::with_capacity(count * (strand_len * 2 - 1));
+    let mut ghosts = Vec::with_capacity(count * (strand_len - 1));
+    let particle = |p: glam::Vec3, along: f32, s: usize, invert_mass: f32| {
+        let pos = [p.x, p.y, p.z, 0.0];
+        ParticleBufferObj {
+            pos,
+            old_pos: pos,
+            accelerate: [0.0; 4],
+            uv_mass: [along, s as f32 / count as f32, invert_mass, 0.0],
+            connect: [0; 4],
+        }
+    };
+    for s in 0..count {
+        let angle = core::f32::consts::TAU * s as f32 / count as f32;
+        let dir = glam::Vec3::new(angle.cos(), 0.0, angle.sin());
+        let root = if count > 1 {
+            ROOT_CENTER + dir * ROOT_RADIUS
+        } else {
+            ROOT_CENTER
+        };
+        // 螺旋绕着发丝的方向旋转
+        let (side, up) = (dir.cross(glam::Vec3::Y), glam::Vec3::Y);
+        let curl_radius = desc.curl * CURL_RADIUS;
+        let positions: Vec<glam::Vec3> = (0..strand_len)
+            .map(|j| {
+                let t = j as f32 * SEGMENT_LENGTH;
+                let phase = t / CURL_PITCH * core::f32::consts::TAU;
+                root + dir * t + (up * (phase.cos() - 1.0) + side * phase.sin()) * curl_radius
+            })
+            .collect();
+        let along = |j: f32| j / (strand_len - 1) as f32;
+        for (j, p) in positions.iter().enumerate() {
+            // 发根固定，其余粒子及幽灵粒子使用相同的质量来保证重力加速度一致
+            let invert_mass = if j == 0 { 0.0 } else { 0.1 };
+            particles.push(particle(*p, along(j as f32), s, invert_mass));
+        }
+
+        // 幽灵粒子位于每段的中点处，标架沿中心线平行移动
+        let mut tangent = (positions[1] - positions[0]).normalize();
+        let mut normal = tangent.any_orthonormal_vector();
+        for (j, segment) in positions.windows(2).enumerate() {
+            let t = (segment[1] - segment[0]).normalize();
+            normal = parallel_transport(normal, tangent, t).normalize();
+            tangent = t;
+            let p = (segment[0] + segment[1]) * 0.5 + normal * GHOST_OFFSET;
+            ghosts.push(particle(p, along(j as f32 + 0.5), s, 0.1));
+        }
+    }
+    particles.append(&mut ghosts);
+    (particles, count, strand_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curly_strands_have_no_rest_twist() {
+        let desc = StrandsDesc {
+            count: 3,
+            length: 0.5,
+            curl: 1.0,
+        };
+        let (particles, count, strand_len) = gen_strand_particles(&desc);
+        assert_eq!(particles.len(), count * (strand_len * 2 - 1));
+        let (_, _, (_, twist)) = super::super::gen_cloth_constraints::generate_strand_constraints(
+            &particles, count, strand_len,
+        );
+        assert_eq!(twist.len(), count * (strand_len - 2));
+        assert!(twist.iter().all(|c| c.rest_twist.abs() < 1e-3));
+    }
+}
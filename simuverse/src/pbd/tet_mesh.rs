@@ -0,0 +1,286 @@
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
+
+/// 软体的形状
+#[derive(Clone, PartialEq, Debug)]
+pub enum SoftBodyShape {
+    Box,
+    Sphere,
+    // TetGen 输出的 .node 文件路径，同名的 .ele 文件存放四面体
+    TetGen(String),
+}
+
+/// 生成软体所需的参数
+#[derive(Clone, PartialEq, Debug)]
+pub struct SoftBodyDesc {
+    pub shape: SoftBodyShape,
+    // 体素化时最长边上的格子数
+    pub resolution: usize,
+}
+
+/// 四面体网格
+#[derive(Default)]
+pub struct TetMesh {
+    pub positions: Vec<[f32; 3]>,
+    pub tets: Vec<[u32; 4]>,
+}
+
+// 立方体的 8 个顶点按 x | y << 1 | z << 2 编号，
+// 沿主对角线 0 -> 7 把立方体分成 6 个四面体，相邻立方体的公共面上剖分一致
+const KUHN_TETS: [[usize; 4]; 6] = [
+    [0, 1, 3, 7],
+    [0, 1, 5, 7],
+    [0, 2, 3, 7],
+    [0, 2, 6, 7],
+    [0, 4, 5, 7],
+    [0, 4, 6, 7],
+];
+
+impl TetMesh {
+    /// 把形状体素化，保留中心在形状内的格子。`size` 为最长边的长度
+    pub fn voxelize(shape: &SoftBodyShape, resolution: usize, size: f32) -> Self {
+        let resolution = resolution.max(1);
+        let extent = match shape {
+            SoftBodyShape::Sphere => glam::Vec3::splat(size),
+            _ => glam::Vec3::new(size, size * 0.6, size * 0.6),
+        };
+        let cell = size / resolution as f32;
+        let dims = (extent / cell).round().max(glam::Vec3::ONE).as_uvec3();
+        let origin = -dims.as_vec3() * cell * 0.5;
+        let is_inside = |center: glam::Vec3| match shape {
+            SoftBodyShape::Sphere => center.length() <= size * 0.5,
+            _ => true,
+        };
+
+        let mut mesh = TetMesh::default();
+        let mut vertex_map: BTreeMap<[u32; 3], u32> = BTreeMap::new();
+        for z in 0..dims.z {
+            for y in 0..dims.y {
+                for x in 0..dims.x {
+                    let center =
+                        origin + (glam::Vec3::new(x as f32, y as f32, z as f32) + 0.5) * cell;
+                    if !is_inside(center) {
+                        continue;
+                    }
+                    let corners: Vec<u32> = (0..8u32)
+                        .map(|i| {
+                            let key = [x + (i & 1), y + ((i >> 1) & 1), z + ((i >> 2) & 1)];
+                            *vertex_map.entry(key).or_insert_with(|| {
+                                let p = origin + glam::UVec3::from(key).as_vec3() * cell;
+                                mesh.positions.push(p.into());
+                                (mesh.positions.len() - 1) as u32
+                            })
+                        })
+                        .collect();
+                    for tet in KUHN_TETS.iter() {
+                        mesh.push_tet(tet.map(|i| corners[i]));
+                    }
+                }
+            }
+        }
+        mesh
+    }
+
+    /// 解析 TetGen 的 .node 及 .ele 文件
+    pub fn from_tetgen(node: &str, ele: &str) -> Result<Self, String> {
+        let mut node_lines = data_lines(node);
+        let header = node_lines.next().ok_or("empty .node data")?;
+        let count: usize = parse_field(&header, 0)?;
+        // 第一个节点的编号决定了索引从 0 还是 1 开始
+        let mut first_index = None;
+        let mut mesh = TetMesh::default();
+        for line in node_lines.take(count) {
+            first_index.get_or_insert(parse_field::<u32>(&line, 0)?);
+            mesh.positions.push([
+                parse_field(&line, 1)?,
+                parse_field(&line, 2)?,
+                parse_field(&line, 3)?,
+            ]);
+        }
+        let first_index = first_index.ok_or("no nodes in .node data")?;
+        if mesh.positions.len() < count {
+            return Err(format!(
+                ".node data is truncated: expected {count} nodes, found {}",
+                mesh.positions.len()
+            ));
+        }
+
+        let mut ele_lines = data_lines(ele);
+        let header = ele_lines.next().ok_or("empty .ele data")?;
+        let count: usize = parse_field(&header, 0)?;
+        for line in ele_lines.take(count) {
+            let mut tet = [0; 4];
+            for (i, v) in tet.iter_mut().enumerate() {
+                *v = parse_field::<u32>(&line, i + 1)?
+                    .checked_sub(first_index)
+                    .filter(|v| (*v as usize) < mesh.positions.len())
+                    .ok_or_else(|| format!("invalid node index in .ele line `{line}`"))?;
+            }
+            mesh.push_tet(tet);
+        }
+        if mesh.tets.len() < count {
+            return Err(format!(
+                ".ele data is truncated: expected {count} tetrahedra, found {}",
+                mesh.tets.len()
+            ));
+        }
+        if mesh.tets.is_empty() {
+            return Err(String::from("no tetrahedra in .ele data"));
+        }
+        Ok(mesh)
+    }
+
+    /// 居中放置，最长边缩放到 `size`
+    pub fn fit_to(&mut self, size: f32) {
+        let (min, max) = self.positions.iter().fold(
+            (glam::Vec3::splat(f32::MAX), glam::Vec3::splat(f32::MIN)),
+            |(min, max), p| (min.min((*p).into()), max.max((*p).into())),
+        );
+        let center = (min + max) * 0.5;
+        let scale = size / (max - min).max_element().max(f32::EPSILON);
+        for p in self.positions.iter_mut() {
+            *p = ((glam::Vec3::from(*p) - center) * scale).into();
+        }
+    }
+
+    // 保证四面体的有向体积为正
+    fn push_tet(&mut self, mut tet: [u32; 4]) {
+        if self.tet_volume(&tet) < 0.0 {
+            tet.swap(2, 3);
+        }
+        self.tets.push(tet);
+    }
+
+    pub fn tet_volume(&self, tet: &[u32; 4]) -> f32 {
+        let [p0, p1, p2, p3] = tet.map(|i| glam::Vec3::from(self.positions[i as usize]));
+        (p1 - p0).cross(p2 - p0).dot(p3 - p0) / 6.0
+    }
+
+    /// 只属于一个四面体的面构成表面，三角形的顶点按逆时针朝外排列
+    pub fn surface_triangles(&self) -> Vec<u32> {
+        let mut faces: BTreeMap<[u32; 3], Option<[u32; 3]>> = BTreeMap::new();
+        for tet in self.tets.iter() {
+            // 从四面体内部看为顺时针，即从外部看为逆时针
+            for face in [
+                [tet[0], tet[2], tet[1]],
+                [tet[0], tet[1], tet[3]],
+                [tet[0], tet[3], tet[2]],
+                [tet[1], tet[2], tet[3]],
+            ] {
+                let mut key = face;
+                key.sort_unstable();
+                faces
+                    .entry(key)
+                    .and_modify(|f| *f = None)
+                    .or_insert(Some(face));
+            }
+        }
+        faces.into_values().flatten().flatten().collect()
+    }
+}
+
+// 跳过空行及 # 开头的注释
+fn data_lines(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.lines()
+        .map(|line| String::from(line.split('#').next().unwrap_or("").trim()))
+        .filter(|line| !line.is_empty())
+}
+
+fn parse_field<T: core::str::FromStr>(line: &str, index: usize) -> Result<T, String> {
+    line.split_whitespace()
+        .nth(index)
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| format!("invalid tetgen line `{line}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NODE_0: &str = "# 0 起始的编号
+4 3 0 0
+0 0.0 0.0 0.0
+1 1.0 0.0 0.0
+2 0.0 1.0 0.0
+3 0.0 0.0 1.0
+";
+    const NODE_1: &str = "4 3 0 0
+1 0.0 0.0 0.0
+2 1.0 0.0 0.0
+3 0.0 1.0 0.0
+4 0.0 0.0 1.0
+";
+
+    #[test]
+    fn tetgen_index_base() {
+        let zero_based = TetMesh::from_tetgen(NODE_0, "1 4 0\n0 0 1 2 3\n").unwrap();
+        let one_based = TetMesh::from_tetgen(NODE_1, "1 4 0\n1 1 2 3 4\n").unwrap();
+        assert_eq!(zero_based.positions, one_based.positions);
+        assert_eq!(zero_based.tets, one_based.tets);
+        assert_eq!(zero_based.positions.len(), 4);
+        // 顶点顺序被调整为正体积
+        assert!((zero_based.tet_volume(&zero_based.tets[0]) - 1.0 / 6.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn tetgen_rejects_invalid_data() {
+        // 索引超出节点数量
+        assert!(TetMesh::from_tetgen(NODE_0, "1 4 0\n0 0 1 2 4\n").is_err());
+        // 1 起始的文件中出现 0
+        assert!(TetMesh::from_tetgen(NODE_1, "1 4 0\n1 0 1 2 3\n").is_err());
+        // 文件被截断
+        assert!(TetMesh::from_tetgen("4 3 0 0\n0 0 0 0\n1 1 0 0\n", "1 4 0\n0 0 1 0 1\n").is_err());
+        assert!(TetMesh::from_tetgen(NODE_0, "2 4 0\n0 0 1 2 3\n").is_err());
+        assert!(TetMesh::from_tetgen(NODE_0, "1 4 0\n0 0 1 2\n").is_err());
+        assert!(TetMesh::from_tetgen("", "1 4 0\n0 0 1 2 3\n").is_err());
+        assert!(TetMesh::from_tetgen(NODE_0, "").is_err());
+    }
+
+    #[test]
+    fn voxelized_tets_have_positive_volume() {
+        for shape in [SoftBodyShape::Box, SoftBodyShape::Sphere] {
+            let mesh = TetMesh::voxelize(&shape, 6, 1.0);
+            assert!(!mesh.tets.is_empty());
+            let mut total = 0.0;
+            for tet in mesh.tets.iter() {
+                let volume = mesh.tet_volume(tet);
+                assert!(volume > 0.0, "{shape:?}: {tet:?} has volume {volume}");
+                total += volume;
+            }
+            // 所有四面体的体积之和等于保留的格子体积
+            let cell = 1.0 / 6.0_f32;
+            let cells = mesh.tets.len() / KUHN_TETS.len();
+            assert!((total - cells as f32 * cell.powi(3)).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn fit_to_centers_and_scales() {
+        let mut mesh = TetMesh::from_tetgen(NODE_0, "1 4 0\n0 0 1 2 3\n").unwrap();
+        for p in mesh.positions.iter_mut() {
+            *p = (glam::Vec3::from(*p) * glam::Vec3::new(4.0, 2.0, 1.0) + 3.0).into();
+        }
+        mesh.fit_to(2.0);
+        let (min, max) = mesh.positions.iter().fold(
+            (glam::Vec3::splat(f32::MAX), glam::Vec3::splat(f32::MIN)),
+            |(min, max), p| (min.min((*p).into()), max.max((*p).into())),
+        );
+        assert!(((min + max) * 0.5).length() < 1e-6);
+        assert!(((max - min) - glam::Vec3::new(2.0, 1.0, 0.5)).length() < 1e-6);
+    }
+
+    #[test]
+    fn single_voxel_surface() {
+        let mesh = TetMesh::voxelize(&SoftBodyShape::Box, 1, 1.0);
+        assert_eq!(mesh.positions.len(), 8);
+        assert_eq!(mesh.tets.len(), 6);
+        let triangles = mesh.surface_triangles();
+        assert_eq!(triangles.len(), 12 * 3);
+        // 每个三角形的法线都朝外
+        for tri in triangles.chunks(3) {
+            let [p0, p1, p2] = [0, 1, 2].map(|i| glam::Vec3::from(mesh.positions[tri[i] as usize]));
+            let normal = (p1 - p0).cross(p2 - p0);
+            let center = (p0 + p1 + p2) / 3.0;
+            assert!(normal.dot(center) > 0.0, "{tri:?} faces inward");
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use alloc::{collections::BTreeMap, format, string::String, vec, vec::Vec};
+
+/// 用于生成布料的三角形网格
+#[derive(Default)]
+pub struct TriangleMesh {
+    pub positions: Vec<[f32; 3]>,
+    // 与 positions 一一对应，网格没有纹理坐标时为空
+    pub uvs: Vec<[f32; 2]>,
+    pub indices: Vec<u32>,
+}
+
+impl TriangleMesh {
+    /// 解析 OBJ 文本，多边形按扇形拆分为三角形
+    ///
+    /// 粒子与 OBJ 中的顶点位置一一对应：纹理接缝处同一位置上的多个 uv 只保留第一个，
+    /// 重复的顶点位置会被合并，保证网格拓扑是连通的
+    pub fn from_obj(text: &str) -> Result<Self, String> {
+        let mut positions: Vec<[f32; 3]> = vec![];
+        let mut tex_coords: Vec<[f32; 2]> = vec![];
+        // 每个面的顶点：(位置索引, uv 索引)
+        let mut faces: Vec<Vec<(usize, Option<usize>)>> = vec![];
+        for (line_index, line) in text.lines().enumerate() {
+            let mut tokens = line.split_whitespace();
+            let invalid = || format!("invalid obj data at line {}", line_index + 1);
+            match tokens.next() {
+                Some("v") => positions.push(parse_floats::<3>(&mut tokens).ok_or_else(invalid)?),
+                Some("vt") => {
+                    let uv = parse_floats::<2>(&mut tokens).ok_or_else(invalid)?;
+                    // OBJ 的 v 轴向上，纹理的 v 轴向下
+                    tex_coords.push([uv[0], 1.0 - uv[1]]);
+                }
+                Some("f") => {
+                    let mut face = vec![];
+                    for token in tokens {
+                        let mut parts = token.split('/');
+                        let v = parse_index(parts.next(), positions.len()).ok_or_else(invalid)?;
+                        let vt = match parts.next() {
+                            Some(s) if !s.is_empty() => {
+                                Some(parse_index(Some(s), tex_coords.len()).ok_or_else(invalid)?)
+                            }
+                            _ => None,
+                        };
+                        face.push((v, vt));
+                    }
+                    if face.len() < 3 {
+                        return Err(invalid());
+                    }
+                    faces.push(face);
+                }
+                _ => {}
+            }
+        }
+        if faces.is_empty() {
+            return Err(String::from("obj data has no faces"));
+        }
+
+        let welded = weld_positions(&positions);
+        let mut remap: Vec<Option<u32>> = vec![None; positions.len()];
+        let mut mesh = TriangleMesh::default();
+        let has_uv = faces.iter().flatten().all(|(_, vt)| vt.is_some());
+        for face in faces.iter() {
+            let mut corners: Vec<u32> = Vec::with_capacity(face.len());
+            for &(v, vt) in face.iter() {
+                let v = welded[v];
+                let index = *remap[v].get_or_insert_with(|| {
+                    mesh.positions.push(positions[v]);
+                    if has_uv {
+                        mesh.uvs.push(tex_coords[vt.unwrap()]);
+                    }
+                    (mesh.positions.len() - 1) as u32
+                });
+                corners.push(index);
+            }
+            for i in 1..(corners.len() - 1) {
+                let tri = [corners[0], corners[i], corners[i + 1]];
+                // 合并顶点后退化的三角形
+                if tri[0] == tri[1] || tri[1] == tri[2] || tri[2] == tri[0] {
+                    continue;
+                }
+                mesh.indices.extend_from_slice(&tri);
+            }
+        }
+        if mesh.indices.is_empty() {
+            return Err(String::from("obj data has no valid triangles"));
+        }
+        Ok(mesh)
+    }
+}
+
+fn parse_floats<'a, const N: usize>(
+    tokens: &mut impl Iterator<Item = &'a str>,
+) -> Option<[f32; N]> {
+    let mut values = [0.0; N];
+    for v in values.iter_mut() {
+        *v = tokens.next()?.parse().ok()?;
+    }
+    Some(values)
+}
+
+// OBJ 索引从 1 开始，负数表示从末尾倒数
+fn parse_index(token: Option<&str>, count: usize) -> Option<usize> {
+    let index: i64 = token?.parse().ok()?;
+    let index = if index < 0 {
+        count as i64 + index
+    } else {
+        index - 1
+    };
+    (0..count as i64).contains(&index).then_some(index as usize)
+}
+
+// 返回每个顶点合并后对应的顶点索引
+fn weld_positions(positions: &[[f32; 3]]) -> Vec<usize> {
+    let (mut min, mut max) = ([f32::MAX; 3], [f32::MIN; 3]);
+    for p in positions.iter() {
+        for i in 0..3 {
+            min[i] = min[i].min(p[i]);
+            max[i] = max[i].max(p[i]);
+        }
+    }
+    let extent = (0..3).map(|i| max[i] - min[i]).fold(0.0_f32, f32::max);
+    let tolerance = (extent * 0.000001).max(f32::EPSILON);
+    let mut cells: BTreeMap<[i64; 3], usize> = BTreeMap::new();
+    positions
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            *cells
+                .entry(p.map(|v| (v / tolerance).round() as i64))
+                .or_insert(i)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weld_duplicated_positions() {
+        // 两个三角形各自使用独立的顶点，共享的边需要合并
+        let mesh = TriangleMesh::from_obj(
+            "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3\nf 4 5 6",
+        )
+        .unwrap();
+        assert_eq!(mesh.positions.len(), 4);
+        assert_eq!(mesh.indices, [0, 1, 2, 0, 2, 3]);
+        assert!(mesh.uvs.is_empty());
+    }
+
+    #[test]
+    fn split_polygons_and_negative_indices() {
+        let mesh =
+            TriangleMesh::from_obj("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf -4 -3 -2 -1").unwrap();
+        assert_eq!(mesh.indices, [0, 1, 2, 0, 2, 3]);
+    }
+
+    #[test]
+    fn skip_degenerate_faces() {
+        // 第 4 个顶点与第 1 个重合，第二个面合并后退化
+        let text = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 0 0\nf 1 2 3\nf 1 2 4";
+        let mesh = TriangleMesh::from_obj(text).unwrap();
+        assert_eq!(mesh.indices, [0, 1, 2]);
+
+        assert!(TriangleMesh::from_obj("v 0 0 0\nv 1 0 0\nv 0 0 0\nf 1 2 3").is_err());
+        assert!(TriangleMesh::from_obj("v 0 0 0\nv 1 0 0\nf 1 2").is_err());
+        assert!(TriangleMesh::from_obj("v 0 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 4").is_err());
+        assert!(TriangleMesh::from_obj("v 0 0 0\nv 1 0 0\nv 1 1 0").is_err());
+    }
+
+    #[test]
+    fn texture_coordinates() {
+        let text = "v 0 0 0\nv 1 0 0\nv 1 1 0\nvt 0 0\nvt 1 0\nvt 1 0.25\nf 1/1 2/2 3/3";
+        let mesh = TriangleMesh::from_obj(text).unwrap();
+        // OBJ 的 v 轴向上
+        assert_eq!(mesh.uvs, [[0.0, 1.0], [1.0, 1.0], [1.0, 0.75]]);
+
+        // 部分顶点缺少纹理坐标时整个网格都不使用纹理坐标
+        let text = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvt 0 0\nf 1/1 2/1 3/1\nf 1 3 4";
+        let mesh = TriangleMesh::from_obj(text).unwrap();
+        assert!(mesh.uvs.is_empty());
+        assert_eq!(mesh.positions.len(), 4);
+    }
+}
@@ -0,0 +1,127 @@
+//! # 场景自动化脚本
+//!
+//! 一行一条命令，`#` 开头的行为注释：
+//!
+//! ```text
+//! simu fluid                   # 切换模拟类型：field | fluid | noise | pbd | cad
+//! set particles_count 20000    # 设置 ControlPanel 参数，见 `ControlPanel::set_param`
+//! set viscosity 0.05
+//! set noise.bake out/a.png    # 以当前的噪声参数烘焙并导出纹理
+//! frames 120                   # 等待 120 帧
+//! click 400 300                # 在物理像素坐标处点击
+//! drag 100 300 600 300 30      # 从 (100, 300) 拖动到 (600, 300)，历时 30 帧（默认 10 帧）
+//! screenshot output/fluid.png  # 截取下一次绘制的画面
+//! exit                         # 退出程序
+//! ```
+//!
+//! 运行：`cargo run -- --script demo.txt`
+
+use crate::SimuType;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+mod script_runner;
+pub use script_runner::ScriptRunner;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScriptCmd {
+    Simu(SimuType),
+    Set(String, String),
+    Frames(u32),
+    Click(glam::Vec2),
+    // 拖动在解析时被拆分为按下、逐帧移动与抬起
+    Press(glam::Vec2),
+    Move(glam::Vec2),
+    Release,
+    Screenshot(String),
+    Exit,
+}
+
+const DEFAULT_DRAG_FRAMES: u32 = 10;
+
+pub fn parse_script(source: &str) -> Result<Vec<ScriptCmd>, String> {
+    let mut cmds: Vec<ScriptCmd> = Vec::new();
+    for (line_index, line) in source.lines().enumerate() {
+        let line = match line.find('#') {
+            Some(index) => &line[..index],
+            None => line,
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if words.is_empty() {
+            continue;
+        }
+        let err = |msg: &str| format!("line {}: {msg}: `{}`", line_index + 1, line.trim());
+        let args = &words[1..];
+        match (words[0], args.len()) {
+            ("simu", 1) => {
+                let ty = SimuType::from_name(args[0]).ok_or_else(|| err("unknown simu type"))?;
+                cmds.push(ScriptCmd::Simu(ty));
+            }
+            ("set", 2) => cmds.push(ScriptCmd::Set(args[0].to_string(), args[1].to_string())),
+            ("frames", 1) => {
+                let count = args[0].parse().map_err(|_| err("invalid frame count"))?;
+                cmds.push(ScriptCmd::Frames(count));
+            }
+            ("click", 2) => {
+                let pos = parse_pos(args[0], args[1]).ok_or_else(|| err("invalid position"))?;
+                cmds.push(ScriptCmd::Click(pos));
+            }
+            ("drag", 4 | 5) => {
+                let from = parse_pos(args[0], args[1]).ok_or_else(|| err("invalid position"))?;
+                let to = parse_pos(args[2], args[3]).ok_or_else(|| err("invalid position"))?;
+                let frames = match args.get(4) {
+                    Some(v) => v.parse().map_err(|_| err("invalid frame count"))?,
+                    None => DEFAULT_DRAG_FRAMES,
+                };
+                let frames = frames.max(1);
+                cmds.push(ScriptCmd::Press(from));
+                for i in 1..=frames {
+                    cmds.push(ScriptCmd::Move(from.lerp(to, i as f32 / frames as f32)));
+                    cmds.push(ScriptCmd::Frames(1));
+                }
+                cmds.push(ScriptCmd::Release);
+            }
+            ("screenshot", 1) => cmds.push(ScriptCmd::Screenshot(args[0].to_string())),
+            ("exit", 0) => cmds.push(ScriptCmd::Exit),
+            _ => return Err(err("unknown command or wrong number of arguments")),
+        }
+    }
+    Ok(cmds)
+}
+
+fn parse_pos(x: &str, y: &str) -> Option<glam::Vec2> {
+    Some(glam::Vec2::new(x.parse().ok()?, y.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_commands() {
+        let cmds = parse_script(
+            "# demo\nsimu noise\nset noise.octave 4 # inline comment\n\nframes 3\ndrag 0 0 10 0 2\nexit",
+        )
+        .unwrap();
+        assert_eq!(
+            cmds,
+            [
+                ScriptCmd::Simu(SimuType::Noise),
+                ScriptCmd::Set("noise.octave".into(), "4".into()),
+                ScriptCmd::Frames(3),
+                ScriptCmd::Press(glam::Vec2::ZERO),
+                ScriptCmd::Move(glam::Vec2::new(5.0, 0.0)),
+                ScriptCmd::Frames(1),
+                ScriptCmd::Move(glam::Vec2::new(10.0, 0.0)),
+                ScriptCmd::Frames(1),
+                ScriptCmd::Release,
+                ScriptCmd::Exit,
+            ]
+        );
+        assert!(parse_script("simu unknown").is_err());
+        assert!(parse_script("click 1").is_err());
+    }
+}
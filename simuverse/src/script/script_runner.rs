@@ -0,0 +1,51 @@
+use super::ScriptCmd;
+use alloc::vec::Vec;
+
+/// 按帧推进脚本
+///
+/// 每帧调用一次 `next_frame`，返回本帧需要执行的命令，遇到 `frames N` 时暂停 N 帧。
+pub struct ScriptRunner {
+    cmds: Vec<ScriptCmd>,
+    cursor: usize,
+    wait_frames: u32,
+}
+
+impl ScriptRunner {
+    pub fn new(cmds: Vec<ScriptCmd>) -> Self {
+        Self {
+            cmds,
+            cursor: 0,
+            wait_frames: 0,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.cursor >= self.cmds.len() && self.wait_frames == 0
+    }
+
+    pub fn next_frame(&mut self) -> Vec<ScriptCmd> {
+        let mut cmds = Vec::new();
+        if self.wait_frames > 0 {
+            self.wait_frames -= 1;
+            return cmds;
+        }
+        while let Some(cmd) = self.cmds.get(self.cursor) {
+            self.cursor += 1;
+            match cmd {
+                ScriptCmd::Frames(count) if *count > 0 => {
+                    // 本帧算作等待的第一帧
+                    self.wait_frames = count - 1;
+                    break;
+                }
+                ScriptCmd::Frames(_) => {}
+                // 截图需要等到本帧绘制，之后的命令留到下一帧
+                ScriptCmd::Screenshot(_) => {
+                    cmds.push(cmd.clone());
+                    break;
+                }
+                _ => cmds.push(cmd.clone()),
+            }
+        }
+        cmds
+    }
+}
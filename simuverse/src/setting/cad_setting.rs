@@ -1,9 +1,31 @@
+use super::parse_param;
+use alloc::{format, string::String, vec, vec::Vec};
 use egui::Color32;
 
 pub struct CADSetting {
     pub simu_ty: u32,
     pub render_mode: u32,
     pub texture: u32,
+    // Obj Load 中加载的 OBJ 或 STL 文件，为空时是内置的模型
+    pub model_file: String,
+    // STEP 中加载的文件，为空时是内置的示例
+    pub step_file: String,
+    // 加载失败的原因
+    pub load_status: String,
+    // STEP 模型树，加载模型后由 StepApp 填充
+    pub step_tree: Vec<StepShellNode>,
+    // 模型树中选中的面：(壳的序号, 面的序号)
+    pub selected_face: Option<(usize, usize)>,
+}
+
+/// STEP 模型树中的壳，每个壳是一个 B-rep 实体的边界
+pub struct StepShellNode {
+    pub id: u64,
+    pub label: String,
+    // 每个面的曲面类型
+    pub faces: Vec<String>,
+    pub edge_count: usize,
+    pub visible: bool,
 }
 
 impl Default for CADSetting {
@@ -18,7 +40,32 @@ impl CADSetting {
             simu_ty: 0,
             render_mode: 3,
             texture: 0,
+            model_file: String::new(),
+            step_file: String::new(),
+            load_status: String::new(),
+            step_tree: Vec::new(),
+            selected_face: None,
+        }
+    }
+
+    pub fn set_param(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "type" => self.simu_ty = parse_param::<u32>(key, value)?.min(2),
+            "render_mode" => self.render_mode = parse_param::<u32>(key, value)?.min(3),
+            "model_file" => self.model_file = String::from(value.trim()),
+            "step_file" => self.step_file = String::from(value.trim()),
+            _ => return Err(format!("unknown cad parameter `{key}`")),
         }
+        Ok(())
+    }
+
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("type", format!("{}", self.simu_ty)),
+            ("render_mode", format!("{}", self.render_mode)),
+            ("model_file", self.model_file.clone()),
+            ("step_file", self.step_file.clone()),
+        ]
     }
 
     pub fn ui_contents(&mut self, ui: &mut egui::Ui) {
@@ -26,11 +73,22 @@ impl CADSetting {
             ui.label("Type:");
             ui.selectable_value(&mut self.simu_ty, 0, "B-Spline");
             ui.selectable_value(&mut self.simu_ty, 1, "Obj Load");
+            ui.selectable_value(&mut self.simu_ty, 2, "STEP");
         });
         if self.simu_ty == 0 {
             return;
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.simu_ty == 1 {
+            self.model_file_ui(ui);
+        } else {
+            self.step_file_ui(ui);
+        }
+        if self.simu_ty == 2 {
+            self.step_tree_ui(ui);
+        }
+
         // ui.separator();
         // ui.horizontal(|ui| {
         //     ui.heading("Procedural texture：");
@@ -91,6 +149,142 @@ impl CADSetting {
             ui.label("to the camera's position");
         });
     }
+
+    /// 拖放到窗口上的文件，接受 OBJ、STL 及 STEP
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open_model_file(&mut self, path: &str) -> Result<(), String> {
+        let ext = path
+            .rsplit_once('.')
+            .map(|(_, ext)| ext.to_ascii_lowercase());
+        match ext.as_deref() {
+            Some("obj" | "stl") => {
+                self.simu_ty = 1;
+                self.model_file = String::from(path);
+            }
+            Some("step" | "stp") => {
+                self.simu_ty = 2;
+                self.step_file = String::from(path);
+            }
+            _ => {
+                return Err(format!(
+                    "unsupported model file {path}, expected .obj, .stl, .step or .stp"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn model_file_ui(&mut self, ui: &mut egui::Ui) {
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Model:");
+            let name = std::path::Path::new(&self.model_file)
+                .file_name()
+                .map_or(String::from("Built-in skull"), |name| {
+                    name.to_string_lossy().into_owned()
+                });
+            ui.label(name).on_hover_text(&self.model_file);
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Open…").clicked()
+                && let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Mesh", &["obj", "stl", "OBJ", "STL"])
+                    .pick_file()
+            {
+                self.model_file = path.to_string_lossy().into_owned();
+            }
+            if ui
+                .add_enabled(!self.model_file.is_empty(), egui::Button::new("Built-in"))
+                .clicked()
+            {
+                self.model_file.clear();
+            }
+        });
+        ui.label("Or drop an OBJ / STL file onto the window");
+        if !self.load_status.is_empty() {
+            ui.colored_label(ui.visuals().error_fg_color, &self.load_status);
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn step_file_ui(&mut self, ui: &mut egui::Ui) {
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Model:");
+            let name = std::path::Path::new(&self.step_file)
+                .file_name()
+                .map_or(String::from("Built-in sample"), |name| {
+                    name.to_string_lossy().into_owned()
+                });
+            ui.label(name).on_hover_text(&self.step_file);
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Open…").clicked()
+                && let Some(path) = rfd::FileDialog::new()
+                    .add_filter("STEP", &["step", "stp", "STEP", "STP"])
+                    .pick_file()
+            {
+                self.step_file = path.to_string_lossy().into_owned();
+            }
+            if ui
+                .add_enabled(!self.step_file.is_empty(), egui::Button::new("Built-in"))
+                .clicked()
+            {
+                self.step_file.clear();
+            }
+        });
+        ui.label("Or drop a STEP file onto the window");
+        if !self.load_status.is_empty() {
+            ui.colored_label(ui.visuals().error_fg_color, &self.load_status);
+        }
+    }
+
+    /// 每个壳可以切换可见性，展开后列出它的面，点击面可以高亮显示
+    fn step_tree_ui(&mut self, ui: &mut egui::Ui) {
+        ui.separator();
+        ui.heading("Model Tree");
+        if self.step_tree.is_empty() {
+            ui.label("No shells loaded");
+            return;
+        }
+        egui::ScrollArea::vertical()
+            .max_height(240.0)
+            .show(ui, |ui| {
+                for (shell_index, shell) in self.step_tree.iter_mut().enumerate() {
+                    let title = if shell.label.is_empty() {
+                        format!("Shell #{}", shell.id)
+                    } else {
+                        format!("{} (#{})", shell.label, shell.id)
+                    };
+                    egui::collapsing_header::CollapsingState::load_with_default_open(
+                        ui.ctx(),
+                        ui.make_persistent_id(("step_shell", shell.id)),
+                        false,
+                    )
+                    .show_header(ui, |ui| {
+                        ui.checkbox(&mut shell.visible, title);
+                    })
+                    .body(|ui| {
+                        ui.label(format!(
+                            "{} faces, {} edges",
+                            shell.faces.len(),
+                            shell.edge_count
+                        ));
+                        for (face_index, kind) in shell.faces.iter().enumerate() {
+                            let index = (shell_index, face_index);
+                            let selected = self.selected_face == Some(index);
+                            if ui
+                                .selectable_label(selected, format!("Face {face_index}: {kind}"))
+                                .clicked()
+                            {
+                                self.selected_face = (!selected).then_some(index);
+                            }
+                        }
+                    });
+                }
+            });
+    }
 }
 
 #[allow(dead_code)]
@@ -1,8 +1,15 @@
+use super::{parse_param, persistence};
 use crate::{
     CADSetting, FieldAnimationType, NoiseSetting, PBDSetting, ParticleColorType, SettingObj,
     SimuType,
 };
-use alloc::{borrow::ToOwned, string::String, vec};
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 use app_surface::AppSurface;
 use egui::{CollapsingHeader, Color32, Context, Ui};
 
@@ -14,6 +21,7 @@ pub struct ControlPanel {
     pub particle_size: i32,
     pub particle_color: u32,
     pub lifetime: i32,
+    pub fluid_viscosity: f32,
     pub wgsl_code: String,
     last_selected_code_snippet: i32,
     selected_code_snippet: Option<i32>,
@@ -22,8 +30,17 @@ pub struct ControlPanel {
     pub noise_setting: NoiseSetting,
     pub pbd_setting: PBDSetting,
     pub cad_setting: CADSetting,
+    /// 是否将面板设置保存下来，供下次启动时恢复
+    pub persistence_enabled: bool,
+    default_params: String,
+    saved_params: String,
+    saved_wgsl_code: String,
+    persist_countdown: u32,
 }
 
+/// 每隔多少帧检查一次设置是否有变化
+const PERSIST_INTERVAL: u32 = 60;
+
 impl ControlPanel {
     pub fn new(app: &AppSurface, egui_ctx: &Context) -> Self {
         let lifetime = 90;
@@ -50,18 +67,24 @@ impl ControlPanel {
         // 实测出来的数值，避免圆角被裁剪
         let window_size: egui::emath::Vec2 = [panel_width - 26.0, panel_height].into();
 
-        let mut bg = egui_ctx.style().visuals.window_fill();
+        let mut bg = egui_ctx.global_style().visuals.window_fill();
         bg = egui::Color32::from_rgba_premultiplied(bg.r(), bg.g(), bg.b(), 230);
         let panel_frame = egui::Frame {
             fill: bg,
             corner_radius: 10.0.into(),
-            stroke: egui_ctx.style().visuals.widgets.noninteractive.fg_stroke,
+            stroke: egui_ctx
+                .global_style()
+                .visuals
+                .widgets
+                .noninteractive
+                .fg_stroke,
             outer_margin: 0.5.into(), // so the stroke is within the bounds
             inner_margin: 12.0.into(),
             ..Default::default()
         };
 
-        Self {
+        let mut instance = Self {
+            fluid_viscosity: setting.fluid_viscosity,
             setting,
             panel_frame,
             window_size,
@@ -77,6 +100,153 @@ impl ControlPanel {
             noise_setting: NoiseSetting::new(),
             pbd_setting: PBDSetting::default(),
             cad_setting: CADSetting::default(),
+            persistence_enabled: true,
+            default_params: String::new(),
+            saved_params: String::new(),
+            saved_wgsl_code: String::new(),
+            persist_countdown: PERSIST_INTERVAL,
+        };
+        instance.default_params = instance.params_to_string();
+        instance.restore();
+        #[cfg(target_arch = "wasm32")]
+        instance.apply_url_params();
+
+        instance
+    }
+
+    /// 导出当前面板状态，每个参数都可由 `set_param` 还原
+    pub fn params(&self) -> Vec<(String, String)> {
+        let mut params = vec![
+            (
+                "simu".to_string(),
+                self.selected_simu_type.name().to_string(),
+            ),
+            (
+                "particles_count".to_string(),
+                format!("{}", self.particles_count),
+            ),
+            (
+                "particle_size".to_string(),
+                format!("{}", self.particle_size),
+            ),
+            (
+                "particle_color".to_string(),
+                format!("{}", self.particle_color),
+            ),
+            ("lifetime".to_string(), format!("{}", self.lifetime)),
+            ("viscosity".to_string(), format!("{}", self.fluid_viscosity)),
+        ];
+        if let Some(preset) = self.selected_code_snippet {
+            params.push(("field.preset".to_string(), format!("{preset}")));
+        }
+        for (prefix, sub_params) in [
+            ("noise", self.noise_setting.params()),
+            ("pbd", self.pbd_setting.params()),
+            ("cad", self.cad_setting.params()),
+        ] {
+            for (key, value) in sub_params {
+                params.push((format!("{prefix}.{key}"), value));
+            }
+        }
+        params
+    }
+
+    fn params_to_string(&self) -> String {
+        self.params()
+            .iter()
+            .map(|(key, value)| format!("{key} {value}\n"))
+            .collect()
+    }
+
+    /// 逐行应用 `key value` 格式的参数
+    fn apply_params(&mut self, params: &str) {
+        for line in params.lines() {
+            let Some((key, value)) = line.trim().split_once(' ') else {
+                continue;
+            };
+            if let Err(e) = self.set_param(key, value.trim()) {
+                log::warn!("ignore saved setting: {e}");
+            }
+        }
+    }
+
+    /// 恢复上次保存的面板设置
+    fn restore(&mut self) {
+        let Some((params, wgsl_code)) = persistence::load() else {
+            return;
+        };
+        self.apply_params(&params);
+        if let Some(code_index) = self.selected_code_snippet {
+            // 避免 ui_contents 用预设实现覆盖掉保存的代码片段
+            self.last_selected_code_snippet = code_index;
+        }
+        if let Some(wgsl_code) = wgsl_code {
+            self.wgsl_code = wgsl_code;
+            self.is_code_snippet_changed = true;
+        }
+        self.saved_params = params;
+        self.saved_wgsl_code = self.wgsl_code.clone();
+    }
+
+    /// 应用分享链接中的参数，优先于保存的设置
+    ///
+    /// 打开分享链接时不再保存设置，避免覆盖访问者自己保存的设置
+    #[cfg(target_arch = "wasm32")]
+    fn apply_url_params(&mut self) {
+        let params = super::share_link::params_from_url();
+        if params.is_empty() {
+            return;
+        }
+        for (key, value) in params {
+            if let Err(e) = self.set_param(&key, &value) {
+                log::warn!("ignore url parameter: {e}");
+            }
+        }
+        self.persistence_enabled = false;
+    }
+
+    /// 分享链接中的参数，与预设不同的代码片段也一并带上
+    #[cfg(target_arch = "wasm32")]
+    fn share_params(&self) -> Vec<(String, String)> {
+        let mut params = self.params();
+        let preset = self.selected_code_snippet.map(|code_index| {
+            crate::get_velocity_code_snippet(crate::FieldAnimationType::from_u32(code_index as u32))
+        });
+        if preset.as_ref() != Some(&self.wgsl_code) {
+            params.push(("field.wgsl".to_string(), self.wgsl_code.clone()));
+        }
+        params
+    }
+
+    /// 恢复默认设置，保持当前的模拟类型不变
+    pub fn reset_to_defaults(&mut self) {
+        let simu_type = self.selected_simu_type;
+        self.noise_setting = NoiseSetting::new();
+        self.pbd_setting = PBDSetting::default();
+        self.cad_setting = CADSetting::default();
+        let default_params = self.default_params.clone();
+        self.apply_params(&default_params);
+        self.selected_simu_type = simu_type;
+        // 强制 ui_contents 重新加载预设的代码片段
+        self.last_selected_code_snippet = -1;
+    }
+
+    /// 设置有变化时保存下来
+    fn persist_if_changed(&mut self) {
+        if !self.persistence_enabled {
+            return;
+        }
+        self.persist_countdown = self.persist_countdown.saturating_sub(1);
+        if self.persist_countdown > 0 {
+            return;
+        }
+        self.persist_countdown = PERSIST_INTERVAL;
+
+        let params = self.params_to_string();
+        if params != self.saved_params || self.wgsl_code != self.saved_wgsl_code {
+            persistence::save(&params, &self.wgsl_code);
+            self.saved_params = params;
+            self.saved_wgsl_code = self.wgsl_code.clone();
         }
     }
 
@@ -116,16 +286,66 @@ impl ControlPanel {
                 self.lifetime as f32,
                 self.particle_size,
             );
+            setting.fluid_viscosity = self.fluid_viscosity;
             setting.update_canvas_size(app, glam::UVec2::new(app.config.width, app.config.height));
             self.setting = setting;
 
             simu_ty_changed = true;
         }
+        self.persist_if_changed();
 
         (workgroup_count_changed, simu_ty_changed)
     }
 
-    pub fn ui_contents(&mut self, ctx: &Context) {
+    /// 按名称设置面板参数，供自动化脚本使用
+    ///
+    /// 取值范围与面板上的滑块一致，`noise.`、`pbd.`、`cad.` 前缀的参数交由对应的设置对象处理
+    pub fn set_param(&mut self, key: &str, value: &str) -> Result<(), String> {
+        if let Some(key) = key.strip_prefix("noise.") {
+            return self.noise_setting.set_param(key, value);
+        }
+        if let Some(key) = key.strip_prefix("pbd.") {
+            return self.pbd_setting.set_param(key, value);
+        }
+        if let Some(key) = key.strip_prefix("cad.") {
+            return self.cad_setting.set_param(key, value);
+        }
+        match key {
+            "simu" => {
+                self.selected_simu_type = SimuType::from_name(value)
+                    .ok_or_else(|| format!("unknown simu type `{value}`"))?
+            }
+            // 当前模拟类型的预设
+            "preset" => match self.selected_simu_type {
+                SimuType::Field => return self.set_param("field.preset", value),
+                SimuType::Noise => return self.noise_setting.set_param("type", value),
+                SimuType::CAD => return self.cad_setting.set_param("type", value),
+                _ => return Err(format!("`{key}` is not supported by current simu type")),
+            },
+            "particles_count" => {
+                self.particles_count = parse_param::<i32>(key, value)?.clamp(2000, 40000)
+            }
+            "particle_size" => self.particle_size = parse_param::<i32>(key, value)?.clamp(1, 8),
+            "particle_color" => self.particle_color = parse_param::<u32>(key, value)?.min(2),
+            "lifetime" => self.lifetime = parse_param::<i32>(key, value)?.clamp(40, 240),
+            "viscosity" => self.fluid_viscosity = parse_param::<f32>(key, value)?.clamp(0.001, 0.5),
+            "field.preset" => {
+                self.selected_code_snippet = Some(parse_param::<i32>(key, value)?.clamp(0, 3))
+            }
+            "field.wgsl" => {
+                self.wgsl_code = String::from(value);
+                self.is_code_snippet_changed = true;
+                if let Some(code_index) = self.selected_code_snippet {
+                    // 避免 ui_contents 用预设实现覆盖掉自定义的代码片段
+                    self.last_selected_code_snippet = code_index;
+                }
+            }
+            _ => return Err(format!("unknown parameter `{key}`")),
+        }
+        Ok(())
+    }
+
+    pub fn ui_contents(&mut self, ui: &mut Ui) {
         match self.selected_code_snippet {
             Some(code_index) if code_index != self.last_selected_code_snippet => {
                 self.last_selected_code_snippet = code_index;
@@ -137,7 +357,7 @@ impl ControlPanel {
             _ => {}
         }
 
-        self.top_bar_ui(ctx);
+        self.top_bar_ui(ui);
 
         let window = egui::Window::new(" Settings")
             .id(egui::Id::new("particles_window_options")) // required since we change the title
@@ -150,7 +370,7 @@ impl ControlPanel {
             .frame(self.panel_frame)
             .enabled(true);
 
-        window.show(ctx, |ui| {
+        window.show(ui.ctx(), |ui| {
             match self.selected_simu_type {
                 SimuType::Field | SimuType::Fluid => self.particles_ctrl_ui(ui),
                 SimuType::Noise => self.noise_setting.ui_contents(ui),
@@ -178,6 +398,11 @@ impl ControlPanel {
                 }
                 _ => (),
             }
+
+            ui.separator();
+            if ui.button("Reset to defaults").clicked() {
+                self.reset_to_defaults();
+            }
         });
     }
 
@@ -185,7 +410,7 @@ impl ControlPanel {
     ///
     /// # NOTE:
     /// 下边的  字符不是乱码，它是 iconfont 中的一个图标，编辑器中无法正确显示
-    fn top_bar_ui(&mut self, ctx: &Context) {
+    fn top_bar_ui(&mut self, ui: &mut Ui) {
         let mut menu_items = vec![
             ("Vector Field", SimuType::Field),
             ("LBM Fluid", SimuType::Fluid),
@@ -195,7 +420,7 @@ impl ControlPanel {
         if cfg!(not(target_arch = "wasm32")) {
             menu_items.push((" CAD Kenel", SimuType::CAD));
         }
-        egui::TopBottomPanel::top("simuverse_top_bar").show(ctx, |ui| {
+        egui::Panel::top("simuverse_top_bar").show_inside(ui, |ui| {
             ui.horizontal_wrapped(|ui| {
                 ui.visuals_mut().button_frame = false;
                 if ui
@@ -222,6 +447,16 @@ impl ControlPanel {
                     {
                         webbrowser::open("https://github.com/jinleili/simuverse").unwrap();
                     }
+                    #[cfg(target_arch = "wasm32")]
+                    {
+                        ui.separator();
+                        if ui.button("Copy share link").clicked()
+                            && let Some(link) = super::share_link::share_link(&self.share_params())
+                        {
+                            super::share_link::copy_to_clipboard(&link);
+                            log::info!("share link copied: {link}");
+                        }
+                    }
                 });
             });
         });
@@ -245,6 +480,12 @@ impl ControlPanel {
                 ui.add(egui::Slider::new(&mut self.lifetime, 40..=240).text("frame"));
                 ui.end_row();
 
+                if self.selected_simu_type == SimuType::Fluid {
+                    ui.label("Viscosity：");
+                    ui.add(egui::Slider::new(&mut self.fluid_viscosity, 0.001..=0.5));
+                    ui.end_row();
+                }
+
                 ui.label("Particle color：");
                 egui::ComboBox::from_label("")
                     .selected_text(get_color_ty_name(self.particle_color))
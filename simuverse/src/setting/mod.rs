@@ -12,3 +12,160 @@ pub(crate) use pbd_setting::PBDSetting;
 
 mod cad_setting;
 pub(crate) use cad_setting::CADSetting;
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use cad_setting::StepShellNode;
+
+mod persistence;
+
+#[cfg(any(target_arch = "wasm32", test))]
+mod share_link;
+
+use alloc::{format, string::String};
+
+/// 解析脚本等外部输入的参数值
+pub(crate) fn parse_param<T: core::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .parse::<T>()
+        .map_err(|_| format!("invalid value `{value}` for `{key}`"))
+}
+
+/// 解析 `x,y,z` 格式的向量
+pub(crate) fn parse_vec3(key: &str, value: &str) -> Result<[f32; 3], String> {
+    let mut v = [0.0; 3];
+    let mut components = value.split(',');
+    for c in v.iter_mut() {
+        let component = components.next().unwrap_or_default();
+        *c = parse_param::<f32>(key, component)?;
+    }
+    if components.next().is_some() {
+        return Err(format!("invalid value `{value}` for `{key}`"));
+    }
+    Ok(v)
+}
+
+/// 解析 `r,g,b` 格式的颜色
+pub(crate) fn parse_color(key: &str, value: &str) -> Result<[f32; 3], String> {
+    Ok(parse_vec3(key, value)?.map(|c| c.clamp(0.0, 1.0)))
+}
+
+pub(crate) fn format_vec3(v: &[f32; 3]) -> String {
+    format!("{},{},{}", v[0], v[1], v[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    // 把导出的参数逐个应用到新的设置上，再次导出的参数应与原来的完全一致
+    fn assert_round_trip<S>(
+        source: &S,
+        mut target: S,
+        params: impl Fn(&S) -> Vec<(&'static str, String)>,
+        set_param: impl Fn(&mut S, &str, &str) -> Result<(), String>,
+    ) {
+        let expected = params(source);
+        for (key, value) in expected.iter() {
+            set_param(&mut target, key, value).unwrap();
+        }
+        assert_eq!(params(&target), expected);
+    }
+
+    #[test]
+    fn noise_params_round_trip() {
+        for ty in 0..=crate::noise::GRAPH_MATERIAL {
+            let mut setting = NoiseSetting::new();
+            setting.set_type(ty);
+            for (key, value) in [
+                ("color0", "0.1,0.2,0.3"),
+                ("scale", "3.5"),
+                ("octave", "7"),
+                ("seed", "42"),
+                ("paused", "true"),
+                ("view", "2"),
+                ("sea_level", "0.3"),
+                ("warp_iterations", "2"),
+                ("bake_dimension", "3"),
+                ("bake_size", "128"),
+                ("tileable", "true"),
+                ("bake_path", "out/noise.ktx2"),
+            ] {
+                setting.set_param(key, value).unwrap();
+            }
+            assert_round_trip(
+                &setting,
+                NoiseSetting::new(),
+                NoiseSetting::params,
+                NoiseSetting::set_param,
+            );
+        }
+    }
+
+    #[test]
+    fn pbd_params_round_trip() {
+        for ty in 0..=3 {
+            let mut setting = PBDSetting::new();
+            setting.set_type(ty);
+            for (key, value) in [
+                ("damping", "0.75"),
+                ("light_azimuth", "123.5"),
+                ("back_color", "0.5,0.25,1"),
+                ("mesh", "2"),
+                ("mesh_file", "cloth.obj"),
+                ("texture", "1"),
+                ("resolution_x", "33"),
+                ("pin", "4"),
+                ("custom_pins", "0,0;0.5,1"),
+                ("tearable", "true"),
+                ("sphere_center", "0.1,-0.2,0.3"),
+                ("box_size", "0.5,1,1.5"),
+                ("wind_direction", "270"),
+                ("soft_body_shape", "2"),
+                ("soft_body_file", "bunny.node"),
+                ("volume_compliance", "0.4"),
+                ("strand_curl", "0.6"),
+                ("fluid_particles", "4096"),
+            ] {
+                setting.set_param(key, value).unwrap();
+            }
+            assert_round_trip(
+                &setting,
+                PBDSetting::new(),
+                PBDSetting::params,
+                PBDSetting::set_param,
+            );
+        }
+    }
+
+    #[test]
+    fn pbd_params_are_clamped_like_the_ui() {
+        let mut setting = PBDSetting::new();
+        setting.set_param("sphere_center", "3,-5,1").unwrap();
+        setting.set_param("box_center", "-2.5,0.5,9").unwrap();
+        assert_eq!(setting.sphere_center, [2.0, -2.0, 1.0]);
+        assert_eq!(setting.box_center, [-2.0, 0.5, 2.0]);
+        // 原生平台才有 Image file 选项
+        setting.set_param("texture", "5").unwrap();
+        let max_texture = if cfg!(target_arch = "wasm32") { 1 } else { 2 };
+        assert_eq!(setting.cloth_texture, max_texture);
+    }
+
+    #[test]
+    fn cad_params_round_trip() {
+        let mut setting = CADSetting::new();
+        for (key, value) in [
+            ("type", "1"),
+            ("render_mode", "1"),
+            ("model_file", "teapot.stl"),
+            ("step_file", "part.step"),
+        ] {
+            setting.set_param(key, value).unwrap();
+        }
+        assert_round_trip(
+            &setting,
+            CADSetting::new(),
+            CADSetting::params,
+            CADSetting::set_param,
+        );
+    }
+}
@@ -1,3 +1,7 @@
+use super::{format_vec3, parse_color, parse_param};
+use crate::noise::{BASIS_NAMES, GRAPH_MATERIAL, MAX_COLOR_STOPS, MaterialGraph};
+use alloc::{format, string::String, vec::Vec};
+
 #[derive(Default)]
 pub struct NoiseSetting {
     pub simu_ty: Option<i32>,
@@ -8,12 +12,71 @@ pub struct NoiseSetting {
     pub lacunarity: f32,
     pub gain: f32,
     hide_gain: bool,
+    // Marble 及 Wood 的多色标渐变，为空时在 color0 与 color1 之间插值
+    pub(crate) color_stops: Vec<ColorStop>,
+    // 基础噪声及域扭曲，与材质类型无关，切换材质时保留
+    pub basis: i32,
+    pub warp_iterations: i32,
+    pub warp_strength: f32,
+    // 动画的速度倍数及是否暂停
+    pub time_speed: f32,
+    pub paused: bool,
+    // 显示方式：0 球面材质 | 1 平面地形 | 2 星球 | 3 体积，地形及星球以噪声为高度场
+    pub view: u32,
+    pub height_scale: f32,
+    // [0, 1] 内的海平面高度
+    pub sea_level: f32,
+    // 体积的密度倍数及步进的步数，不透明度在 opacity_low 与 opacity_high 之间增加
+    pub density_scale: f32,
+    pub volume_steps: u32,
+    pub opacity_low: f32,
+    pub opacity_high: f32,
+    // 打乱排列表及梯度表的种子，0 为经典的排列表
+    pub seed: u32,
+    // 节点图材质
+    pub(crate) graph: MaterialGraph,
+    show_graph_editor: bool,
+    // 烘焙导出：2 | 3 维，边长，是否可平铺，保存路径
+    pub bake_dimension: u32,
+    pub bake_size: u32,
+    pub tileable: bool,
+    pub bake_path: String,
+    // 点击导出后由 TextureSimulator 处理并写回结果
+    pub bake_requested: bool,
+    pub bake_status: String,
+}
+
+/// 渐变的色标
+#[derive(Clone, Copy)]
+pub(crate) struct ColorStop {
+    pub position: f32,
+    pub color: [f32; 3],
+    /// 到下一个色标的插值方式：0 线性 | 1 平滑 | 2 常量
+    pub interpolation: u32,
 }
 
+const INTERPOLATION_NAMES: [&str; 3] = ["Linear", "Smooth", "Constant"];
+
+#[cfg(not(target_arch = "wasm32"))]
+const BAKE_SIZES_2D: [u32; 5] = [256, 512, 1024, 2048, 4096];
+#[cfg(not(target_arch = "wasm32"))]
+const BAKE_SIZES_3D: [u32; 4] = [32, 64, 128, 256];
+
 impl NoiseSetting {
     pub fn new() -> Self {
         let mut instance = Self {
             simu_ty: Some(0),
+            warp_strength: 1.0,
+            time_speed: 1.0,
+            height_scale: 0.2,
+            sea_level: 0.45,
+            density_scale: 4.0,
+            volume_steps: 96,
+            opacity_low: 0.3,
+            opacity_high: 0.8,
+            bake_dimension: 2,
+            bake_size: 1024,
+            bake_path: String::from("noise.png"),
             ..Default::default()
         };
         instance.ty_changed();
@@ -21,8 +84,101 @@ impl NoiseSetting {
         instance
     }
 
+    /// 切换材质类型，同时重置为该类型的预设参数
+    pub fn set_type(&mut self, ty: i32) {
+        self.simu_ty = Some(ty.clamp(0, GRAPH_MATERIAL));
+        self.ty_changed();
+    }
+
+    pub fn set_param(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "type" => self.set_type(parse_param(key, value)?),
+            "color0" => self.back_color = parse_color(key, value)?,
+            "color1" => self.front_color = parse_color(key, value)?,
+            "gradient" => self.color_stops = parse_color_stops(key, value)?,
+            "scale" => self.noise_scale = parse_param::<f32>(key, value)?.clamp(0.2, 14.0),
+            "octave" => self.octave = parse_param::<i32>(key, value)?.clamp(1, 40),
+            "lacunarity" => self.lacunarity = parse_param::<f32>(key, value)?.clamp(0.2, 8.4),
+            "gain" => self.gain = parse_param::<f32>(key, value)?.clamp(0.15, 1.0),
+            "seed" => self.seed = parse_param(key, value)?,
+            "speed" => self.time_speed = parse_param::<f32>(key, value)?.clamp(0.0, 4.0),
+            "paused" => self.paused = parse_param(key, value)?,
+            "view" => self.view = parse_param::<u32>(key, value)?.min(3),
+            "height_scale" => self.height_scale = parse_param::<f32>(key, value)?.clamp(0.0, 1.0),
+            "sea_level" => self.sea_level = parse_param::<f32>(key, value)?.clamp(0.0, 1.0),
+            "density" => self.density_scale = parse_param::<f32>(key, value)?.clamp(0.0, 20.0),
+            "steps" => self.volume_steps = parse_param::<u32>(key, value)?.clamp(16, 256),
+            "opacity_low" => self.opacity_low = parse_param::<f32>(key, value)?.clamp(0.0, 1.0),
+            "opacity_high" => self.opacity_high = parse_param::<f32>(key, value)?.clamp(0.0, 1.0),
+            "graph" => self.graph = MaterialGraph::from_param(value)?,
+            "basis" => self.basis = parse_param::<i32>(key, value)?.clamp(0, 4),
+            "warp_iterations" => self.warp_iterations = parse_param::<i32>(key, value)?.clamp(0, 3),
+            "warp_strength" => self.warp_strength = parse_param::<f32>(key, value)?.clamp(0.0, 4.0),
+            "bake_dimension" => {
+                self.set_bake_dimension(parse_param::<u32>(key, value)?.clamp(2, 3))
+            }
+            "bake_size" => {
+                let max_size = if self.bake_dimension == 3 { 256 } else { 4096 };
+                self.bake_size = parse_param::<u32>(key, value)?.clamp(16, max_size);
+            }
+            "tileable" => self.tileable = parse_param(key, value)?,
+            "bake_path" => self.bake_path = String::from(value.trim()),
+            // 以当前参数烘焙并导出到指定路径，供脚本使用
+            #[cfg(not(target_arch = "wasm32"))]
+            "bake" => {
+                self.bake_path = String::from(value.trim());
+                self.bake_requested = true;
+            }
+            _ => return Err(format!("unknown noise parameter `{key}`")),
+        }
+        Ok(())
+    }
+
+    /// 导出可由 `set_param` 还原的参数，`type` 需在最前面，因为它会重置其余参数
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(ty) = self.simu_ty {
+            params.push(("type", format!("{ty}")));
+        }
+        params.push(("color0", format_vec3(&self.back_color)));
+        params.push(("color1", format_vec3(&self.front_color)));
+        params.push(("gradient", format_color_stops(&self.color_stops)));
+        params.push(("scale", format!("{}", self.noise_scale)));
+        params.push(("octave", format!("{}", self.octave)));
+        params.push(("lacunarity", format!("{}", self.lacunarity)));
+        params.push(("gain", format!("{}", self.gain)));
+        params.push(("seed", format!("{}", self.seed)));
+        params.push(("speed", format!("{}", self.time_speed)));
+        params.push(("paused", format!("{}", self.paused)));
+        params.push(("view", format!("{}", self.view)));
+        params.push(("height_scale", format!("{}", self.height_scale)));
+        params.push(("sea_level", format!("{}", self.sea_level)));
+        params.push(("density", format!("{}", self.density_scale)));
+        params.push(("steps", format!("{}", self.volume_steps)));
+        params.push(("opacity_low", format!("{}", self.opacity_low)));
+        params.push(("opacity_high", format!("{}", self.opacity_high)));
+        params.push(("graph", self.graph.to_param()));
+        params.push(("basis", format!("{}", self.basis)));
+        params.push(("warp_iterations", format!("{}", self.warp_iterations)));
+        params.push(("warp_strength", format!("{}", self.warp_strength)));
+        params.push(("bake_dimension", format!("{}", self.bake_dimension)));
+        params.push(("bake_size", format!("{}", self.bake_size)));
+        params.push(("tileable", format!("{}", self.tileable)));
+        params.push(("bake_path", self.bake_path.clone()));
+        params
+    }
+
+    /// 切换维度时边长重置为该维度的默认值
+    fn set_bake_dimension(&mut self, dimension: u32) {
+        if dimension != self.bake_dimension {
+            self.bake_dimension = dimension;
+            self.bake_size = if dimension == 3 { 64 } else { 1024 };
+        }
+    }
+
     fn ty_changed(&mut self) {
         self.hide_gain = false;
+        self.color_stops.clear();
         match self.simu_ty {
             Some(1) => {
                 self.back_color = [0.69, 0.498, 0.361];
@@ -86,26 +242,122 @@ impl NoiseSetting {
             {
                 self.ty_changed();
             };
+            if ui
+                .selectable_value(&mut self.simu_ty, Some(GRAPH_MATERIAL), "Node graph")
+                .clicked()
+            {
+                self.ty_changed();
+                self.show_graph_editor = true;
+            };
         });
         ui.separator();
 
+        let is_graph = self.simu_ty == Some(GRAPH_MATERIAL);
+        if is_graph {
+            self.graph_ui(ui);
+        }
+
         egui::Grid::new("my_grid")
             .num_columns(2)
             .spacing([10.0, 12.0])
             .striped(true)
             .show(ui, |ui| {
-                ui.label("Color 0:");
-                ui.color_edit_button_rgb(&mut self.back_color);
+                ui.label("View:");
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.view, 0, "Sphere");
+                    ui.selectable_value(&mut self.view, 1, "Terrain");
+                    ui.selectable_value(&mut self.view, 2, "Planet");
+                    ui.selectable_value(&mut self.view, 3, "Volume");
+                });
                 ui.end_row();
 
-                ui.label("Color 1:");
-                ui.color_edit_button_rgb(&mut self.front_color);
+                if self.view == 3 {
+                    ui.label("Density:");
+                    ui.add(egui::Slider::new(&mut self.density_scale, 0.0..=20.0));
+                    ui.end_row();
+
+                    ui.label("Steps:");
+                    ui.add(egui::Slider::new(&mut self.volume_steps, 16..=256));
+                    ui.end_row();
+
+                    // 传递函数的不透明度随亮度增加的区间
+                    ui.label("Opacity:");
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::DragValue::new(&mut self.opacity_low)
+                                .range(0.0..=self.opacity_high)
+                                .speed(0.005),
+                        );
+                        ui.label("→");
+                        ui.add(
+                            egui::DragValue::new(&mut self.opacity_high)
+                                .range(self.opacity_low..=1.0)
+                                .speed(0.005),
+                        );
+                    });
+                    ui.end_row();
+                } else if self.view != 0 {
+                    ui.label("Height:");
+                    ui.add(egui::Slider::new(&mut self.height_scale, 0.0..=1.0));
+                    ui.end_row();
+
+                    ui.label("Sea level:");
+                    ui.add(egui::Slider::new(&mut self.sea_level, 0.0..=1.0));
+                    ui.end_row();
+                }
+
+                ui.label("Seed:");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut self.seed));
+                    if ui.button("Random").clicked() {
+                        self.seed = rand::random();
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Animation:");
+                ui.horizontal(|ui| {
+                    ui.add(egui::Slider::new(&mut self.time_speed, 0.0..=4.0).text("speed"));
+                    ui.toggle_value(&mut self.paused, "Pause");
+                });
                 ui.end_row();
 
                 ui.label("Noise scale:");
                 ui.add(egui::Slider::new(&mut self.noise_scale, 0.2..=14.0));
                 ui.end_row();
 
+                // 节点图中的噪声及颜色在各节点上设置
+                if is_graph {
+                    return;
+                }
+
+                ui.label("Basis:");
+                egui::ComboBox::from_id_salt("noise_basis")
+                    .selected_text(BASIS_NAMES[self.basis as usize])
+                    .show_ui(ui, |ui| {
+                        for (basis, name) in BASIS_NAMES.iter().enumerate() {
+                            ui.selectable_value(&mut self.basis, basis as i32, *name);
+                        }
+                    });
+                ui.end_row();
+
+                if self.color_stops.is_empty() {
+                    ui.label("Color 0:");
+                    ui.color_edit_button_rgb(&mut self.back_color);
+                    ui.end_row();
+
+                    ui.label("Color 1:");
+                    ui.color_edit_button_rgb(&mut self.front_color);
+                    ui.end_row();
+                }
+
+                // 只有 Marble 及 Wood 在两种颜色之间插值，地形及体积用渐变着色
+                if self.simu_ty == Some(0) || self.simu_ty == Some(1) || self.view != 0 {
+                    ui.label("Gradient:");
+                    self.gradient_ui(ui);
+                    ui.end_row();
+                }
+
                 if self.simu_ty == Some(0) || self.simu_ty == Some(1) {
                     ui.label("Octave:");
                     ui.add(egui::Slider::new(&mut self.octave, 1..=40));
@@ -121,6 +373,238 @@ impl NoiseSetting {
                     ui.add(egui::Slider::new(&mut self.gain, 0.15..=1.0));
                     ui.end_row();
                 }
+
+                ui.label("Domain warp:");
+                ui.add(egui::Slider::new(&mut self.warp_iterations, 0..=3).text("iterations"));
+                ui.end_row();
+
+                if self.warp_iterations > 0 {
+                    ui.label("");
+                    ui.add(egui::Slider::new(&mut self.warp_strength, 0.0..=4.0).text("strength"));
+                    ui.end_row();
+                }
+            });
+
+        // 只有原生平台能保存文件
+        #[cfg(not(target_arch = "wasm32"))]
+        self.bake_ui(ui);
+    }
+
+    /// 色标按位置排列，拖动时限制在相邻的两个色标之间
+    fn gradient_ui(&mut self, ui: &mut egui::Ui) {
+        ui.vertical(|ui| {
+            let mut removed = None;
+            let count = self.color_stops.len();
+            for i in 0..count {
+                let min = if i == 0 {
+                    0.0
+                } else {
+                    self.color_stops[i - 1].position
+                };
+                let max = if i + 1 == count {
+                    1.0
+                } else {
+                    self.color_stops[i + 1].position
+                };
+                let stop = &mut self.color_stops[i];
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut stop.position)
+                            .range(min..=max)
+                            .speed(0.005)
+                            .max_decimals(3),
+                    );
+                    ui.color_edit_button_rgb(&mut stop.color);
+                    if i + 1 < count {
+                        egui::ComboBox::from_id_salt(("stop_interpolation", i))
+                            .width(80.0)
+                            .selected_text(INTERPOLATION_NAMES[stop.interpolation as usize])
+                            .show_ui(ui, |ui| {
+                                for (mode, name) in INTERPOLATION_NAMES.iter().enumerate() {
+                                    ui.selectable_value(
+                                        &mut stop.interpolation,
+                                        mode as u32,
+                                        *name,
+                                    );
+                                }
+                            });
+                    }
+                    if ui.small_button("✖").clicked() {
+                        removed = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = removed {
+                self.remove_color_stop(i);
+            }
+            ui.add_enabled_ui(self.color_stops.len() < MAX_COLOR_STOPS, |ui| {
+                if ui.button("Add stop").clicked() {
+                    self.add_color_stop();
+                }
             });
+        });
+    }
+
+    /// 在最大的间隔中间插入色标，第一次添加时以 color0、color1 作为两端
+    fn add_color_stop(&mut self) {
+        if self.color_stops.is_empty() {
+            for (position, color) in [(0.0, self.back_color), (1.0, self.front_color)] {
+                self.color_stops.push(ColorStop {
+                    position,
+                    color,
+                    interpolation: 0,
+                });
+            }
+        }
+        let (index, _) = self
+            .color_stops
+            .windows(2)
+            .enumerate()
+            .map(|(i, pair)| (i, pair[1].position - pair[0].position))
+            .fold(
+                (0, f32::MIN),
+                |max, item| if item.1 > max.1 { item } else { max },
+            );
+        let (s0, s1) = (self.color_stops[index], self.color_stops[index + 1]);
+        self.color_stops.insert(
+            index + 1,
+            ColorStop {
+                position: (s0.position + s1.position) * 0.5,
+                color: core::array::from_fn(|c| (s0.color[c] + s1.color[c]) * 0.5),
+                interpolation: s0.interpolation,
+            },
+        );
     }
+
+    /// 少于两个色标时恢复为两种颜色，两端的颜色保留到 color0、color1
+    fn remove_color_stop(&mut self, index: usize) {
+        self.color_stops.remove(index);
+        if self.color_stops.len() < 2 {
+            if let (Some(first), Some(last)) = (self.color_stops.first(), self.color_stops.last()) {
+                self.back_color = first.color;
+                self.front_color = last.color;
+            }
+            self.color_stops.clear();
+        }
+    }
+
+    /// 节点编辑器在单独的窗口中显示
+    fn graph_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.toggle_value(&mut self.show_graph_editor, "Edit graph");
+            if ui.button("Reset graph").clicked() {
+                self.graph = MaterialGraph::default();
+            }
+        });
+        egui::CollapsingHeader::new("Generated WGSL")
+            .default_open(false)
+            .show(ui, |ui| {
+                egui::ScrollArea::both().max_height(200.0).show(ui, |ui| {
+                    crate::show_code(ui, &self.graph.to_wgsl());
+                });
+            });
+        ui.separator();
+
+        let graph = &mut self.graph;
+        egui::Window::new("Material graph")
+            .open(&mut self.show_graph_editor)
+            .default_size([640.0, 360.0])
+            .resizable(true)
+            .show(ui.ctx(), |ui| {
+                ui.label("Right click to add nodes, drag from an output port to an input port to connect");
+                graph.editor_ui(ui);
+            });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn bake_ui(&mut self, ui: &mut egui::Ui) {
+        ui.separator();
+        ui.heading("Bake");
+        egui::Grid::new("bake_grid")
+            .num_columns(2)
+            .spacing([10.0, 12.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Dimension:");
+                ui.horizontal(|ui| {
+                    let mut dimension = self.bake_dimension;
+                    ui.selectable_value(&mut dimension, 2, "2D");
+                    ui.selectable_value(&mut dimension, 3, "3D");
+                    self.set_bake_dimension(dimension);
+                });
+                ui.end_row();
+
+                ui.label("Size:");
+                let sizes: &[u32] = if self.bake_dimension == 3 {
+                    &BAKE_SIZES_3D
+                } else {
+                    &BAKE_SIZES_2D
+                };
+                egui::ComboBox::from_id_salt("bake_size")
+                    .selected_text(format!("{}", self.bake_size))
+                    .show_ui(ui, |ui| {
+                        for size in sizes {
+                            ui.selectable_value(&mut self.bake_size, *size, format!("{size}"));
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Tileable:");
+                ui.checkbox(&mut self.tileable, "");
+                ui.end_row();
+
+                ui.label("File:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.bake_path).hint_text("path/to/noise.png"),
+                );
+                ui.end_row();
+            });
+        if ui.button("Bake & export").clicked() {
+            self.bake_requested = true;
+        }
+        if !self.bake_status.is_empty() {
+            ui.label(&self.bake_status);
+        }
+    }
+}
+
+/// `位置:r,g,b:插值方式` 格式的色标，以 `;` 分隔
+fn parse_color_stops(key: &str, value: &str) -> Result<Vec<ColorStop>, String> {
+    let mut stops: Vec<ColorStop> = Vec::new();
+    for item in value.split(';').filter(|item| !item.trim().is_empty()) {
+        let mut fields = item.trim().split(':');
+        let (Some(position), Some(color), interpolation, None) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(format!("invalid color stop `{item}` for `{key}`"));
+        };
+        stops.push(ColorStop {
+            position: parse_param::<f32>(key, position)?.clamp(0.0, 1.0),
+            color: parse_color(key, color)?,
+            interpolation: match interpolation {
+                Some(mode) => parse_param::<u32>(key, mode)?.min(2),
+                None => 0,
+            },
+        });
+    }
+    if stops.len() == 1 || stops.len() > MAX_COLOR_STOPS {
+        return Err(format!("`{key}` needs 2 to {MAX_COLOR_STOPS} color stops"));
+    }
+    stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+    Ok(stops)
+}
+
+fn format_color_stops(stops: &[ColorStop]) -> String {
+    stops
+        .iter()
+        .map(|stop| {
+            format!(
+                "{}:{}:{}",
+                stop.position,
+                format_vec3(&stop.color),
+                stop.interpolation
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(";")
 }
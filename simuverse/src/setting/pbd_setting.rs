@@ -1,3 +1,13 @@
+use super::{format_vec3, parse_color, parse_param, parse_vec3};
+use crate::pbd::{
+    ClothFabricDesc, ClothMesh, ClothPinPattern, ClothTexture, FluidDesc, SoftBodyDesc,
+    SoftBodyShape, StrandsDesc,
+};
+use alloc::{format, string::String, vec, vec::Vec};
+
+// 布料纹理来源的数量，只有原生平台能读取图片文件
+const TEXTURE_NUM: u32 = if cfg!(target_arch = "wasm32") { 2 } else { 3 };
+
 pub struct PBDSetting {
     pub simu_ty: Option<i32>,
     pub damping: f32,
@@ -5,6 +15,73 @@ pub struct PBDSetting {
     pub compliance: f32,
     pub stiffness: f32,
     pub show_mesh: bool,
+    // 光源的方位角及仰角
+    pub light_azimuth: f32,
+    pub light_elevation: f32,
+    // 布料正反面的颜色，与纹理颜色相乘
+    pub front_color: [f32; 3],
+    pub back_color: [f32; 3],
+    // 布料网格的来源：0 矩形网格，1 CAD 模型，2 OBJ 文件
+    pub cloth_mesh: u32,
+    pub mesh_file: String,
+    // 编辑中的文件路径，确认后才更新 mesh_file
+    mesh_file_input: String,
+    // 布料纹理的来源：0 默认图片，1 噪声材质，2 图片文件
+    pub cloth_texture: u32,
+    pub texture_file: String,
+    texture_file_input: String,
+    // 矩形网格的粒子数
+    pub resolution_x: usize,
+    pub resolution_y: usize,
+    // 布料宽度与视口宽度之比
+    pub cloth_size: f32,
+    pub pin_pattern: u32,
+    // 自定义固定点的 uv 坐标
+    pub custom_pins: Vec<[f32; 2]>,
+    // 编辑自定义固定点时，模拟会暂停并恢复到静止状态
+    pub editing_pins: bool,
+    // 固定点在屏幕上的物理像素坐标，编辑时由模拟器更新
+    pub pin_markers: Vec<glam::Vec2>,
+    pub tearable: bool,
+    // 约束长度超过静止长度的此倍数时断开
+    pub tear_threshold: f32,
+    // 碰撞体，坐标为布料所在的模型空间
+    pub ground: bool,
+    pub ground_height: f32,
+    pub sphere: bool,
+    pub sphere_center: [f32; 3],
+    pub sphere_radius: f32,
+    pub box_collider: bool,
+    pub box_center: [f32; 3],
+    // 长方体的长宽高
+    pub box_size: [f32; 3],
+    pub self_collision: bool,
+    pub friction: f32,
+    // 风向，xz 平面上的角度
+    pub wind_direction: f32,
+    pub wind_strength: f32,
+    // 阵风强度，控制湍流的幅度
+    pub gustiness: f32,
+    // 软体的形状：0 长方体，1 球体，2 TetGen 文件
+    pub soft_body_shape: u32,
+    pub soft_body_resolution: usize,
+    pub soft_body_file: String,
+    soft_body_file_input: String,
+    // 边约束的柔度
+    pub softness: f32,
+    // 体积约束的 XPBD 柔度，0 表示体积不可压缩
+    pub volume_compliance: f32,
+    // 发丝的数量及长度
+    pub strand_count: usize,
+    pub strand_length: f32,
+    // 螺旋形静止形状的卷曲程度，0 为直发
+    pub strand_curl: f32,
+    pub strand_stiffness: f32,
+    pub fluid_particle_count: usize,
+    // XSPH 粘性系数
+    pub viscosity: f32,
+    // 涡度约束强度
+    pub vorticity: f32,
 }
 
 impl Default for PBDSetting {
@@ -22,10 +99,304 @@ impl PBDSetting {
             compliance: 0.001,
             stiffness: 0.05,
             show_mesh: false,
+            light_azimuth: 30.0,
+            light_elevation: 40.0,
+            front_color: [1.0; 3],
+            back_color: [0.6, 0.65, 0.75],
+            cloth_mesh: 0,
+            mesh_file: String::new(),
+            mesh_file_input: String::new(),
+            cloth_texture: 0,
+            texture_file: String::new(),
+            texture_file_input: String::new(),
+            resolution_x: 50,
+            resolution_y: 50,
+            cloth_size: 1.0,
+            pin_pattern: ClothPinPattern::TopEdge as u32,
+            custom_pins: vec![],
+            editing_pins: false,
+            pin_markers: vec![],
+            tearable: false,
+            tear_threshold: 1.6,
+            ground: false,
+            ground_height: -0.9,
+            sphere: false,
+            sphere_center: [0.0, -0.3, 0.35],
+            sphere_radius: 0.3,
+            box_collider: false,
+            box_center: [0.0, -0.6, 0.35],
+            box_size: [0.8, 0.4, 0.6],
+            self_collision: false,
+            friction: 0.3,
+            wind_direction: 90.0,
+            wind_strength: 0.0,
+            gustiness: 0.3,
+            soft_body_shape: 0,
+            soft_body_resolution: 8,
+            soft_body_file: String::new(),
+            soft_body_file_input: String::new(),
+            softness: 0.3,
+            volume_compliance: 0.0,
+            strand_count: 32,
+            strand_length: 0.8,
+            strand_curl: 0.0,
+            strand_stiffness: 0.5,
+            fluid_particle_count: 16384,
+            viscosity: 0.05,
+            vorticity: 0.3,
+        }
+    }
+
+    /// `cad_model_file` 是 CAD 面板中加载的模型文件
+    pub fn fabric_desc(&self, cad_model_file: &str) -> ClothFabricDesc {
+        let mesh = match self.cloth_mesh {
+            1 => ClothMesh::CADModel(String::from(cad_model_file)),
+            2 if !self.mesh_file.is_empty() => ClothMesh::File(self.mesh_file.clone()),
+            _ => ClothMesh::Grid,
+        };
+        ClothFabricDesc {
+            mesh,
+            horizontal_num: self.resolution_x,
+            vertical_num: self.resolution_y,
+            size: self.cloth_size,
+            pin_pattern: ClothPinPattern::from_u32(self.pin_pattern),
+            custom_pins: self.custom_pins.clone(),
+        }
+    }
+
+    pub fn texture_desc(&self) -> ClothTexture {
+        match self.cloth_texture {
+            1 => ClothTexture::Noise,
+            2 if !self.texture_file.is_empty() => ClothTexture::File(self.texture_file.clone()),
+            _ => ClothTexture::Default,
+        }
+    }
+
+    /// 添加自定义固定点，点击已有的固定点则将其移除
+    pub fn toggle_custom_pin(&mut self, uv: glam::Vec2) {
+        let grid = glam::Vec2::new(
+            (self.resolution_x - 1) as f32,
+            (self.resolution_y - 1) as f32,
+        );
+        let existing = self
+            .custom_pins
+            .iter()
+            .position(|pin| ((glam::Vec2::from(*pin) - uv) * grid).length() < 1.0);
+        match existing {
+            Some(index) => {
+                self.custom_pins.remove(index);
+            }
+            None => self.custom_pins.push(uv.into()),
         }
     }
 
-    fn ty_changed(&self) {}
+    pub fn soft_body_desc(&self) -> SoftBodyDesc {
+        let shape = match self.soft_body_shape {
+            1 => SoftBodyShape::Sphere,
+            2 if !self.soft_body_file.is_empty() => {
+                SoftBodyShape::TetGen(self.soft_body_file.clone())
+            }
+            _ => SoftBodyShape::Box,
+        };
+        SoftBodyDesc {
+            shape,
+            resolution: self.soft_body_resolution,
+        }
+    }
+
+    pub fn strands_desc(&self) -> StrandsDesc {
+        StrandsDesc {
+            count: self.strand_count,
+            length: self.strand_length,
+            curl: self.strand_curl,
+        }
+    }
+
+    pub fn fluid_desc(&self) -> FluidDesc {
+        FluidDesc {
+            particle_count: self.fluid_particle_count,
+        }
+    }
+
+    pub fn is_cloth(&self) -> bool {
+        self.simu_ty.unwrap_or(0) == 0
+    }
+
+    pub fn is_soft_body(&self) -> bool {
+        self.simu_ty == Some(1)
+    }
+
+    /// 切换模拟类型，同时重置为该类型的预设参数
+    pub fn set_type(&mut self, ty: i32) {
+        self.simu_ty = Some(ty.clamp(0, 3));
+        self.ty_changed();
+    }
+
+    fn ty_changed(&mut self) {
+        // 软体没有固定点，需要落在地面上
+        if self.is_soft_body() {
+            self.ground = true;
+        }
+    }
+
+    pub fn set_param(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "type" => self.set_type(parse_param(key, value)?),
+            "damping" => self.damping = parse_param::<f32>(key, value)?.clamp(0.3, 1.0),
+            "gravity" => self.gravity = parse_param::<f32>(key, value)?.clamp(0.1, 1.0),
+            "compliance" => self.compliance = parse_param::<f32>(key, value)?.clamp(0.00001, 0.2),
+            "stiffness" => self.stiffness = parse_param::<f32>(key, value)?.clamp(0.01, 0.99),
+            "show_mesh" => self.show_mesh = parse_param(key, value)?,
+            "light_azimuth" => {
+                self.light_azimuth = parse_param::<f32>(key, value)?.rem_euclid(360.0)
+            }
+            "light_elevation" => {
+                self.light_elevation = parse_param::<f32>(key, value)?.clamp(-90.0, 90.0)
+            }
+            "front_color" => self.front_color = parse_color(key, value)?,
+            "back_color" => self.back_color = parse_color(key, value)?,
+            "mesh" => self.cloth_mesh = parse_param::<u32>(key, value)?.min(2),
+            "mesh_file" => {
+                self.mesh_file = String::from(value.trim());
+                self.mesh_file_input = self.mesh_file.clone();
+            }
+            "texture" => self.cloth_texture = parse_param::<u32>(key, value)?.min(TEXTURE_NUM - 1),
+            "texture_file" => {
+                self.texture_file = String::from(value.trim());
+                self.texture_file_input = self.texture_file.clone();
+            }
+            "resolution_x" => self.resolution_x = parse_param::<usize>(key, value)?.clamp(10, 120),
+            "resolution_y" => self.resolution_y = parse_param::<usize>(key, value)?.clamp(10, 120),
+            "size" => self.cloth_size = parse_param::<f32>(key, value)?.clamp(0.3, 1.5),
+            "pin" => self.pin_pattern = parse_param::<u32>(key, value)?.min(4),
+            // `u,v;u,v` 格式
+            "custom_pins" => {
+                let mut pins = vec![];
+                for pin in value.split(';').filter(|pin| !pin.is_empty()) {
+                    let (u, v) = pin
+                        .split_once(',')
+                        .ok_or_else(|| format!("invalid value `{value}` for `{key}`"))?;
+                    pins.push([
+                        parse_param::<f32>(key, u)?.clamp(0.0, 1.0),
+                        parse_param::<f32>(key, v)?.clamp(0.0, 1.0),
+                    ]);
+                }
+                self.custom_pins = pins;
+            }
+            "tearable" => self.tearable = parse_param(key, value)?,
+            "tear_threshold" => {
+                self.tear_threshold = parse_param::<f32>(key, value)?.clamp(1.1, 3.0)
+            }
+            "ground" => self.ground = parse_param(key, value)?,
+            "ground_height" => {
+                self.ground_height = parse_param::<f32>(key, value)?.clamp(-1.5, 0.5)
+            }
+            "sphere" => self.sphere = parse_param(key, value)?,
+            "sphere_center" => {
+                self.sphere_center = parse_vec3(key, value)?.map(|v| v.clamp(-2.0, 2.0))
+            }
+            "sphere_radius" => {
+                self.sphere_radius = parse_param::<f32>(key, value)?.clamp(0.05, 0.8)
+            }
+            "box" => self.box_collider = parse_param(key, value)?,
+            "box_center" => self.box_center = parse_vec3(key, value)?.map(|v| v.clamp(-2.0, 2.0)),
+            "box_size" => self.box_size = parse_vec3(key, value)?.map(|v| v.clamp(0.05, 2.0)),
+            "self_collision" => self.self_collision = parse_param(key, value)?,
+            "friction" => self.friction = parse_param::<f32>(key, value)?.clamp(0.0, 1.0),
+            "wind_direction" => {
+                self.wind_direction = parse_param::<f32>(key, value)?.rem_euclid(360.0)
+            }
+            "wind_strength" => self.wind_strength = parse_param::<f32>(key, value)?.clamp(0.0, 1.0),
+            "gustiness" => self.gustiness = parse_param::<f32>(key, value)?.clamp(0.0, 1.0),
+            "soft_body_shape" => self.soft_body_shape = parse_param::<u32>(key, value)?.min(2),
+            "soft_body_resolution" => {
+                self.soft_body_resolution = parse_param::<usize>(key, value)?.clamp(3, 16)
+            }
+            "soft_body_file" => {
+                self.soft_body_file = String::from(value.trim());
+                self.soft_body_file_input = self.soft_body_file.clone();
+            }
+            "softness" => self.softness = parse_param::<f32>(key, value)?.clamp(0.0, 1.0),
+            "volume_compliance" => {
+                self.volume_compliance = parse_param::<f32>(key, value)?.clamp(0.0, 1.0)
+            }
+            "strand_count" => self.strand_count = parse_param::<usize>(key, value)?.clamp(1, 128),
+            "strand_length" => self.strand_length = parse_param::<f32>(key, value)?.clamp(0.2, 1.5),
+            "strand_curl" => self.strand_curl = parse_param::<f32>(key, value)?.clamp(0.0, 1.0),
+            "strand_stiffness" => {
+                self.strand_stiffness = parse_param::<f32>(key, value)?.clamp(0.0, 1.0)
+            }
+            "fluid_particles" => {
+                self.fluid_particle_count = parse_param::<usize>(key, value)?.clamp(1024, 65536)
+            }
+            "viscosity" => self.viscosity = parse_param::<f32>(key, value)?.clamp(0.0, 0.5),
+            "vorticity" => self.vorticity = parse_param::<f32>(key, value)?.clamp(0.0, 1.0),
+            _ => return Err(format!("unknown pbd parameter `{key}`")),
+        }
+        Ok(())
+    }
+
+    /// `type` 需在最前面，因为它会重置部分参数
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("type", format!("{}", self.simu_ty.unwrap_or(0))),
+            ("damping", format!("{}", self.damping)),
+            ("gravity", format!("{}", self.gravity)),
+            ("compliance", format!("{}", self.compliance)),
+            ("stiffness", format!("{}", self.stiffness)),
+            ("show_mesh", format!("{}", self.show_mesh)),
+            ("light_azimuth", format!("{}", self.light_azimuth)),
+            ("light_elevation", format!("{}", self.light_elevation)),
+            ("front_color", format_vec3(&self.front_color)),
+            ("back_color", format_vec3(&self.back_color)),
+            ("mesh", format!("{}", self.cloth_mesh)),
+            ("mesh_file", self.mesh_file.clone()),
+            ("texture", format!("{}", self.cloth_texture)),
+            ("texture_file", self.texture_file.clone()),
+            ("resolution_x", format!("{}", self.resolution_x)),
+            ("resolution_y", format!("{}", self.resolution_y)),
+            ("size", format!("{}", self.cloth_size)),
+            ("pin", format!("{}", self.pin_pattern)),
+            (
+                "custom_pins",
+                self.custom_pins
+                    .iter()
+                    .map(|pin| format!("{},{}", pin[0], pin[1]))
+                    .collect::<Vec<_>>()
+                    .join(";"),
+            ),
+            ("tearable", format!("{}", self.tearable)),
+            ("tear_threshold", format!("{}", self.tear_threshold)),
+            ("ground", format!("{}", self.ground)),
+            ("ground_height", format!("{}", self.ground_height)),
+            ("sphere", format!("{}", self.sphere)),
+            ("sphere_center", format_vec3(&self.sphere_center)),
+            ("sphere_radius", format!("{}", self.sphere_radius)),
+            ("box", format!("{}", self.box_collider)),
+            ("box_center", format_vec3(&self.box_center)),
+            ("box_size", format_vec3(&self.box_size)),
+            ("self_collision", format!("{}", self.self_collision)),
+            ("friction", format!("{}", self.friction)),
+            ("wind_direction", format!("{}", self.wind_direction)),
+            ("wind_strength", format!("{}", self.wind_strength)),
+            ("gustiness", format!("{}", self.gustiness)),
+            ("soft_body_shape", format!("{}", self.soft_body_shape)),
+            (
+                "soft_body_resolution",
+                format!("{}", self.soft_body_resolution),
+            ),
+            ("soft_body_file", self.soft_body_file.clone()),
+            ("softness", format!("{}", self.softness)),
+            ("volume_compliance", format!("{}", self.volume_compliance)),
+            ("strand_count", format!("{}", self.strand_count)),
+            ("strand_length", format!("{}", self.strand_length)),
+            ("strand_curl", format!("{}", self.strand_curl)),
+            ("strand_stiffness", format!("{}", self.strand_stiffness)),
+            ("fluid_particles", format!("{}", self.fluid_particle_count)),
+            ("viscosity", format!("{}", self.viscosity)),
+            ("vorticity", format!("{}", self.vorticity)),
+        ]
+    }
 
     pub fn ui_contents(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
@@ -33,12 +404,23 @@ impl PBDSetting {
             if ui
                 .selectable_value(&mut self.simu_ty, Some(0), "Cloth")
                 .clicked()
+                || ui
+                    .selectable_value(&mut self.simu_ty, Some(1), "Soft body")
+                    .clicked()
+                || ui
+                    .selectable_value(&mut self.simu_ty, Some(2), "Strands")
+                    .clicked()
+                || ui
+                    .selectable_value(&mut self.simu_ty, Some(3), "Fluid")
+                    .clicked()
             {
                 self.ty_changed();
             };
         });
         ui.separator();
 
+        let is_cloth = self.is_cloth();
+
         egui::Grid::new("my_grid")
             .num_columns(2)
             .spacing([10.0, 12.0])
@@ -52,6 +434,9 @@ impl PBDSetting {
                 ui.add(egui::Slider::new(&mut self.gravity, 0.1..=1.0));
                 ui.end_row();
 
+                if !is_cloth {
+                    return;
+                }
                 ui.label("Compliance:");
                 ui.add(egui::Slider::new(&mut self.compliance, 0.00001..=0.2));
                 ui.end_row();
@@ -60,5 +445,356 @@ impl PBDSetting {
                 ui.add(egui::Slider::new(&mut self.stiffness, 0.01..=0.99));
                 ui.end_row();
             });
+
+        if !is_cloth {
+            self.editing_pins = false;
+            match self.simu_ty {
+                Some(1) => self.soft_body_ui(ui),
+                Some(2) => self.strands_ui(ui),
+                // 流体只与容器碰撞
+                _ => {
+                    self.fluid_ui(ui);
+                    return;
+                }
+            }
+            self.collision_ui(ui);
+            return;
+        }
+
+        ui.separator();
+        ui.heading("Fabric");
+        egui::Grid::new("fabric_grid")
+            .num_columns(2)
+            .spacing([10.0, 12.0])
+            .striped(true)
+            .show(ui, |ui| {
+                // 只有原生平台能加载 OBJ 网格
+                #[cfg(not(target_arch = "wasm32"))]
+                self.mesh_ui(ui);
+
+                let is_grid = self.cloth_mesh == 0;
+                ui.label("Resolution X:");
+                ui.add_enabled(is_grid, egui::Slider::new(&mut self.resolution_x, 10..=120));
+                ui.end_row();
+
+                ui.label("Resolution Y:");
+                ui.add_enabled(is_grid, egui::Slider::new(&mut self.resolution_y, 10..=120));
+                ui.end_row();
+
+                ui.label("Size:");
+                ui.add(egui::Slider::new(&mut self.cloth_size, 0.3..=1.5));
+                ui.end_row();
+
+                self.texture_ui(ui);
+
+                ui.label("Pin:");
+                egui::ComboBox::from_id_salt("pin_pattern")
+                    .selected_text(ClothPinPattern::from_u32(self.pin_pattern).name())
+                    .show_ui(ui, |ui| {
+                        for ty in 0..5 {
+                            ui.selectable_value(
+                                &mut self.pin_pattern,
+                                ty,
+                                ClothPinPattern::from_u32(ty).name(),
+                            );
+                        }
+                    });
+                ui.end_row();
+
+                ui.checkbox(&mut self.tearable, "Tearable");
+                ui.add_enabled(
+                    self.tearable,
+                    egui::Slider::new(&mut self.tear_threshold, 1.1..=3.0).text("threshold"),
+                );
+                ui.end_row();
+            });
+
+        ui.separator();
+        ui.heading("Shading");
+        egui::Grid::new("shading_grid")
+            .num_columns(2)
+            .spacing([10.0, 12.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Light azimuth:");
+                ui.add(egui::Slider::new(&mut self.light_azimuth, 0.0..=360.0).suffix("°"));
+                ui.end_row();
+
+                ui.label("Light elevation:");
+                ui.add(egui::Slider::new(&mut self.light_elevation, -90.0..=90.0).suffix("°"));
+                ui.end_row();
+
+                ui.label("Front color:");
+                ui.color_edit_button_rgb(&mut self.front_color);
+                ui.end_row();
+
+                ui.label("Back color:");
+                ui.color_edit_button_rgb(&mut self.back_color);
+                ui.end_row();
+
+                ui.checkbox(&mut self.show_mesh, "Show mesh");
+                ui.end_row();
+            });
+
+        ui.separator();
+        ui.heading("Wind");
+        egui::Grid::new("wind_grid")
+            .num_columns(2)
+            .spacing([10.0, 12.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Direction:");
+                ui.add(egui::Slider::new(&mut self.wind_direction, 0.0..=360.0).suffix("°"));
+                ui.end_row();
+
+                ui.label("Strength:");
+                ui.add(egui::Slider::new(&mut self.wind_strength, 0.0..=1.0));
+                ui.end_row();
+
+                ui.label("Gustiness:");
+                ui.add(egui::Slider::new(&mut self.gustiness, 0.0..=1.0));
+                ui.end_row();
+            });
+
+        self.collision_ui(ui);
+
+        if ClothPinPattern::from_u32(self.pin_pattern) != ClothPinPattern::Custom {
+            self.editing_pins = false;
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.editing_pins, "Edit pins");
+            if ui.button("Clear pins").clicked() {
+                self.custom_pins.clear();
+            }
+        });
+        if self.editing_pins {
+            ui.label(
+                "Click the cloth to add or remove a pin, the simulation is paused while editing",
+            );
+            self.draw_pin_markers(ui.ctx());
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn mesh_ui(&mut self, ui: &mut egui::Ui) {
+        let names = ["Grid", "CAD model", "OBJ file"];
+        ui.label("Mesh:");
+        egui::ComboBox::from_id_salt("cloth_mesh")
+            .selected_text(names[self.cloth_mesh as usize])
+            .show_ui(ui, |ui| {
+                for (ty, name) in names.iter().enumerate() {
+                    ui.selectable_value(&mut self.cloth_mesh, ty as u32, *name);
+                }
+            });
+        ui.end_row();
+
+        if self.cloth_mesh == 2 {
+            ui.label("File:");
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.mesh_file_input).hint_text("path/to/mesh.obj"),
+            );
+            if response.lost_focus() {
+                self.mesh_file = String::from(self.mesh_file_input.trim());
+            }
+            ui.end_row();
+        }
     }
+
+    fn texture_ui(&mut self, ui: &mut egui::Ui) {
+        let names = ["Image", "Noise material", "Image file"];
+        ui.label("Texture:");
+        egui::ComboBox::from_id_salt("cloth_texture")
+            .selected_text(names[self.cloth_texture as usize])
+            .show_ui(ui, |ui| {
+                for (ty, name) in names.iter().enumerate().take(TEXTURE_NUM as usize) {
+                    ui.selectable_value(&mut self.cloth_texture, ty as u32, *name);
+                }
+            });
+        ui.end_row();
+
+        if self.cloth_texture == 2 {
+            ui.label("File:");
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.texture_file_input)
+                    .hint_text("path/to/image.png"),
+            );
+            if response.lost_focus() {
+                self.texture_file = String::from(self.texture_file_input.trim());
+            }
+            ui.end_row();
+        }
+    }
+
+    fn soft_body_ui(&mut self, ui: &mut egui::Ui) {
+        ui.separator();
+        ui.heading("Soft body");
+        egui::Grid::new("soft_body_grid")
+            .num_columns(2)
+            .spacing([10.0, 12.0])
+            .striped(true)
+            .show(ui, |ui| {
+                let names = ["Box", "Sphere", "TetGen file"];
+                // 只有原生平台能读取 TetGen 文件
+                let shape_num = if cfg!(target_arch = "wasm32") { 2 } else { 3 };
+                ui.label("Shape:");
+                egui::ComboBox::from_id_salt("soft_body_shape")
+                    .selected_text(names[self.soft_body_shape as usize])
+                    .show_ui(ui, |ui| {
+                        for (ty, name) in names.iter().enumerate().take(shape_num) {
+                            ui.selectable_value(&mut self.soft_body_shape, ty as u32, *name);
+                        }
+                    });
+                ui.end_row();
+
+                if self.soft_body_shape == 2 {
+                    ui.label("File:");
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.soft_body_file_input)
+                            .hint_text("path/to/mesh.node"),
+                    );
+                    if response.lost_focus() {
+                        self.soft_body_file = String::from(self.soft_body_file_input.trim());
+                    }
+                    ui.end_row();
+                } else {
+                    ui.label("Resolution:");
+                    ui.add(egui::Slider::new(&mut self.soft_body_resolution, 3..=16));
+                    ui.end_row();
+                }
+
+                ui.label("Softness:");
+                ui.add(egui::Slider::new(&mut self.softness, 0.0..=1.0));
+                ui.end_row();
+
+                ui.label("Volume compliance:");
+                ui.add(egui::Slider::new(&mut self.volume_compliance, 0.0..=1.0));
+                ui.end_row();
+            });
+    }
+
+    fn strands_ui(&mut self, ui: &mut egui::Ui) {
+        ui.separator();
+        ui.heading("Strands");
+        egui::Grid::new("strands_grid")
+            .num_columns(2)
+            .spacing([10.0, 12.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Count:");
+                ui.add(egui::Slider::new(&mut self.strand_count, 1..=128));
+                ui.end_row();
+
+                ui.label("Length:");
+                ui.add(egui::Slider::new(&mut self.strand_length, 0.2..=1.5));
+                ui.end_row();
+
+                ui.label("Curl:");
+                ui.add(egui::Slider::new(&mut self.strand_curl, 0.0..=1.0));
+                ui.end_row();
+
+                ui.label("Stiffness:");
+                ui.add(egui::Slider::new(&mut self.strand_stiffness, 0.0..=1.0));
+                ui.end_row();
+            });
+    }
+
+    fn fluid_ui(&mut self, ui: &mut egui::Ui) {
+        ui.separator();
+        ui.heading("Fluid");
+        egui::Grid::new("fluid_grid")
+            .num_columns(2)
+            .spacing([10.0, 12.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Particles:");
+                ui.add(egui::Slider::new(
+                    &mut self.fluid_particle_count,
+                    1024..=65536,
+                ));
+                ui.end_row();
+
+                ui.label("Viscosity:");
+                ui.add(egui::Slider::new(&mut self.viscosity, 0.0..=0.5));
+                ui.end_row();
+
+                ui.label("Vorticity:");
+                ui.add(egui::Slider::new(&mut self.vorticity, 0.0..=1.0));
+                ui.end_row();
+            });
+    }
+
+    fn collision_ui(&mut self, ui: &mut egui::Ui) {
+        ui.separator();
+        ui.heading("Collision");
+        egui::Grid::new("collision_grid")
+            .num_columns(2)
+            .spacing([10.0, 12.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.checkbox(&mut self.ground, "Ground");
+                ui.add_enabled(
+                    self.ground,
+                    egui::Slider::new(&mut self.ground_height, -1.5..=0.5).text("height"),
+                );
+                ui.end_row();
+
+                ui.checkbox(&mut self.sphere, "Sphere");
+                ui.add_enabled(
+                    self.sphere,
+                    egui::Slider::new(&mut self.sphere_radius, 0.05..=0.8).text("radius"),
+                );
+                ui.end_row();
+                if self.sphere {
+                    ui.label("Center:");
+                    vec3_drag_values(ui, &mut self.sphere_center, -2.0..=2.0);
+                    ui.end_row();
+                }
+
+                ui.checkbox(&mut self.box_collider, "Box");
+                ui.end_row();
+                if self.box_collider {
+                    ui.label("Center:");
+                    vec3_drag_values(ui, &mut self.box_center, -2.0..=2.0);
+                    ui.end_row();
+                    ui.label("Size:");
+                    vec3_drag_values(ui, &mut self.box_size, 0.05..=2.0);
+                    ui.end_row();
+                }
+
+                if self.is_cloth() {
+                    ui.checkbox(&mut self.self_collision, "Self collision");
+                    ui.end_row();
+                }
+
+                ui.label("Friction:");
+                ui.add(egui::Slider::new(&mut self.friction, 0.0..=1.0));
+                ui.end_row();
+            });
+    }
+
+    fn draw_pin_markers(&self, ctx: &egui::Context) {
+        let painter = ctx.layer_painter(egui::LayerId::new(
+            egui::Order::Background,
+            egui::Id::new("cloth_pin_markers"),
+        ));
+        let pixels_per_point = ctx.pixels_per_point();
+        for marker in self.pin_markers.iter() {
+            let pos = egui::pos2(marker.x, marker.y) / pixels_per_point;
+            painter.circle_filled(pos, 4.0, egui::Color32::from_rgb(235, 80, 80));
+        }
+    }
+}
+
+fn vec3_drag_values(ui: &mut egui::Ui, v: &mut [f32; 3], range: core::ops::RangeInclusive<f32>) {
+    ui.horizontal(|ui| {
+        for (c, prefix) in v.iter_mut().zip(["x: ", "y: ", "z: "]) {
+            ui.add(
+                egui::DragValue::new(c)
+                    .speed(0.01)
+                    .range(range.clone())
+                    .prefix(prefix),
+            );
+        }
+    });
 }
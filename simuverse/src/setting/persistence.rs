@@ -0,0 +1,78 @@
+//! 控制面板设置的持久化
+//!
+//! 参数以 `key value` 的文本行保存（与 `ControlPanel::set_param` 的参数名一致），自定义的 WGSL 代码片段单独保存；
+//! native 端保存在用户配置目录下的 `simuverse` 文件夹中，web 端保存在 localStorage 中。
+
+use alloc::string::String;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod storage {
+    use alloc::string::String;
+    use std::path::PathBuf;
+
+    fn config_dir() -> Option<PathBuf> {
+        let dir = if cfg!(target_os = "windows") {
+            std::env::var_os("APPDATA").map(PathBuf::from)
+        } else if cfg!(target_os = "macos") {
+            std::env::var_os("HOME")
+                .map(|home| PathBuf::from(home).join("Library/Application Support"))
+        } else {
+            std::env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .or_else(|| {
+                    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config"))
+                })
+        };
+        dir.map(|dir| dir.join("simuverse"))
+    }
+
+    pub fn get(name: &str) -> Option<String> {
+        std::fs::read_to_string(config_dir()?.join(name)).ok()
+    }
+
+    pub fn set(name: &str, content: &str) {
+        let Some(dir) = config_dir() else {
+            return;
+        };
+        let res =
+            std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(dir.join(name), content));
+        if let Err(e) = res {
+            log::warn!("failed to save {name}: {e}");
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod storage {
+    use alloc::{format, string::String};
+
+    fn local_storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    pub fn get(name: &str) -> Option<String> {
+        local_storage()?
+            .get_item(&format!("simuverse/{name}"))
+            .ok()?
+    }
+
+    pub fn set(name: &str, content: &str) {
+        if let Some(storage) = local_storage() {
+            let _ = storage.set_item(&format!("simuverse/{name}"), content);
+        }
+    }
+}
+
+const SETTINGS_NAME: &str = "settings.txt";
+const WGSL_NAME: &str = "velocity_snippet.wgsl";
+
+/// 读取上次保存的 (参数, WGSL 代码片段)
+pub(crate) fn load() -> Option<(String, Option<String>)> {
+    let params = storage::get(SETTINGS_NAME)?;
+    Some((params, storage::get(WGSL_NAME)))
+}
+
+pub(crate) fn save(params: &str, wgsl_code: &str) {
+    storage::set(SETTINGS_NAME, params);
+    storage::set(WGSL_NAME, wgsl_code);
+}
@@ -0,0 +1,148 @@
+//! web 端的分享链接
+//!
+//! 链接的 query 或 hash 部分为 `key=value&key=value` 格式，参数名与 `ControlPanel::set_param` 一致，
+//! 如 `#simu=noise&preset=2&noise.scale=1.5`，自定义的 WGSL 代码片段保存在 `field.wgsl` 中。
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// 读取当前页面 URL 的 query 与 hash 中的参数，hash 中的同名参数优先
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn params_from_url() -> Vec<(String, String)> {
+    let Some(location) = web_sys::window().map(|win| win.location()) else {
+        return Vec::new();
+    };
+    let mut params = Vec::new();
+    for part in [location.search(), location.hash()] {
+        let Ok(part) = part else {
+            continue;
+        };
+        params.extend(decode(part.trim_start_matches(['?', '#'])));
+    }
+    params
+}
+
+/// 生成指向当前页面、带有给定参数的链接
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn share_link(params: &[(String, String)]) -> Option<String> {
+    let location = web_sys::window()?.location();
+    let base = format!("{}{}", location.origin().ok()?, location.pathname().ok()?);
+    Some(format!("{base}#{}", encode(params)))
+}
+
+/// 复制到剪贴板
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn copy_to_clipboard(text: &str) {
+    if let Some(win) = web_sys::window() {
+        let _ = win.navigator().clipboard().write_text(text);
+    }
+}
+
+/// 把参数编码为 `key=value&key=value`
+fn encode(params: &[(String, String)]) -> String {
+    let pairs: Vec<String> = params
+        .iter()
+        .map(|(key, value)| format!("{}={}", percent_encode(key), percent_encode(value)))
+        .collect();
+    pairs.join("&")
+}
+
+/// 解析 `key=value&key=value`，保留参数的先后顺序，同名参数由后出现的覆盖；
+/// 跳过没有 `=` 或参数名为空的项
+fn decode(query: &str) -> Vec<(String, String)> {
+    // 无法解码的部分保留原样
+    let decode_component = |s: &str| percent_decode(s).unwrap_or_else(|| s.to_string());
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .filter(|(key, _)| !key.is_empty())
+        .map(|(key, value)| (decode_component(key), decode_component(value)))
+        .collect()
+}
+
+/// 与 JS 的 `encodeURIComponent` 一致：除字母、数字及 `-_.!~*'()` 外的字节都编码为 `%XX`
+fn percent_encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.!~*'()".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// `%XX` 不完整或解码结果不是 UTF-8 时返回 None
+fn percent_decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut iter = s.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            let hex = core::str::from_utf8(&hex).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(key: &str, value: &str) -> (String, String) {
+        (String::from(key), String::from(value))
+    }
+
+    #[test]
+    fn encode_round_trip() {
+        let params = alloc::vec![
+            pair("simu", "noise"),
+            pair("noise.gradient", "0:1,0,0;1:0,0,1"),
+            pair(
+                "field.wgsl",
+                "let a = b & c;\nreturn a == 1 ? x : y; // 注释 100%"
+            ),
+            pair("k=ey&", "a=b&c=d"),
+            pair("empty", ""),
+        ];
+        let encoded = encode(&params);
+        assert_eq!(encoded.matches('&').count(), params.len() - 1);
+        assert_eq!(encoded.matches('=').count(), params.len());
+        assert_eq!(decode(&encoded), params);
+    }
+
+    #[test]
+    fn encode_matches_encode_uri_component() {
+        assert_eq!(percent_encode("a-b_c.d!e~f*g'h(i)j"), "a-b_c.d!e~f*g'h(i)j");
+        assert_eq!(percent_encode("a b&c=d/e#f+g"), "a%20b%26c%3Dd%2Fe%23f%2Bg");
+        assert_eq!(percent_encode("é"), "%C3%A9");
+    }
+
+    #[test]
+    fn decode_repeated_and_malformed_keys() {
+        // 同名参数都保留，应用时后出现的覆盖之前的
+        assert_eq!(
+            decode("scale=1&scale=2"),
+            alloc::vec![pair("scale", "1"), pair("scale", "2")]
+        );
+        // 没有 `=` 或参数名为空的项被跳过，值中的 `=` 属于值
+        assert_eq!(
+            decode("&flag&=1&a==b&b=c=d&"),
+            alloc::vec![pair("a", "=b"), pair("b", "c=d")]
+        );
+        // 无效的转义保留原样
+        assert_eq!(
+            decode("a%zz=1&b=%E4%B8&c=50%"),
+            alloc::vec![pair("a%zz", "1"), pair("b", "%E4%B8"), pair("c", "50%")]
+        );
+        assert_eq!(decode("name=%e4%B8%AD"), alloc::vec![pair("name", "中")]);
+        assert!(decode("").is_empty());
+    }
+}
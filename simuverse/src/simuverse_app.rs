@@ -19,7 +19,12 @@ pub struct SimuverseApp {
     canvas_buf: BufferObj,
     simulator: Box<dyn Simulator>,
     depth_view: TextureView,
-    cloth_texture: Option<AnyTexture>,
+    cloth_texture: AnyTexture,
+    #[cfg(not(target_arch = "wasm32"))]
+    script: Option<crate::script::ScriptRunner>,
+    #[cfg(not(target_arch = "wasm32"))]
+    screenshot_path: Option<std::path::PathBuf>,
+    exit_requested: bool,
 }
 
 impl SimuverseApp {
@@ -46,20 +51,13 @@ impl SimuverseApp {
             Some("canvas_buf"),
         );
 
-        #[cfg(target_arch = "wasm32")]
-        let cloth_texture = {
-            let (cloth_texture, _) = crate::util::load_texture::from_path(
-                "cloth_500x500.png",
-                &app,
-                wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
-                false,
-            )
-            .await;
-            Some(cloth_texture)
-        };
-
-        #[cfg(not(target_arch = "wasm32"))]
-        let cloth_texture = None;
+        let (cloth_texture, _) = crate::util::load_texture::from_path(
+            "cloth_500x500.png",
+            &app,
+            wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+            false,
+        )
+        .await;
 
         let simulator = Box::new(FieldSimulator::new(
             &app,
@@ -84,7 +82,115 @@ impl SimuverseApp {
             simulator,
             depth_view,
             cloth_texture,
+            #[cfg(not(target_arch = "wasm32"))]
+            script: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            screenshot_path: None,
+            exit_requested: false,
+        }
+    }
+
+    /// 加载场景自动化脚本，从下一帧开始执行
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_script(&mut self, path: &std::path::Path) {
+        let cmds = std::fs::read_to_string(path)
+            .map_err(|e| alloc::format!("{e}"))
+            .and_then(|source| crate::script::parse_script(&source));
+        match cmds {
+            Ok(cmds) => {
+                // 截图需要从 surface 纹理拷贝数据
+                let usages = self
+                    .app_surface
+                    .surface
+                    .get_capabilities(&self.app_surface.adapter)
+                    .usages;
+                if usages.contains(wgpu::TextureUsages::COPY_SRC) {
+                    self.app_surface.ctx.config.usage |= wgpu::TextureUsages::COPY_SRC;
+                    self.app_surface
+                        .surface
+                        .configure(&self.app_surface.device, &self.app_surface.config);
+                } else {
+                    log::warn!("surface does not support COPY_SRC, screenshots are disabled");
+                }
+                // 脚本中的参数修改不应覆盖用户保存的设置
+                self.ctrl_panel.persistence_enabled = false;
+                self.script = Some(crate::script::ScriptRunner::new(cmds));
+            }
+            Err(e) => log::error!("failed to load script {}: {e}", path.display()),
+        }
+    }
+
+    /// 拖放到窗口上的模型文件在 CAD 中打开
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open_dropped_file(&mut self, path: &std::path::Path) {
+        match self
+            .ctrl_panel
+            .cad_setting
+            .open_model_file(&path.to_string_lossy())
+        {
+            Ok(()) => self.ctrl_panel.selected_simu_type = SimuType::CAD,
+            Err(e) => log::warn!("{e}"),
+        }
+    }
+
+    /// 脚本执行了 `exit` 命令
+    pub fn is_exit_requested(&self) -> bool {
+        self.exit_requested
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn run_script(&mut self) {
+        use crate::script::ScriptCmd;
+        use winit::dpi::PhysicalPosition;
+        use winit::event::{ElementState, MouseButton};
+
+        let Some(script) = self.script.as_mut() else {
+            return;
+        };
+        let cmds = script.next_frame();
+        if script.is_finished() {
+            self.script = None;
+        }
+        if cmds.is_empty() {
+            return;
         }
+
+        let to_physical = |pos: glam::Vec2| PhysicalPosition::new(pos.x as f64, pos.y as f64);
+        for cmd in cmds {
+            match cmd {
+                ScriptCmd::Simu(ty) => self.ctrl_panel.selected_simu_type = ty,
+                ScriptCmd::Set(key, value) => {
+                    if let Err(e) = self.ctrl_panel.set_param(&key, &value) {
+                        log::error!("script: {e}");
+                    }
+                }
+                ScriptCmd::Frames(_) => {}
+                ScriptCmd::Click(pos) => {
+                    self.cursor_moved(to_physical(pos));
+                    self.mouse_input(&ElementState::Pressed, &MouseButton::Left);
+                    self.on_click(pos);
+                    self.mouse_input(&ElementState::Released, &MouseButton::Left);
+                }
+                ScriptCmd::Press(pos) => {
+                    self.cursor_moved(to_physical(pos));
+                    self.mouse_input(&ElementState::Pressed, &MouseButton::Left);
+                    self.on_click(pos);
+                    self.simulator.touch_begin(&self.app_surface);
+                }
+                ScriptCmd::Move(pos) => {
+                    self.cursor_moved(to_physical(pos));
+                    self.touch_move(pos);
+                }
+                ScriptCmd::Release => {
+                    self.mouse_input(&ElementState::Released, &MouseButton::Left);
+                    self.simulator.touch_end(&self.app_surface);
+                }
+                ScriptCmd::Screenshot(path) => self.screenshot_path = Some(path.into()),
+                ScriptCmd::Exit => self.exit_requested = true,
+            }
+        }
+        // 让参数变更在本帧绘制前生效
+        self.update_setting();
     }
 
     pub fn get_adapter_info(&self) -> wgpu::AdapterInfo {
@@ -143,9 +249,10 @@ impl SimuverseApp {
         }
     }
 
-    pub fn on_ui_event(&mut self, event: &winit::event::WindowEvent) {
+    /// 返回事件是否已被 UI 消费
+    pub fn on_ui_event(&mut self, event: &winit::event::WindowEvent) -> bool {
         self.egui_layer
-            .on_ui_event(self.app_surface.get_view(), event);
+            .on_ui_event(self.app_surface.get_view(), event)
     }
 
     pub fn on_click(&mut self, pos: glam::Vec2) {
@@ -183,6 +290,9 @@ impl SimuverseApp {
         self.frame_count += 1;
         self.resize_surface_if_needed();
 
+        #[cfg(not(target_arch = "wasm32"))]
+        self.run_script();
+
         let mut encoder =
             self.app_surface
                 .device
@@ -241,6 +351,26 @@ impl SimuverseApp {
             self.egui_layer.compose_by_pass(&mut rpass);
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
+        let readback = match self.screenshot_path.take() {
+            Some(path)
+                if self
+                    .app_surface
+                    .config
+                    .usage
+                    .contains(wgpu::TextureUsages::COPY_SRC) =>
+            {
+                let readback = crate::util::texture_readback::TextureReadback::new(
+                    &self.app_surface.device,
+                    &mut encoder,
+                    &output.texture,
+                    0,
+                );
+                Some((readback, path))
+            }
+            _ => None,
+        };
+
         if let Some(egui_cmd_bufs) = egui_cmd_buffers {
             self.app_surface
                 .queue
@@ -248,6 +378,14 @@ impl SimuverseApp {
         } else {
             self.app_surface.queue.submit(Some(encoder.finish()));
         }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some((readback, path)) = readback {
+            match readback.save_png(&self.app_surface.device, &path) {
+                Ok(_) => log::info!("screenshot saved to {}", path.display()),
+                Err(e) => log::error!("failed to save screenshot {}: {e}", path.display()),
+            }
+        }
         output.present();
 
         self.update_setting();
@@ -265,10 +403,12 @@ impl SimuverseApp {
                 canvas_buf,
                 &ctrl_panel.setting,
             )),
-            SimuType::Noise => Box::new(TextureSimulator::new(app)),
+            SimuType::Noise => Box::new(TextureSimulator::new(app, &ctrl_panel.noise_setting)),
             SimuType::PBDynamic => Box::new(crate::pbd::PBDSimulator::new(
                 app,
-                self.cloth_texture.as_ref(),
+                &self.cloth_texture,
+                &ctrl_panel.pbd_setting,
+                &ctrl_panel.cad_setting.model_file,
             )),
             #[cfg(not(target_arch = "wasm32"))]
             SimuType::CAD => Box::new(crate::CADObjViewer::new(app, ctrl_panel)),
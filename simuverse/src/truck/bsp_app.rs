@@ -126,7 +126,7 @@ impl CADApp for BSplineApp {
         camera
     }
 
-    fn update(&mut self, scene: &mut Scene, _setting: &CADSetting) {
+    fn update(&mut self, scene: &mut Scene, _setting: &mut CADSetting) {
         if self.updated.load(Ordering::SeqCst) {
             let object = self.object.lock().unwrap();
             scene.update_vertex_buffer(&*object);
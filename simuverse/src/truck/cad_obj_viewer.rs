@@ -2,7 +2,10 @@ use crate::{ControlPanel, SettingObj, Simulator};
 use alloc::{boxed::Box, vec};
 use app_surface::AppSurface;
 
-use super::{CADApp, CADAppType, bsp_app::BSplineApp, obj_app::ObjApp, platform::*, rendimpl::*};
+use super::{
+    CADApp, CADAppType, bsp_app::BSplineApp, obj_app::ObjApp, platform::*, rendimpl::*,
+    step_app::StepApp,
+};
 
 pub struct CADObjViewer {
     scene: Scene,
@@ -43,6 +46,7 @@ impl CADObjViewer {
         let cad_obj: Box<dyn CADApp> = match ty {
             CADAppType::Bspline => Box::new(BSplineApp::new(app, scene)),
             CADAppType::Obj => Box::new(ObjApp::new(app, scene)),
+            CADAppType::Step => Box::new(StepApp::new(app, scene)),
         };
         scene.descriptor_mut().studio.camera = cad_obj.get_camera();
 
@@ -82,9 +86,11 @@ impl Simulator for CADObjViewer {
             self.cad_obj.remove_from_scene(&mut self.scene);
             self.cad_obj = Self::create_cad_app(app, &mut self.scene, ty);
             self.ty = ty;
+            // 上一种模型的加载错误不再适用
+            control_panel.cad_setting.load_status.clear();
         }
         self.cad_obj
-            .update(&mut self.scene, &control_panel.cad_setting);
+            .update(&mut self.scene, &mut control_panel.cad_setting);
     }
 
     fn draw_by_rpass<'b, 'a: 'b>(
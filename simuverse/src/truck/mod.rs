@@ -10,22 +10,28 @@ use winit::{
 
 mod bsp_app;
 mod obj_app;
+mod step_app;
+pub(crate) use obj_app::TEAPOT_BYTES;
 
 use crate::CADSetting;
 
-use self::platform::{Camera, Scene};
+use self::platform::*;
+use self::rendimpl::Material;
+use truck_meshalgo::prelude::*;
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum CADAppType {
     Bspline = 0,
     Obj,
+    Step,
 }
 
 impl CADAppType {
     pub fn from_u32(ty: u32) -> Self {
         match ty {
             0 => CADAppType::Bspline,
-            _ => CADAppType::Obj,
+            1 => CADAppType::Obj,
+            _ => CADAppType::Step,
         }
     }
 }
@@ -61,6 +67,124 @@ trait CADApp {
     fn cursor_moved(&mut self, _scene: &mut Scene, _position: PhysicalPosition<f64>) {}
 
     fn get_camera(&self) -> Camera;
-    fn update(&mut self, scene: &mut Scene, setting: &CADSetting);
+    fn update(&mut self, scene: &mut Scene, setting: &mut CADSetting);
     fn remove_from_scene(&mut self, scene: &mut Scene);
 }
+
+/// 相机的视角
+const FOV: f64 = core::f64::consts::PI / 4.0;
+
+/// 查看网格模型时的相机操作，ObjApp 与 StepApp 共用：
+/// 左键拖动旋转相机，滚轮前后移动相机，右键把光源移到相机处
+struct ModelViewControl {
+    rotate_flag: bool,
+    prev_cursor: Vector2,
+    // 缩放到单位尺寸后模型包围球的半径
+    radius: f64,
+}
+
+impl ModelViewControl {
+    fn new(radius: f64) -> Self {
+        Self {
+            rotate_flag: false,
+            prev_cursor: Vector2::zero(),
+            radius,
+        }
+    }
+
+    fn mouse_input(&mut self, scene: &mut Scene, state: &ElementState, button: &MouseButton) {
+        match button {
+            MouseButton::Left => {
+                self.rotate_flag = *state == ElementState::Pressed;
+            }
+            MouseButton::Right => {
+                let (light, camera) = {
+                    let desc = scene.studio_config_mut();
+                    (&mut desc.lights[0], &desc.camera)
+                };
+                match light.light_type {
+                    LightType::Point => {
+                        light.position = camera.position();
+                    }
+                    LightType::Uniform => {
+                        light.position = camera.position();
+                        let strength = light.position.to_vec().magnitude();
+                        light.position /= strength;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn mouse_wheel(&self, scene: &mut Scene, delta: &MouseScrollDelta) {
+        if let MouseScrollDelta::LineDelta(_, y) = *delta {
+            let camera = &mut scene.studio_config_mut().camera;
+            let trans_vec = camera.eye_direction() * 0.2 * y as f64;
+            camera.matrix = Matrix4::from_translation(trans_vec) * camera.matrix;
+        }
+    }
+
+    fn cursor_moved(&mut self, scene: &mut Scene, position: PhysicalPosition<f64>) {
+        let position = Vector2::new(position.x, position.y);
+        if self.rotate_flag {
+            let matrix = &mut scene.studio_config_mut().camera.matrix;
+            let dir2d = position - self.prev_cursor;
+            if dir2d.so_small() {
+                return;
+            }
+            let mut axis = dir2d[1] * matrix[0].truncate();
+            axis += dir2d[0] * matrix[1].truncate();
+            axis /= axis.magnitude();
+            let angle = dir2d.magnitude() * 0.01;
+            let mat = Matrix4::from_axis_angle(axis, Rad(angle));
+            *matrix = mat.invert().unwrap() * *matrix;
+        }
+        self.prev_cursor = position;
+    }
+
+    /// 相机与模型的距离使包围球恰好在视野内
+    fn camera(&self) -> Camera {
+        let distance = self.radius / (FOV * 0.5).sin() * 1.1;
+        let eye = Point3::new(1.0, 1.0, 1.0) * (distance / 3.0_f64.sqrt());
+        let matrix = Matrix4::look_at_rh(eye, Point3::origin(), Vector3::unit_y());
+        Camera::perspective_camera(
+            matrix.invert().unwrap(),
+            Rad(FOV),
+            distance * 0.05,
+            distance * 20.0,
+        )
+    }
+
+    /// 加载新模型后，相机及光源回到初始位置
+    fn reset_camera(&mut self, scene: &mut Scene, radius: f64) {
+        self.radius = radius;
+        let camera = self.camera();
+        let desc = scene.studio_config_mut();
+        desc.lights[0].position = camera.position();
+        desc.camera = camera;
+    }
+}
+
+/// 模型表面的材质，消隐线模式下只显示 albedo 的颜色
+fn model_material(albedo: Vector4, hidden_line: bool) -> Material {
+    if hidden_line {
+        Material {
+            albedo,
+            reflectance: 0.0,
+            roughness: 0.0,
+            ambient_ratio: 1.0,
+            background_ratio: 0.0,
+            alpha_blend: false,
+        }
+    } else {
+        Material {
+            albedo,
+            reflectance: 0.5,
+            roughness: 0.1,
+            ambient_ratio: 0.02,
+            background_ratio: 0.0,
+            alpha_blend: false,
+        }
+    }
+}
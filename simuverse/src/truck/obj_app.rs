@@ -1,65 +1,82 @@
 use crate::CADSetting;
 
-use super::{CADApp, RenderMode, platform::*, rendimpl::*};
+use super::{CADApp, ModelViewControl, RenderMode, model_material, platform::*, rendimpl::*};
+use alloc::{format, string::String};
 use app_surface::AppSurface;
-use std::io::Read;
+use std::path::Path;
 use truck_meshalgo::prelude::*;
 use winit::{
     dpi::PhysicalPosition,
     event::{ElementState, MouseButton, MouseScrollDelta, TouchPhase},
 };
 
-const TEAPOT_BYTES: &[u8] = include_bytes!(concat!(
+pub(crate) const TEAPOT_BYTES: &[u8] = include_bytes!(concat!(
     env!("CARGO_MANIFEST_DIR"),
     "/../assets/obj/skull-with-texcoord.obj",
 ));
-// const TEAPOT_BYTES: &[u8] = include_bytes!(concat!(
-//     env!("CARGO_MANIFEST_DIR"),
-//     "/assets/obj/skull-modified.obj",
-// ));
-// const TEAPOT_BYTES: &[u8] = include_bytes!(concat!(
-//     env!("CARGO_MANIFEST_DIR"),
-//     "/assets/obj/teapot.obj",
-// ));
 
 pub struct ObjApp {
-    _creator: InstanceCreator,
-    rotate_flag: bool,
-    prev_cursor: Vector2,
+    creator: InstanceCreator,
+    view: ModelViewControl,
     instance: PolygonInstance,
     wireframe: WireFrameInstance,
     render_mode: RenderMode,
+    // 当前模型的文件路径，为空时是内置的模型
+    model_file: String,
 }
 
 impl ObjApp {
     pub fn new(_app: &AppSurface, scene: &mut Scene) -> Self {
         let creator = scene.instance_creator();
-        let (instance, wireframe) = Self::load_obj(&creator, TEAPOT_BYTES);
+        let mesh = obj::read(TEAPOT_BYTES).unwrap();
+        let (instance, wireframe, radius) = Self::create_instances(&creator, mesh);
         scene.add_object(&instance);
         scene.add_object(&wireframe);
         let mut app = Self {
-            _creator: creator,
-            rotate_flag: false,
-            prev_cursor: Vector2::zero(),
+            creator,
+            view: ModelViewControl::new(radius),
             instance,
             wireframe,
             render_mode: RenderMode::NaiveSurface,
+            model_file: String::new(),
         };
         app.update_render_mode(scene);
         app
     }
 
+    /// 替换为文件中的模型，读取失败时保留当前的模型
+    fn load_model(&mut self, scene: &mut Scene, setting: &mut CADSetting) {
+        self.model_file = setting.model_file.clone();
+        let mesh = if self.model_file.is_empty() {
+            obj::read(TEAPOT_BYTES).map_err(|e| format!("{e}"))
+        } else {
+            read_mesh(&self.model_file)
+        };
+        let mesh = match mesh {
+            Ok(mesh) => mesh,
+            Err(e) => {
+                log::warn!("{e}");
+                setting.load_status = e;
+                return;
+            }
+        };
+        setting.load_status.clear();
+
+        self.remove_from_scene(scene);
+        let (instance, wireframe, radius) = Self::create_instances(&self.creator, mesh);
+        scene.add_object(&instance);
+        scene.add_object(&wireframe);
+        self.instance = instance;
+        self.wireframe = wireframe;
+        self.update_render_mode(scene);
+        self.view.reset_camera(scene, radius);
+    }
+
     fn update_render_mode(&mut self, scene: &mut Scene) {
         let visible = match self.render_mode {
             RenderMode::NaiveSurface => {
-                self.instance.instance_state_mut().material = Material {
-                    albedo: Vector4::new(1.0, 1.0, 1.0, 1.0),
-                    reflectance: 0.5,
-                    roughness: 0.1,
-                    ambient_ratio: 0.02,
-                    background_ratio: 0.0,
-                    alpha_blend: false,
-                };
+                self.instance.instance_state_mut().material =
+                    model_material(Vector4::new(1.0, 1.0, 1.0, 1.0), false);
                 scene.update_bind_group(&self.instance);
                 (true, false)
             }
@@ -69,28 +86,16 @@ impl ObjApp {
                 (false, true)
             }
             RenderMode::HiddenLineEliminate => {
-                self.instance.instance_state_mut().material = Material {
-                    albedo: Vector4::new(0.0, 0.0, 0.0, 1.0),
-                    reflectance: 0.0,
-                    roughness: 0.0,
-                    ambient_ratio: 1.0,
-                    background_ratio: 0.0,
-                    alpha_blend: false,
-                };
+                self.instance.instance_state_mut().material =
+                    model_material(Vector4::new(0.0, 0.0, 0.0, 1.0), true);
                 self.wireframe.instance_state_mut().color = Vector4::new(1.0, 1.0, 1.0, 1.0);
                 scene.update_bind_group(&self.instance);
                 scene.update_bind_group(&self.wireframe);
                 (true, true)
             }
             RenderMode::SurfaceAndWireFrame => {
-                self.instance.instance_state_mut().material = Material {
-                    albedo: Vector4::new(1.0, 1.0, 1.0, 1.0),
-                    reflectance: 0.5,
-                    roughness: 0.1,
-                    ambient_ratio: 0.02,
-                    background_ratio: 0.0,
-                    alpha_blend: false,
-                };
+                self.instance.instance_state_mut().material =
+                    model_material(Vector4::new(1.0, 1.0, 1.0, 1.0), false);
                 self.wireframe.instance_state_mut().color = Vector4::new(0.0, 0.0, 0.0, 1.0);
                 scene.update_bind_group(&self.instance);
                 scene.update_bind_group(&self.wireframe);
@@ -101,15 +106,16 @@ impl ObjApp {
         scene.set_visibility(&self.wireframe, visible.1);
     }
 
-    fn load_obj<R: Read>(
+    /// 模型平移到原点并缩放到单位尺寸，同时返回缩放后包围球的半径
+    fn create_instances(
         creator: &InstanceCreator,
-        reader: R,
-    ) -> (PolygonInstance, WireFrameInstance) {
-        let mut mesh = obj::read(reader).unwrap();
+        mut mesh: PolygonMesh,
+    ) -> (PolygonInstance, WireFrameInstance, f64) {
         mesh.put_together_same_attrs(TOLERANCE * 2.0)
             .add_smooth_normals(0.5, false);
         let bdd_box = mesh.bounding_box();
         let (size, center) = (bdd_box.size(), bdd_box.center());
+        let radius = bdd_box.diagonal().magnitude() / size * 0.5;
         let mat = Matrix4::from_translation(center.to_vec()) * Matrix4::from_scale(size);
         let polygon_state = PolygonState {
             matrix: mat.invert().unwrap(),
@@ -122,81 +128,76 @@ impl ObjApp {
         (
             creator.create_instance(&mesh, &polygon_state),
             creator.create_instance(&mesh, &wire_state),
+            radius,
         )
     }
 }
 
+/// 读取 OBJ 或 STL（二进制及 ASCII）文件
+fn read_mesh(path: &str) -> Result<PolygonMesh, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("failed to read {path}: {e}"))?;
+    parse_mesh(path, &bytes)
+}
+
+/// 按 `path` 的扩展名解析文件内容，`path` 同时用于错误信息
+fn parse_mesh(path: &str, bytes: &[u8]) -> Result<PolygonMesh, String> {
+    let ext = Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    let mesh = match ext.as_deref() {
+        Some("obj") => obj::read(bytes),
+        Some("stl") => stl::read(bytes, stl_type(bytes)),
+        _ => {
+            return Err(format!(
+                "unsupported model file {path}, expected .obj or .stl"
+            ));
+        }
+    }
+    .map_err(|e| format!("failed to parse {path}: {e}"))?;
+    if mesh.positions().is_empty() || mesh.faces().is_empty() {
+        return Err(format!("{path} contains no faces"));
+    }
+    // 缩放到单位尺寸时需要求逆，尺寸为 0 或不是有限值的模型无法显示
+    let size = mesh.bounding_box().size();
+    if size <= TOLERANCE || !size.is_finite() {
+        return Err(format!("{path}: model is degenerate"));
+    }
+    Ok(mesh)
+}
+
+/// 不少二进制 STL 的文件头也以 `solid` 开头，先按三角形数量与文件长度是否吻合来判断
+fn stl_type(bytes: &[u8]) -> stl::StlType {
+    if bytes.len() >= 84 {
+        let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+        if 84 + count * 50 == bytes.len() {
+            return stl::StlType::Binary;
+        }
+    }
+    stl::StlType::Automatic
+}
+
 impl CADApp for ObjApp {
     fn mouse_input(&mut self, scene: &mut Scene, state: &ElementState, button: &MouseButton) {
-        match button {
-            MouseButton::Left => {
-                self.rotate_flag = *state == ElementState::Pressed;
-            }
-            MouseButton::Right => {
-                let (light, camera) = {
-                    let desc = scene.studio_config_mut();
-                    (&mut desc.lights[0], &desc.camera)
-                };
-                match light.light_type {
-                    LightType::Point => {
-                        light.position = camera.position();
-                    }
-                    LightType::Uniform => {
-                        light.position = camera.position();
-                        let strength = light.position.to_vec().magnitude();
-                        light.position /= strength;
-                    }
-                }
-            }
-            _ => {}
-        }
+        self.view.mouse_input(scene, state, button);
     }
 
     fn mouse_wheel(&mut self, scene: &mut Scene, delta: &MouseScrollDelta, _: &TouchPhase) {
-        match *delta {
-            MouseScrollDelta::LineDelta(_, y) => {
-                let camera = &mut scene.studio_config_mut().camera;
-                let trans_vec = camera.eye_direction() * 0.2 * y as f64;
-                camera.matrix = Matrix4::from_translation(trans_vec) * camera.matrix;
-            }
-            MouseScrollDelta::PixelDelta(_) => {}
-        };
+        self.view.mouse_wheel(scene, delta);
     }
 
     fn cursor_moved(&mut self, scene: &mut Scene, position: PhysicalPosition<f64>) {
-        let position = Vector2::new(position.x, position.y);
-        if self.rotate_flag {
-            let matrix = &mut scene.studio_config_mut().camera.matrix;
-            let position = Vector2::new(position.x, position.y);
-            let dir2d = position - self.prev_cursor;
-            if dir2d.so_small() {
-                return;
-            }
-            let mut axis = dir2d[1] * matrix[0].truncate();
-            axis += dir2d[0] * matrix[1].truncate();
-            axis /= axis.magnitude();
-            let angle = dir2d.magnitude() * 0.01;
-            let mat = Matrix4::from_axis_angle(axis, Rad(angle));
-            *matrix = mat.invert().unwrap() * *matrix;
-        }
-        self.prev_cursor = position;
+        self.view.cursor_moved(scene, position);
     }
 
     fn get_camera(&self) -> Camera {
-        let matrix = Matrix4::look_at_rh(
-            Point3::new(1.0, 1.0, 1.0),
-            Point3::origin(),
-            Vector3::unit_y(),
-        );
-        Camera::perspective_camera(
-            matrix.invert().unwrap(),
-            Rad(core::f64::consts::PI / 4.0),
-            0.1,
-            40.0,
-        )
+        self.view.camera()
     }
 
-    fn update(&mut self, scene: &mut Scene, setting: &CADSetting) {
+    fn update(&mut self, scene: &mut Scene, setting: &mut CADSetting) {
+        if setting.model_file != self.model_file {
+            self.load_model(scene, setting);
+        }
         let mode = RenderMode::from_u32(setting.render_mode);
         if self.render_mode != mode {
             self.render_mode = mode;
@@ -209,3 +210,20 @@ impl CADApp for ObjApp {
         scene.remove_object(&self.wireframe);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reject_degenerate_mesh() {
+        let flat = b"v 1 1 1\nv 1 1 1\nv 1 1 1\nf 1 2 3\n";
+        let err = parse_mesh("flat.obj", flat).err().unwrap();
+        assert!(err.contains("degenerate"));
+
+        let triangle = b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+        assert!(parse_mesh("triangle.OBJ", triangle).is_ok());
+        assert!(parse_mesh("triangle.ply", triangle).is_err());
+        assert!(parse_mesh("empty.obj", b"v 0 0 0\n").is_err());
+    }
+}
@@ -92,34 +92,6 @@ pub struct BufferHandler {
 /// The member variables of this struct are the ones of [`BindGroupLayoutEntry`]
 /// with only `binding` removed. We can create `BindGroupLayout` by
 /// giving its iterator to the function truck_platform::[`create_bind_group_layout`].
-/// # Examples
-/// ```
-/// use std::sync::{Arc, Mutex};
-/// use truck_platform::*;
-/// use wgpu::*;
-/// let handler = pollster::block_on(AppSurface::default_device());
-/// let entries = [
-///     PreBindGroupLayoutEntry {
-///         visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
-///         ty: BindingType::Buffer {
-///             ty: BufferBindingType::Uniform,
-///             has_dynamic_offset: false,
-///             min_binding_size: None,
-///         },
-///         count: None,
-///     },
-///     PreBindGroupLayoutEntry {
-///         visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
-///         ty: BindingType::Buffer {
-///             ty: BufferBindingType::Uniform,
-///             has_dynamic_offset: false,
-///             min_binding_size: None,
-///         },
-///         count: None,
-///     },
-/// ];
-/// let layout: BindGroupLayout = bind_group_util::create_bind_group_layout(handler.device(), &entries);
-/// ```
 ///
 /// [`BindGroupLayoutEntry`]: https://docs.rs/wgpu/0.10.1/wgpu/struct.BindGroupLayoutEntry.html
 #[doc(hidden)]
@@ -202,12 +174,8 @@ pub struct Light {
 /// If you want to define a new drawing element in which "Rendered" will be implemented, you need to add
 /// this structure to the member variables of that drawing element.
 ///
-/// This structure is assigned a unique value each time it is generated by `RenderID::gen()`.
+/// This structure is assigned a unique value each time it is generated by `RenderID::gen_id()`.
 /// This property allows us to map a `Rendred` entity to data in GPU memory held by `Scene`.
-/// ```
-/// use truck_platform::RenderID;
-/// assert_ne!(RenderID::gen(), RenderID::gen());
-/// ```
 #[derive(Clone, Copy, Hash, PartialEq, Eq, Debug)]
 pub struct RenderID(usize);
 
@@ -379,3 +347,37 @@ pub mod bind_group_util {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_id_is_unique() {
+        assert_ne!(RenderID::gen_id(), RenderID::gen_id());
+    }
+
+    #[test]
+    fn create_bind_group_layout() {
+        let instance = Instance::new(InstanceDescriptor::new_without_display_handle());
+        // 没有可用的适配器时跳过
+        let Ok(adapter) =
+            pollster::block_on(instance.request_adapter(&RequestAdapterOptions::default()))
+        else {
+            return;
+        };
+        let (device, _queue) =
+            pollster::block_on(adapter.request_device(&DeviceDescriptor::default())).unwrap();
+        let uniform = || PreBindGroupLayoutEntry {
+            visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let entries = [uniform(), uniform()];
+        let _layout: BindGroupLayout = bind_group_util::create_bind_group_layout(&device, &entries);
+    }
+}
@@ -0,0 +1,359 @@
+use crate::{CADSetting, StepShellNode};
+
+use super::{CADApp, ModelViewControl, RenderMode, model_material, platform::*, rendimpl::*};
+use alloc::{format, string::String, vec::Vec};
+use app_surface::AppSurface;
+use truck_meshalgo::prelude::*;
+use truck_stepio::r#in::{
+    Table,
+    alias::{ElementarySurface, Surface, SweptCurve},
+    ruststep,
+};
+use winit::{
+    dpi::PhysicalPosition,
+    event::{ElementState, MouseButton, MouseScrollDelta, TouchPhase},
+};
+
+const SAMPLE_STEP: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../assets/step/sample.step",
+));
+
+/// 网格化后的壳：每个面一个网格，网格化失败的面为 None
+struct MeshedShell {
+    id: u64,
+    label: String,
+    faces: Vec<(&'static str, Option<PolygonMesh>)>,
+    edges: Vec<PolylineCurve<Point3>>,
+}
+
+/// 场景中的壳：每个面一个实例，以便在模型树中单独高亮
+struct ShellInstance {
+    faces: Vec<Option<PolygonInstance>>,
+    wireframe: WireFrameInstance,
+    visible: bool,
+}
+
+/// 显示 STEP 文件中的 B-rep 实体
+pub struct StepApp {
+    creator: InstanceCreator,
+    view: ModelViewControl,
+    shells: Vec<ShellInstance>,
+    render_mode: RenderMode,
+    // 当前模型的文件路径，为空时是内置的示例
+    step_file: String,
+    selected_face: Option<(usize, usize)>,
+    // 模型树需要与新加载的模型同步
+    tree: Option<Vec<StepShellNode>>,
+}
+
+impl StepApp {
+    pub fn new(_app: &AppSurface, scene: &mut Scene) -> Self {
+        let creator = scene.instance_creator();
+        let mut app = Self {
+            creator,
+            view: ModelViewControl::new(1.0),
+            shells: Vec::new(),
+            render_mode: RenderMode::NaiveSurface,
+            step_file: String::new(),
+            selected_face: None,
+            tree: None,
+        };
+        match read_step(SAMPLE_STEP) {
+            Ok(shells) => app.replace_model(scene, shells),
+            Err(e) => log::warn!("{e}"),
+        }
+        app
+    }
+
+    /// 替换为文件中的模型，读取失败时保留当前的模型
+    fn load_model(&mut self, scene: &mut Scene, setting: &mut CADSetting) {
+        self.step_file = setting.step_file.clone();
+        let shells = if self.step_file.is_empty() {
+            read_step(SAMPLE_STEP)
+        } else {
+            std::fs::read_to_string(&self.step_file)
+                .map_err(|e| format!("failed to read {}: {e}", self.step_file))
+                .and_then(|text| read_step(&text))
+        };
+        match shells {
+            Ok(shells) => {
+                setting.load_status.clear();
+                self.replace_model(scene, shells);
+            }
+            Err(e) => {
+                log::warn!("{e}");
+                setting.load_status = e;
+            }
+        }
+    }
+
+    fn replace_model(&mut self, scene: &mut Scene, shells: Vec<MeshedShell>) {
+        // 所有的壳共用一个变换，整体平移到原点并缩放到单位尺寸
+        let bdd_box = bounding_box(&shells);
+        let (size, center) = (bdd_box.size(), bdd_box.center());
+        // read_step 已排除了尺寸为 0 的模型
+        let Some(matrix) =
+            (Matrix4::from_translation(center.to_vec()) * Matrix4::from_scale(size)).invert()
+        else {
+            log::warn!("STEP model is degenerate");
+            return;
+        };
+        self.remove_from_scene(scene);
+
+        let polygon_state = PolygonState {
+            matrix,
+            ..Default::default()
+        };
+        let wire_state = WireFrameState {
+            matrix,
+            ..Default::default()
+        };
+        let mut tree = Vec::with_capacity(shells.len());
+        self.shells = shells
+            .into_iter()
+            .map(|shell| {
+                let faces = shell
+                    .faces
+                    .iter()
+                    .map(|(_, mesh)| {
+                        let instance = self.creator.create_instance(mesh.as_ref()?, &polygon_state);
+                        scene.add_object(&instance);
+                        Some(instance)
+                    })
+                    .collect();
+                let wireframe = self.creator.create_instance(&shell.edges, &wire_state);
+                scene.add_object(&wireframe);
+                tree.push(StepShellNode {
+                    id: shell.id,
+                    label: shell.label,
+                    faces: shell
+                        .faces
+                        .iter()
+                        .map(|(kind, mesh)| match mesh {
+                            Some(_) => String::from(*kind),
+                            None => format!("{kind} (failed)"),
+                        })
+                        .collect(),
+                    edge_count: shell.edges.len(),
+                    visible: true,
+                });
+                ShellInstance {
+                    faces,
+                    wireframe,
+                    visible: true,
+                }
+            })
+            .collect();
+        self.selected_face = None;
+        self.tree = Some(tree);
+        self.update_render_mode(scene);
+        let radius = bdd_box.diagonal().magnitude() / size * 0.5;
+        self.view.reset_camera(scene, radius);
+    }
+
+    fn update_render_mode(&mut self, scene: &mut Scene) {
+        let (show_surface, show_wireframe, line_color) = match self.render_mode {
+            RenderMode::NaiveSurface => (true, false, Vector4::new(1.0, 1.0, 1.0, 1.0)),
+            RenderMode::NaiveWireFrame => (false, true, Vector4::new(1.0, 1.0, 1.0, 1.0)),
+            RenderMode::HiddenLineEliminate => (true, true, Vector4::new(1.0, 1.0, 1.0, 1.0)),
+            RenderMode::SurfaceAndWireFrame => (true, true, Vector4::new(0.0, 0.0, 0.0, 1.0)),
+        };
+        let hidden_line = self.render_mode == RenderMode::HiddenLineEliminate;
+        for (shell_index, shell) in self.shells.iter_mut().enumerate() {
+            for (face_index, face) in shell.faces.iter_mut().enumerate() {
+                let Some(face) = face else {
+                    continue;
+                };
+                let selected = self.selected_face == Some((shell_index, face_index));
+                face.instance_state_mut().material = face_material(hidden_line, selected);
+                scene.update_bind_group(&*face);
+                scene.set_visibility(&*face, shell.visible && show_surface);
+            }
+            shell.wireframe.instance_state_mut().color = line_color;
+            scene.update_bind_group(&shell.wireframe);
+            scene.set_visibility(&shell.wireframe, shell.visible && show_wireframe);
+        }
+    }
+}
+
+/// 选中的面显示为橙色
+fn face_material(hidden_line: bool, selected: bool) -> Material {
+    let albedo = match (hidden_line, selected) {
+        (_, true) => Vector4::new(1.0, 0.55, 0.1, 1.0),
+        (true, false) => Vector4::new(0.0, 0.0, 0.0, 1.0),
+        (false, false) => Vector4::new(1.0, 1.0, 1.0, 1.0),
+    };
+    model_material(albedo, hidden_line)
+}
+
+/// 解析 STEP (AP203/214) 文件中的壳并网格化，无法转换的壳会被跳过
+fn read_step(text: &str) -> Result<Vec<MeshedShell>, String> {
+    let exchange =
+        ruststep::parser::parse(text).map_err(|e| format!("failed to parse STEP data: {e}"))?;
+    let Some(data) = exchange.data.first() else {
+        return Err(String::from("STEP data contains no DATA section"));
+    };
+    let table = Table::from_data_section(data);
+    let mut ids: Vec<u64> = table.shell.keys().copied().collect();
+    ids.sort_unstable();
+
+    let mut shells = Vec::with_capacity(ids.len());
+    for id in ids {
+        let holder = &table.shell[&id];
+        let shell = match table.to_compressed_shell(holder) {
+            Ok(shell) => shell,
+            Err(e) => {
+                log::warn!("failed to convert shell #{id}: {e}");
+                continue;
+            }
+        };
+        // 容差取顶点包围盒对角线的千分之一，只有一个顶点的壳（如球面）先粗略网格化来估计尺寸
+        let vertices_bdd: BoundingBox<Point3> = shell.vertices.iter().collect();
+        let mut diameter = vertices_bdd.diameter();
+        if diameter.is_nan() || diameter < TOLERANCE {
+            diameter = shell
+                .robust_triangulation(0.01)
+                .to_polygon()
+                .bounding_box()
+                .diameter();
+        }
+        if diameter.is_nan() || diameter < TOLERANCE {
+            log::warn!("shell #{id} is empty");
+            continue;
+        }
+        let meshed = shell.robust_triangulation(diameter * 0.001);
+
+        let faces = shell
+            .faces
+            .iter()
+            .zip(meshed.faces)
+            .map(|(face, meshed_face)| {
+                let mesh = meshed_face.surface.map(|mut mesh| {
+                    if !meshed_face.orientation {
+                        mesh.invert();
+                    }
+                    mesh.put_together_same_attrs(TOLERANCE * 2.0)
+                        .add_smooth_normals(0.5, false);
+                    mesh
+                });
+                (surface_kind(&face.surface), mesh)
+            })
+            .collect();
+        shells.push(MeshedShell {
+            id,
+            label: holder.label.clone(),
+            faces,
+            edges: meshed.edges.into_iter().map(|edge| edge.curve).collect(),
+        });
+    }
+    if shells.is_empty() {
+        return Err(String::from("STEP data contains no B-rep shell"));
+    }
+    let size = bounding_box(&shells).size();
+    if size <= TOLERANCE || !size.is_finite() {
+        return Err(String::from("no face of the STEP model could be meshed"));
+    }
+    Ok(shells)
+}
+
+/// 所有网格化成功的面的包围盒
+fn bounding_box(shells: &[MeshedShell]) -> BoundingBox<Point3> {
+    shells
+        .iter()
+        .flat_map(|shell| shell.faces.iter())
+        .filter_map(|(_, mesh)| mesh.as_ref())
+        .map(|mesh| mesh.bounding_box())
+        .fold(BoundingBox::new(), |bdd_box, b| bdd_box + b)
+}
+
+fn surface_kind(surface: &Surface) -> &'static str {
+    match surface {
+        Surface::ElementarySurface(surface) => match **surface {
+            ElementarySurface::Plane(_) => "Plane",
+            ElementarySurface::Sphere(_) => "Sphere",
+            ElementarySurface::CylindricalSurface(_) => "Cylinder",
+            ElementarySurface::ToroidalSurface(_) => "Torus",
+            ElementarySurface::ConicalSurface(_) => "Cone",
+        },
+        Surface::SweptCurve(surface) => match **surface {
+            SweptCurve::ExtrudedCurve(_) => "Extrusion",
+            SweptCurve::RevolutedCurve(_) => "Revolution",
+        },
+        Surface::BSplineSurface(_) => "B-spline",
+        Surface::NurbsSurface(_) => "NURBS",
+    }
+}
+
+impl CADApp for StepApp {
+    fn mouse_input(&mut self, scene: &mut Scene, state: &ElementState, button: &MouseButton) {
+        self.view.mouse_input(scene, state, button);
+    }
+
+    fn mouse_wheel(&mut self, scene: &mut Scene, delta: &MouseScrollDelta, _: &TouchPhase) {
+        self.view.mouse_wheel(scene, delta);
+    }
+
+    fn cursor_moved(&mut self, scene: &mut Scene, position: PhysicalPosition<f64>) {
+        self.view.cursor_moved(scene, position);
+    }
+
+    fn get_camera(&self) -> Camera {
+        self.view.camera()
+    }
+
+    fn update(&mut self, scene: &mut Scene, setting: &mut CADSetting) {
+        if setting.step_file != self.step_file {
+            self.load_model(scene, setting);
+        }
+        if let Some(tree) = self.tree.take() {
+            setting.step_tree = tree;
+            setting.selected_face = None;
+        }
+
+        let mut changed = setting.selected_face != self.selected_face;
+        self.selected_face = setting.selected_face;
+        for (shell, node) in self.shells.iter_mut().zip(&setting.step_tree) {
+            changed |= shell.visible != node.visible;
+            shell.visible = node.visible;
+        }
+        let mode = RenderMode::from_u32(setting.render_mode);
+        if self.render_mode != mode || changed {
+            self.render_mode = mode;
+            self.update_render_mode(scene);
+        }
+    }
+
+    fn remove_from_scene(&mut self, scene: &mut Scene) {
+        for shell in &self.shells {
+            for face in shell.faces.iter().flatten() {
+                scene.remove_object(face);
+            }
+            scene.remove_object(&shell.wireframe);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_sample_step() {
+        let shells = read_step(SAMPLE_STEP).unwrap();
+        assert!(bounding_box(&shells).size() > TOLERANCE);
+        assert!(read_step("not a step file").is_err());
+    }
+
+    #[test]
+    fn unmeshed_faces_have_no_size() {
+        let shells = [MeshedShell {
+            id: 0,
+            label: String::new(),
+            faces: alloc::vec![("Plane", None)],
+            edges: Vec::new(),
+        }];
+        let size = bounding_box(&shells).size();
+        assert!(size <= TOLERANCE || !size.is_finite());
+    }
+}
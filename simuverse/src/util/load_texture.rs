@@ -6,6 +6,7 @@ use std::path::PathBuf;
 use image::{DynamicImage, GenericImageView};
 use wgpu::{Extent3d, Sampler, Texture, TextureFormat, TextureView};
 
+#[derive(Clone)]
 pub struct AnyTexture {
     pub size: Extent3d,
     pub tex: Texture,
@@ -50,6 +51,35 @@ pub async fn from_path(
         image::open(path.as_path()).unwrap()
     };
 
+    let any_tex = create_from_img(img, app, usage, set_to_grayscale);
+
+    (any_tex, default_sampler(&app.device))
+}
+
+/// 加载用户选择的图片文件，文件不存在或格式不支持时返回错误
+#[cfg(not(target_arch = "wasm32"))]
+pub fn from_file(
+    path: &std::path::Path,
+    app: &app_surface::AppSurface,
+    usage: wgpu::TextureUsages,
+) -> Result<AnyTexture, String> {
+    let img = image::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let max_size = app.device.limits().max_texture_dimension_2d;
+    if img.width() > max_size || img.height() > max_size {
+        return Err(format!(
+            "{}: image is larger than {max_size}x{max_size}",
+            path.display()
+        ));
+    }
+    Ok(create_from_img(img, app, usage, false))
+}
+
+fn create_from_img(
+    img: DynamicImage,
+    app: &app_surface::AppSurface,
+    usage: wgpu::TextureUsages,
+    set_to_grayscale: bool,
+) -> AnyTexture {
     let (texels, texture_extent, format) = load_from_img(img, set_to_grayscale);
     let pixel_bytes = single_pixel_bytes(format);
     let texture = app.device.create_texture(&wgpu::TextureDescriptor {
@@ -81,15 +111,13 @@ pub async fn from_path(
         },
         texture_extent,
     );
-    let any_tex = AnyTexture {
+    AnyTexture {
         size: texture_extent,
         tex: texture,
         tex_view: texture_view,
         view_dimension: wgpu::TextureViewDimension::D2,
         format,
-    };
-
-    (any_tex, default_sampler(&app.device))
+    }
 }
 
 fn load_from_img(
@@ -110,10 +138,8 @@ fn load_from_img(
     } else {
         match img.color() {
             image::ColorType::L8 => (TextureFormat::R8Unorm, img.into_bytes()),
-            image::ColorType::Rgb8 | image::ColorType::Rgba8 => {
-                (TextureFormat::Rgba8UnormSrgb, img.into_rgba8().into_raw())
-            }
-            _ => panic!("unsupported color type"),
+            // 16 位及浮点图片也转换为 8 位
+            _ => (TextureFormat::Rgba8UnormSrgb, img.into_rgba8().into_raw()),
         }
     };
 
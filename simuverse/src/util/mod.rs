@@ -15,9 +15,15 @@ pub use buffer::BufferObj;
 pub mod load_texture;
 pub use load_texture::AnyTexture;
 
+mod orbit_camera;
+pub use orbit_camera::OrbitCamera;
+
 pub mod shader;
 pub mod vertex;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod texture_readback;
+
 use bytemuck::{Pod, Zeroable};
 
 #[repr(C)]
@@ -0,0 +1,223 @@
+use winit::event::{ElementState, MouseButton, MouseScrollDelta};
+
+// 拖动一个像素旋转的弧度
+const ORBIT_SPEED: f32 = 0.008;
+const MAX_PITCH: f32 = 1.5;
+const MIN_ZOOM: f32 = 0.2;
+const MAX_ZOOM: f32 = 5.0;
+
+/// 绕模型空间原点旋转、平移及缩放的相机
+///
+/// 旋转使用 `orbit_button` 拖动，平移使用鼠标中键拖动，缩放使用滚轮
+pub struct OrbitCamera {
+    proj: glam::Mat4,
+    // 初始的模型视图矩阵，旋转、平移及缩放都以它为基准
+    base_mv: glam::Mat4,
+    viewport: glam::Vec2,
+    orbit_button: MouseButton,
+    yaw: f32,
+    pitch: f32,
+    // 相机到原点的距离与初始距离之比
+    zoom: f32,
+    // 视图空间中的平移
+    pan: glam::Vec2,
+    dragging: Option<MouseButton>,
+    cursor_pos: glam::Vec2,
+}
+
+impl OrbitCamera {
+    pub fn new(
+        proj: glam::Mat4,
+        base_mv: glam::Mat4,
+        viewport: glam::Vec2,
+        orbit_button: MouseButton,
+    ) -> Self {
+        Self {
+            proj,
+            base_mv,
+            viewport,
+            orbit_button,
+            yaw: 0.0,
+            pitch: 0.0,
+            zoom: 1.0,
+            pan: glam::Vec2::ZERO,
+            dragging: None,
+            cursor_pos: glam::Vec2::ZERO,
+        }
+    }
+
+    /// 视口变化后更新投影及初始矩阵，保留用户的旋转、平移及缩放
+    pub fn resize(&mut self, proj: glam::Mat4, base_mv: glam::Mat4, viewport: glam::Vec2) {
+        self.proj = proj;
+        self.base_mv = base_mv;
+        self.viewport = viewport;
+    }
+
+    /// 返回事件是否已被相机消费
+    pub fn mouse_input(&mut self, state: &ElementState, button: &MouseButton) -> bool {
+        if *button != self.orbit_button && *button != MouseButton::Middle {
+            return false;
+        }
+        match state {
+            ElementState::Pressed => {
+                self.dragging.get_or_insert(*button);
+            }
+            // 只有开始拖动的按键松开时才结束拖动
+            ElementState::Released => {
+                if self.dragging == Some(*button) {
+                    self.dragging = None;
+                }
+            }
+        }
+        true
+    }
+
+    /// 返回视角是否发生了变化
+    pub fn cursor_moved(&mut self, pos: glam::Vec2) -> bool {
+        let delta = pos - self.cursor_pos;
+        self.cursor_pos = pos;
+        match self.dragging {
+            Some(MouseButton::Middle) => {
+                // 在原点所在的深度上，平移量与光标的移动保持一致
+                let pixel_size = 2.0 * self.distance() / (self.proj.y_axis.y * self.viewport.y);
+                self.pan += glam::Vec2::new(delta.x, -delta.y) * pixel_size;
+                true
+            }
+            Some(_) => {
+                self.yaw += delta.x * ORBIT_SPEED;
+                self.pitch = (self.pitch + delta.y * ORBIT_SPEED).clamp(-MAX_PITCH, MAX_PITCH);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 返回视角是否发生了变化
+    pub fn mouse_wheel(&mut self, delta: &MouseScrollDelta) -> bool {
+        let y = match delta {
+            MouseScrollDelta::LineDelta(_, y) => *y,
+            MouseScrollDelta::PixelDelta(pos) => pos.y as f32 / 40.0,
+        };
+        if y == 0.0 {
+            return false;
+        }
+        self.zoom = (self.zoom * 0.9_f32.powf(y)).clamp(MIN_ZOOM, MAX_ZOOM);
+        true
+    }
+
+    pub fn view_matrix(&self) -> glam::Mat4 {
+        let mut translation = self.base_mv.w_axis.truncate();
+        translation.z = -self.base_distance() * self.zoom;
+        translation += self.pan.extend(0.0);
+        let mut rotation = self.base_mv;
+        rotation.w_axis = glam::Vec4::W;
+        glam::Mat4::from_translation(translation) * rotation * self.orbit_rotation()
+    }
+
+    /// model: 模型自身的变换，如自转
+    ///
+    /// mv_no_rotation 只包含环绕的旋转，不受模型变换、平移及缩放的影响
+    pub fn mvp_uniform(&self, model: glam::Mat4) -> crate::MVPMatUniform {
+        let mv = self.view_matrix() * model;
+        crate::MVPMatUniform {
+            mv: mv.to_cols_array_2d(),
+            proj: self.proj.to_cols_array_2d(),
+            mvp: (self.proj * mv).to_cols_array_2d(),
+            mv_no_rotation: (self.base_mv * self.orbit_rotation()).to_cols_array_2d(),
+            normal: mv.inverse().transpose().to_cols_array_2d(),
+            u_time: 0.0,
+            _padding: [0.0; 3],
+        }
+    }
+
+    fn orbit_rotation(&self) -> glam::Mat4 {
+        glam::Mat4::from_rotation_x(self.pitch) * glam::Mat4::from_rotation_y(self.yaw)
+    }
+
+    fn base_distance(&self) -> f32 {
+        -self.base_mv.w_axis.z
+    }
+
+    fn distance(&self) -> f32 {
+        self.base_distance() * self.zoom
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn camera() -> OrbitCamera {
+        let proj = glam::Mat4::perspective_rh(core::f32::consts::FRAC_PI_2, 1.0, 0.1, 100.0);
+        let base_mv = glam::Mat4::from_translation(glam::Vec3::new(0.0, 0.0, -4.0));
+        OrbitCamera::new(
+            proj,
+            base_mv,
+            glam::Vec2::new(800.0, 800.0),
+            MouseButton::Right,
+        )
+    }
+
+    #[test]
+    fn view_matrix() {
+        let mut camera = camera();
+        assert!(camera.view_matrix().abs_diff_eq(camera.base_mv, 1e-6));
+
+        // 向右拖动绕 y 轴旋转，原点到相机的距离不变
+        camera.mouse_input(&ElementState::Pressed, &MouseButton::Right);
+        camera.cursor_moved(glam::Vec2::new(100.0, 0.0));
+        let mv = camera.view_matrix();
+        let expected = camera.base_mv * glam::Mat4::from_rotation_y(100.0 * ORBIT_SPEED);
+        assert!(mv.abs_diff_eq(expected, 1e-6));
+        assert!((mv.transform_point3(glam::Vec3::ZERO).length() - 4.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn release_only_ends_own_drag() {
+        let mut camera = camera();
+        camera.mouse_input(&ElementState::Pressed, &MouseButton::Right);
+        assert!(camera.mouse_input(&ElementState::Released, &MouseButton::Middle));
+        assert!(camera.cursor_moved(glam::Vec2::new(10.0, 0.0)));
+        camera.mouse_input(&ElementState::Released, &MouseButton::Right);
+        assert!(!camera.cursor_moved(glam::Vec2::new(20.0, 0.0)));
+        // 其它按键不会被相机消费
+        assert!(!camera.mouse_input(&ElementState::Pressed, &MouseButton::Left));
+    }
+
+    #[test]
+    fn zoom_is_clamped() {
+        let mut camera = camera();
+        assert!(!camera.mouse_wheel(&MouseScrollDelta::LineDelta(0.0, 0.0)));
+        assert!(camera.mouse_wheel(&MouseScrollDelta::LineDelta(0.0, 1.0)));
+        assert!((camera.zoom - 0.9).abs() < 1e-6);
+        assert!((camera.distance() - 3.6).abs() < 1e-5);
+        for _ in 0..100 {
+            camera.mouse_wheel(&MouseScrollDelta::LineDelta(0.0, 1.0));
+        }
+        assert_eq!(camera.zoom, MIN_ZOOM);
+        for _ in 0..100 {
+            camera.mouse_wheel(&MouseScrollDelta::LineDelta(0.0, -1.0));
+        }
+        assert_eq!(camera.zoom, MAX_ZOOM);
+    }
+
+    #[test]
+    fn pan_follows_cursor() {
+        let mut camera = camera();
+        camera.cursor_moved(glam::Vec2::new(400.0, 400.0));
+        camera.mouse_input(&ElementState::Pressed, &MouseButton::Middle);
+        camera.cursor_moved(glam::Vec2::new(500.0, 350.0));
+        // 原点投影到屏幕上的位置与光标移动相同的像素数
+        let to_pixel = |camera: &OrbitCamera| {
+            let ndc = (camera.proj * camera.view_matrix()).project_point3(glam::Vec3::ZERO);
+            glam::Vec2::new(ndc.x + 1.0, 1.0 - ndc.y) * 0.5 * camera.viewport
+        };
+        assert!(to_pixel(&camera).abs_diff_eq(glam::Vec2::new(500.0, 350.0), 1e-3));
+
+        // 拉远后同样的拖动对应更大的平移量
+        let pan = camera.pan;
+        camera.zoom = 2.0;
+        camera.cursor_moved(glam::Vec2::new(600.0, 350.0));
+        assert!(((camera.pan - pan).x - 2.0 * pan.x).abs() < 1e-5);
+    }
+}
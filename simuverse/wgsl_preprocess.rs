@@ -20,13 +20,45 @@ pub fn preprocess_wgsl() -> Result<(), Box<dyn Error>> {
         "trajectory_update",
         "present",
         "field_setting",
+        "volume",
+        "noise/2d_noise_tex",
         "noise/3d_noise_tex",
         "noise/sphere_tex",
+        "noise/terrain",
         "pbd/cloth_display",
         "pbd/cloth_external_force",
+        "pbd/cloth_hash_clear",
+        "pbd/cloth_hash_insert",
+        "pbd/cloth_normal",
+        "pbd/cloth_pick",
+        "pbd/cloth_pick_resolve",
+        "pbd/cloth_wind",
+        "pbd/cloth_wireframe",
+        "pbd/collider_display",
+        "pbd/fluid/pbf_apply",
+        "pbd/fluid/pbf_confine",
+        "pbd/fluid/pbf_delta",
+        "pbd/fluid/pbf_hash_count",
+        "pbd/fluid/pbf_hash_scatter",
+        "pbd/fluid/pbf_lambda",
+        "pbd/fluid/pbf_predict",
+        "pbd/fluid/pbf_prefix_sum",
+        "pbd/fluid/pbf_velocity",
+        "pbd/fluid/pbf_vorticity",
+        "pbd/pbf_display",
+        "pbd/soft_body_display",
+        "pbd/strand_display",
+        "pbd/xxpbd/cloth_attach_solver",
         "pbd/xxpbd/cloth_bending_solver",
+        "pbd/xxpbd/cloth_collision_solver",
         "pbd/xxpbd/cloth_predict",
+        "pbd/xxpbd/cloth_self_collision_solver",
         "pbd/xxpbd/cloth_stretch_solver",
+        "pbd/xxpbd/collision_solver",
+        "pbd/xxpbd/distance_solver",
+        "pbd/xxpbd/soft_body_volume_reset",
+        "pbd/xxpbd/soft_body_volume_solver",
+        "pbd/xxpbd/strand_twist_solver",
     ];
 
     // 创建目录